@@ -3,7 +3,8 @@ use cpal::{
     SampleFormat, SampleRate, SizedSample, StreamConfig, StreamError, SupportedStreamConfigRange,
 };
 use phonic_signal::{
-    utils::slice_as_uninit_mut, PhonicError, Sample, Signal, SignalReader, SignalSpec, SignalWriter,
+    utils::slice_as_uninit_mut, ChannelLayout, PhonicError, Sample, Signal, SignalReader,
+    SignalSpec, SignalWriter,
 };
 use std::{
     any::TypeId,
@@ -18,9 +19,17 @@ pub trait SignalSpecExt {
 
 impl SignalSpecExt for SignalSpec {
     fn from_cpal_config(config: StreamConfig) -> SignalSpec {
+        // the order of surround channels depends on the host so only mono and stereo are assumed
+        let layout = match config.channels {
+            1 => Some(ChannelLayout::MONO),
+            2 => Some(ChannelLayout::STEREO),
+            _ => None,
+        };
+
         SignalSpec {
             sample_rate: config.sample_rate.0 as usize,
             n_channels: config.channels as usize,
+            layout,
         }
     }
 
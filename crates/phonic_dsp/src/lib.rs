@@ -1,3 +1,4 @@
 pub mod ops;
+pub mod spectral;
 pub mod utils;
 pub mod types;
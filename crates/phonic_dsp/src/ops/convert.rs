@@ -2,7 +2,7 @@ use crate::ops::ClipSample;
 use phonic_signal::{
     delegate_signal,
     utils::{slice_as_init, DefaultSizedBuf},
-    PhonicResult, Sample, Signal, SignalExt, SignalReader, SignalSpec, SignalWriter,
+    PhonicResult, Sample, Signal, SignalExt, SignalReader, SignalSpec, SignalWriter, I24, U24,
 };
 use std::{marker::PhantomData, mem::MaybeUninit};
 
@@ -131,12 +131,14 @@ impl_convert!(
     // signed
     i8 => s,
     i16 => (s as i16) << 8,
+    I24 => i32::from_sample(s).into_sample(),
     i32 => (s as i32) << 24,
     i64 => (s as i64) << 56,
 
     // unsigned
     u8 => (s as u8).wrapping_add(const { 1 << 7 }),
     u16 => u8::from_sample(s).into_sample(),
+    U24 => u8::from_sample(s).into_sample(),
     u32 => u8::from_sample(s).into_sample(),
     u64 => u8::from_sample(s).into_sample(),
 
@@ -151,12 +153,14 @@ impl_convert!(
     // signed
     i8 => (s >> 8) as i8,
     i16 => s,
+    I24 => i32::from_sample(s).into_sample(),
     i32 => (s as i32) << 16,
     i64 => (s as i64) << 48,
 
     // unsigned
     u8 => u16::from_sample(s).into_sample(),
     u16 => (s as u16).wrapping_add(const { 1 << 15 }),
+    U24 => u16::from_sample(s).into_sample(),
     u32 => u16::from_sample(s).into_sample(),
     u64 => u16::from_sample(s).into_sample(),
 
@@ -165,18 +169,42 @@ impl_convert!(
     f64 => s as f64 / const { i16::MAX as f64 + 1.0 }
 );
 
+impl_convert!(
+    I24 as s,
+
+    // signed
+    i8 => (s.get() >> 16) as i8,
+    i16 => (s.get() >> 8) as i16,
+    I24 => s,
+    i32 => s.get() << 8,
+    i64 => (s.get() as i64) << 40,
+
+    // unsigned
+    u8 => i32::from_sample(s).into_sample(),
+    u16 => i32::from_sample(s).into_sample(),
+    U24 => U24::new((s.get() as u32).wrapping_add(const { 1 << 23 })),
+    u32 => i32::from_sample(s).into_sample(),
+    u64 => i32::from_sample(s).into_sample(),
+
+    // float
+    f32 => s.get() as f32 / const { (1 << 23) as f32 },
+    f64 => s.get() as f64 / const { (1 << 23) as f64 }
+);
+
 impl_convert!(
     i32 as s,
 
     // signed
     i8 => (s >> 24) as i8,
     i16 => (s >> 16) as i16,
+    I24 => I24::new(s >> 8),
     i32 => s,
     i64 => (s as i64) << 32,
 
     // unsigned
     u8 => u32::from_sample(s).into_sample(),
     u16 => u32::from_sample(s).into_sample(),
+    U24 => u32::from_sample(s).into_sample(),
     u32 => (s as u32).wrapping_add(const { 1 << 31 }),
     u64 => u32::from_sample(s).into_sample(),
 
@@ -191,12 +219,14 @@ impl_convert!(
     // signed
     i8 => (s >> 56) as i8,
     i16 => (s >> 48) as i16,
+    I24 => i32::from_sample(s).into_sample(),
     i32 => (s >> 32) as i32,
     i64 => s,
 
     // unsigned
     u8 => u64::from_sample(s).into_sample(),
     u16 => u64::from_sample(s).into_sample(),
+    U24 => u64::from_sample(s).into_sample(),
     u32 => u64::from_sample(s).into_sample(),
     u64 => (s as u64).wrapping_add(const { 1 << 63 }),
 
@@ -211,12 +241,14 @@ impl_convert!(
     // signed
     i8 => s.wrapping_sub(const { 1 << 7 }) as i8,
     i16 => i8::from_sample(s).into_sample(),
+    I24 => i8::from_sample(s).into_sample(),
     i32 => i8::from_sample(s).into_sample(),
     i64 => i8::from_sample(s).into_sample(),
 
     // unsigned
     u8 => s,
     u16 => (s as u16) << 8,
+    U24 => u32::from_sample(s).into_sample(),
     u32 => (s as u32) << 24,
     u64 => (s as u64) << 56,
 
@@ -231,12 +263,14 @@ impl_convert!(
     // signed
     i8 => i16::from_sample(s).into_sample(),
     i16 => s.wrapping_sub(const { 1 << 15 }) as i16,
+    I24 => i16::from_sample(s).into_sample(),
     i32 => i16::from_sample(s).into_sample(),
     i64 => i16::from_sample(s).into_sample(),
 
     // unsigned
     u8 => (s >> 8) as u8,
     u16 => s,
+    U24 => u32::from_sample(s).into_sample(),
     u32 => (s as u32) << 16,
     u64 => (s as u64) << 48,
 
@@ -245,18 +279,42 @@ impl_convert!(
     f64 => ((s as f64) / const { u16::ORIGIN as f64 }) - 1.0
 );
 
+impl_convert!(
+    U24 as s,
+
+    // signed
+    i8 => u32::from_sample(s).into_sample(),
+    i16 => u32::from_sample(s).into_sample(),
+    I24 => I24::new(s.get().wrapping_sub(const { 1 << 23 }) as i32),
+    i32 => u32::from_sample(s).into_sample(),
+    i64 => u32::from_sample(s).into_sample(),
+
+    // unsigned
+    u8 => (s.get() >> 16) as u8,
+    u16 => (s.get() >> 8) as u16,
+    U24 => s,
+    u32 => s.get() << 8,
+    u64 => (s.get() as u64) << 40,
+
+    // float
+    f32 => ((s.get() as f32) / const { (1 << 23) as f32 }) - 1.0,
+    f64 => ((s.get() as f64) / const { (1 << 23) as f64 }) - 1.0
+);
+
 impl_convert!(
     u32 as s,
 
     // signed
     i8 => i32::from_sample(s).into_sample(),
     i16 => i32::from_sample(s).into_sample(),
+    I24 => i32::from_sample(s).into_sample(),
     i32 => s.wrapping_sub(const { 1 << 31 }) as i32,
     i64 => i32::from_sample(s).into_sample(),
 
     // unsigned
     u8 => (s >> 24) as u8,
     u16 => (s >> 16) as u16,
+    U24 => U24::new(s >> 8),
     u32 => s,
     u64 => (s as u64) << 32,
 
@@ -271,12 +329,14 @@ impl_convert!(
     // signed
     i8 => i64::from_sample(s).into_sample(),
     i16 => i64::from_sample(s).into_sample(),
+    I24 => i64::from_sample(s).into_sample(),
     i32 => i64::from_sample(s).into_sample(),
     i64 => s.wrapping_sub(const { 1 << 63 }) as i64,
 
     // unsigned
     u8 => (s >> 56) as u8,
     u16 => (s >> 48) as u16,
+    U24 => u32::from_sample(s).into_sample(),
     u32 => (s >> 32) as u32,
     u64 => s,
 
@@ -291,12 +351,14 @@ impl_convert!(
     // signed
     i8 => (s.clip() * const { i8::MAX as f32 + 1.0 }) as i8,
     i16 => (s.clip() * const { i16::MAX as f32 + 1.0 }) as i16,
+    I24 => i32::from_sample(s).into_sample(),
     i32 => f64::from_sample(s).into_sample(),
     i64 => f64::from_sample(s).into_sample(),
 
     // unsigned
     u8 => ((s.clip() + 1.0) * const { u8::ORIGIN as f32 }) as u8,
     u16 => ((s.clip() + 1.0) * const { u16::ORIGIN  as f32 }) as u16,
+    U24 => u32::from_sample(s).into_sample(),
     u32 => f64::from_sample(s).into_sample(),
     u64 => f64::from_sample(s).into_sample(),
 
@@ -311,12 +373,14 @@ impl_convert!(
     // signed
     i8 => (s.clip() * const { i8::MAX as f64 + 1.0 }) as i8,
     i16 => (s.clip() * const { i16::MAX as f64 + 1.0 }) as i16,
+    I24 => i32::from_sample(s).into_sample(),
     i32 => (s.clip() * const { i32::MAX as f64 + 1.0 }) as i32,
     i64 => (s.clip() * const { i64::MAX as f64 + 1.0 }) as i64,
 
     // unsigned
     u8 => ((s.clip() + 1.0) * const { u8::ORIGIN as f64 }) as u8,
     u16 => ((s.clip() + 1.0) * const { u16::ORIGIN as f64 }) as u16,
+    U24 => u32::from_sample(s).into_sample(),
     u32 => ((s.clip() + 1.0) * const { u32::ORIGIN as f64 }) as u32,
     u64 => ((s.clip() + 1.0) * const { u64::ORIGIN as f64 }) as u64,
 
@@ -394,4 +458,22 @@ mod tests {
 
     impl_test!(from_f32, f32);
     impl_test!(from_f64, f64);
+
+    #[test]
+    fn packed_24_bit_conversions() {
+        use crate::ops::{FromSample, IntoSample};
+        use phonic_signal::{Sample, I24, U24};
+
+        assert_eq!(I24::from_sample(0x12345678i32), I24::new(0x123456));
+        assert_eq!(i32::from_sample(I24::new(0x123456)), 0x12345600);
+        assert_eq!(I24::from_sample(i16::MIN), I24::MIN);
+        assert_eq!(U24::from_sample(I24::ORIGIN), U24::ORIGIN);
+        assert_eq!(I24::from_sample(U24::MAX), I24::MAX);
+        assert_eq!(u8::from_sample(U24::MAX), u8::MAX);
+
+        assert_eq!(IntoSample::<f64>::into_sample(I24::MIN), -1.0);
+        assert_eq!(IntoSample::<f32>::into_sample(U24::ORIGIN), 0.0);
+        assert_eq!(I24::from_sample(1.0f32), I24::MAX);
+        assert_eq!(I24::from_sample(-1.0f64), I24::MIN);
+    }
 }
@@ -5,17 +5,19 @@ use phonic_io::{
 };
 use phonic_signal::{
     utils::{DefaultSizedBuf, SizedBuf},
-    Sample,
+    Sample, I24, U24,
 };
 
 pub trait FromKnownSample:
     Sample
     + FromSample<i8>
     + FromSample<i16>
+    + FromSample<I24>
     + FromSample<i32>
     + FromSample<i64>
     + FromSample<u8>
     + FromSample<u16>
+    + FromSample<U24>
     + FromSample<u32>
     + FromSample<u64>
     + FromSample<f32>
@@ -27,10 +29,12 @@ pub trait IntoKnownSample:
     Sample
     + IntoSample<i8>
     + IntoSample<i16>
+    + IntoSample<I24>
     + IntoSample<i32>
     + IntoSample<i64>
     + IntoSample<u8>
     + IntoSample<u16>
+    + IntoSample<U24>
     + IntoSample<u32>
     + IntoSample<u64>
     + IntoSample<f32>
@@ -42,10 +46,12 @@ impl<S> FromKnownSample for S where
     S: Sample
         + FromSample<i8>
         + FromSample<i16>
+        + FromSample<I24>
         + FromSample<i32>
         + FromSample<i64>
         + FromSample<u8>
         + FromSample<u16>
+        + FromSample<U24>
         + FromSample<u32>
         + FromSample<u64>
         + FromSample<f32>
@@ -57,10 +63,12 @@ impl<S> IntoKnownSample for S where
     S: Sample
         + IntoSample<i8>
         + IntoSample<i16>
+        + IntoSample<I24>
         + IntoSample<i32>
         + IntoSample<i64>
         + IntoSample<u8>
         + IntoSample<u16>
+        + IntoSample<U24>
         + IntoSample<u32>
         + IntoSample<u64>
         + IntoSample<f32>
@@ -0,0 +1,678 @@
+use crate::ops::{DbRatio, IntoSample, TruePeakDetector};
+use phonic_signal::{
+    delegate_signal,
+    utils::{slice_as_init, IntoDuration, NFrames},
+    IndexedSignal, PhonicResult, Signal, SignalExt, SignalReader, SignalSeeker, SignalSpec,
+};
+use std::{collections::VecDeque, mem::MaybeUninit, time::Duration};
+
+/// The lowest gain in dB applied by a dynamics processor
+const MIN_GAIN_DB: f64 = -144.0;
+
+/// Determines whether the level of a frame is detected across all channels, applying the same
+/// gain to each channel, or detected and applied to each channel independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Detection {
+    #[default]
+    Linked,
+    PerChannel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DynamicsCurve {
+    /// Reduces the level above the threshold by the ratio
+    Compressor,
+
+    /// Reduces the level below the threshold by the ratio
+    Expander,
+
+    /// Silences everything below the threshold
+    Gate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DynamicsParams {
+    pub curve: DynamicsCurve,
+    pub threshold_db: f64,
+    pub ratio: f64,
+    pub knee_db: f64,
+    pub attack: Duration,
+    pub release: Duration,
+    pub makeup_db: f64,
+    pub detection: Detection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimiterParams {
+    pub ceiling_db: f64,
+    pub lookahead: Duration,
+    pub release: Duration,
+    pub detection: Detection,
+}
+
+/// A compressor, expander or gate with a soft knee and attack and release smoothing of the gain
+pub struct Dynamics<T> {
+    inner: T,
+    params: DynamicsParams,
+    attack_coeff: f64,
+    release_coeff: f64,
+    makeup: f64,
+    level: Box<[f64]>,
+    envelope: Box<[f64]>,
+}
+
+/// A lookahead true peak limiter. The signal is delayed by the lookahead so the gain can be
+/// reduced smoothly before a peak arrives. The delay is compensated by reading ahead of the
+/// output, which is reflected by `IndexedSignal::pos` trailing the position of the inner signal
+/// by `latency` frames.
+pub struct Limiter<T> {
+    inner: T,
+    params: LimiterParams,
+    ceiling: f64,
+    release_coeff: f64,
+
+    lookahead: usize,
+    latency: usize,
+    detector: TruePeakDetector,
+    smoothers: Box<[GainSmoother]>,
+    gains: Box<[f64]>,
+    frame: Box<[f64]>,
+
+    delay: VecDeque<f64>,
+    n_pending: usize,
+    n_arrived: u64,
+    exhausted: bool,
+}
+
+/// Turns the gain required by each frame into a gain that reaches it smoothly by the time the
+/// frame leaves the lookahead delay and releases afterward.
+struct GainSmoother {
+    hold: VecDeque<(u64, f64)>,
+    average: VecDeque<f64>,
+    sum: f64,
+    envelope: f64,
+}
+
+#[inline]
+fn amplitude_to_db(amplitude: f64) -> f64 {
+    (20.0 * amplitude.log10()).max(MIN_GAIN_DB)
+}
+
+#[inline]
+fn smoothing_coeff(duration: Duration, spec: &SignalSpec) -> f64 {
+    let NFrames { n_frames } = duration.into_duration(spec);
+    if n_frames == 0 {
+        0.0
+    } else {
+        (-1.0 / n_frames as f64).exp()
+    }
+}
+
+impl DynamicsParams {
+    fn new(curve: DynamicsCurve, threshold_db: f64, ratio: f64) -> Self {
+        Self {
+            curve,
+            threshold_db,
+            ratio,
+            knee_db: 0.0,
+            attack: Duration::from_millis(10),
+            release: Duration::from_millis(100),
+            makeup_db: 0.0,
+            detection: Detection::default(),
+        }
+    }
+
+    pub fn compressor(threshold_db: f64, ratio: f64) -> Self {
+        Self::new(DynamicsCurve::Compressor, threshold_db, ratio)
+    }
+
+    pub fn expander(threshold_db: f64, ratio: f64) -> Self {
+        Self::new(DynamicsCurve::Expander, threshold_db, ratio)
+    }
+
+    pub fn gate(threshold_db: f64) -> Self {
+        Self::new(DynamicsCurve::Gate, threshold_db, f64::INFINITY)
+    }
+
+    pub fn knee(mut self, knee_db: f64) -> Self {
+        self.knee_db = knee_db;
+        self
+    }
+
+    pub fn attack(mut self, attack: Duration) -> Self {
+        self.attack = attack;
+        self
+    }
+
+    pub fn release(mut self, release: Duration) -> Self {
+        self.release = release;
+        self
+    }
+
+    pub fn makeup(mut self, makeup_db: f64) -> Self {
+        self.makeup_db = makeup_db;
+        self
+    }
+
+    pub fn detection(mut self, detection: Detection) -> Self {
+        self.detection = detection;
+        self
+    }
+
+    /// The static gain in dB applied to a signal at the given level in dB, without makeup gain
+    pub fn gain_db(&self, level_db: f64) -> f64 {
+        let threshold = self.threshold_db;
+        let half_knee = self.knee_db / 2.0;
+
+        let output_db = match self.curve {
+            DynamicsCurve::Compressor if level_db <= threshold - half_knee => level_db,
+            DynamicsCurve::Compressor if level_db < threshold + half_knee => {
+                let x = level_db - threshold + half_knee;
+                level_db + (1.0 / self.ratio - 1.0) * x * x / (2.0 * self.knee_db)
+            }
+            DynamicsCurve::Compressor => threshold + (level_db - threshold) / self.ratio,
+
+            DynamicsCurve::Expander if level_db >= threshold + half_knee => level_db,
+            DynamicsCurve::Expander if level_db > threshold - half_knee => {
+                let x = level_db - threshold - half_knee;
+                level_db + (1.0 - self.ratio) * x * x / (2.0 * self.knee_db)
+            }
+            DynamicsCurve::Expander => threshold + (level_db - threshold) * self.ratio,
+
+            DynamicsCurve::Gate if level_db >= threshold => level_db,
+            DynamicsCurve::Gate => return MIN_GAIN_DB,
+        };
+
+        (output_db - level_db).clamp(MIN_GAIN_DB, 0.0)
+    }
+}
+
+impl Default for LimiterParams {
+    fn default() -> Self {
+        Self {
+            ceiling_db: -1.0,
+            lookahead: Duration::from_millis(5),
+            release: Duration::from_millis(50),
+            detection: Detection::default(),
+        }
+    }
+}
+
+impl LimiterParams {
+    pub fn new(ceiling_db: f64) -> Self {
+        Self {
+            ceiling_db,
+            ..Self::default()
+        }
+    }
+
+    pub fn lookahead(mut self, lookahead: Duration) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    pub fn release(mut self, release: Duration) -> Self {
+        self.release = release;
+        self
+    }
+
+    pub fn detection(mut self, detection: Detection) -> Self {
+        self.detection = detection;
+        self
+    }
+}
+
+impl<T: Signal> Dynamics<T> {
+    pub fn new(inner: T, params: DynamicsParams) -> Self {
+        let spec = inner.spec();
+        let n_detectors = match params.detection {
+            Detection::Linked => 1,
+            Detection::PerChannel => spec.n_channels,
+        };
+
+        Self {
+            attack_coeff: smoothing_coeff(params.attack, spec),
+            release_coeff: smoothing_coeff(params.release, spec),
+            makeup: params.makeup_db.db_ratio(),
+            level: vec![0.0; n_detectors].into(),
+            envelope: vec![0.0; n_detectors].into(),
+            params,
+            inner,
+        }
+    }
+}
+
+impl<T> Dynamics<T> {
+    pub fn params(&self) -> &DynamicsParams {
+        &self.params
+    }
+
+    pub fn reset(&mut self) {
+        self.level.fill(0.0);
+        self.envelope.fill(0.0);
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    #[inline]
+    fn gain(&mut self, detector: usize, peak: f64) -> f64 {
+        // the level follows peaks instantly and decays with the release so the gain doesn't
+        // follow the waveform between peaks
+        let level = &mut self.level[detector];
+        *level = peak.max(*level * self.release_coeff);
+
+        let target = self.params.gain_db(amplitude_to_db(*level));
+        let envelope = &mut self.envelope[detector];
+        let coeff = if target < *envelope {
+            self.attack_coeff
+        } else {
+            self.release_coeff
+        };
+
+        *envelope = target + (*envelope - target) * coeff;
+        envelope.db_ratio() * self.makeup
+    }
+}
+
+impl GainSmoother {
+    fn new(lookahead: usize) -> Self {
+        Self {
+            hold: VecDeque::new(),
+            average: VecDeque::from(vec![1.0; lookahead]),
+            sum: lookahead as f64,
+            envelope: 1.0,
+        }
+    }
+
+    #[inline]
+    fn process(&mut self, idx: u64, gain: f64, hold_len: u64, release_coeff: f64) -> f64 {
+        while self.hold.back().is_some_and(|(_, g)| *g >= gain) {
+            self.hold.pop_back();
+        }
+
+        self.hold.push_back((idx, gain));
+        while self.hold.front().is_some_and(|(i, _)| i + hold_len <= idx) {
+            self.hold.pop_front();
+        }
+
+        let held = self.hold.front().map_or(gain, |(_, g)| *g);
+        self.average.push_back(held);
+        self.sum += held;
+        if let Some(expired) = self.average.pop_front() {
+            self.sum -= expired;
+        }
+
+        let smoothed = self.sum / self.average.len() as f64;
+
+        self.envelope = if smoothed < self.envelope {
+            smoothed
+        } else {
+            smoothed + (self.envelope - smoothed) * release_coeff
+        };
+
+        self.envelope
+    }
+}
+
+impl<T: Signal> Limiter<T> {
+    pub fn new(inner: T, params: LimiterParams) -> Self {
+        let spec = inner.spec();
+        let n_channels = spec.n_channels;
+        let NFrames { n_frames } = params.lookahead.into_duration(spec);
+        let lookahead = (n_frames as usize).max(1);
+
+        let n_detectors = match params.detection {
+            Detection::Linked => 1,
+            Detection::PerChannel => n_channels,
+        };
+
+        Self {
+            ceiling: params.ceiling_db.db_ratio(),
+            release_coeff: smoothing_coeff(params.release, spec),
+
+            lookahead,
+            latency: lookahead + TruePeakDetector::DELAY,
+            detector: TruePeakDetector::new(n_channels),
+            smoothers: (0..n_detectors)
+                .map(|_| GainSmoother::new(lookahead))
+                .collect(),
+            gains: vec![1.0; n_detectors].into(),
+            frame: vec![0.0; n_channels].into(),
+
+            delay: VecDeque::new(),
+            n_pending: 0,
+            n_arrived: 0,
+            exhausted: false,
+
+            params,
+            inner,
+        }
+    }
+}
+
+impl<T> Limiter<T> {
+    pub fn params(&self) -> &LimiterParams {
+        &self.params
+    }
+
+    /// The number of frames the output is delayed by relative to the input
+    pub fn latency(&self) -> usize {
+        self.latency
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn reset(&mut self) {
+        self.detector.reset();
+        self.smoothers
+            .iter_mut()
+            .for_each(|smoother| *smoother = GainSmoother::new(self.lookahead));
+
+        self.gains.fill(1.0);
+        self.delay.clear();
+        self.n_pending = 0;
+        self.n_arrived = 0;
+        self.exhausted = false;
+    }
+
+    /// Pushes `self.frame` into the lookahead delay and updates the gains of the frame leaving
+    /// it. Returns false while the delay is still filling.
+    fn process_frame(&mut self) -> bool {
+        let hold_len = (self.latency + 1) as u64;
+        let idx = self.n_arrived;
+        self.n_arrived += 1;
+
+        let mut linked_peak: f64 = 0.0;
+        for (channel, &x) in self.frame.iter().enumerate() {
+            self.delay.push_back(x);
+
+            let peak = self.detector.process(channel, x);
+            match self.params.detection {
+                Detection::Linked => linked_peak = linked_peak.max(peak),
+                Detection::PerChannel => {
+                    let required = (self.ceiling / peak).min(1.0);
+                    self.gains[channel] = self.smoothers[channel].process(
+                        idx,
+                        required,
+                        hold_len,
+                        self.release_coeff,
+                    );
+                }
+            }
+        }
+
+        if self.params.detection == Detection::Linked {
+            let required = (self.ceiling / linked_peak).min(1.0);
+            self.gains[0] = self.smoothers[0].process(idx, required, hold_len, self.release_coeff);
+        }
+
+        self.delay.len() > self.latency * self.frame.len()
+    }
+}
+
+delegate_signal! {
+    impl<T> * + !Read + !Write + !SignalSeeker for Dynamics<T> {
+        Self as T;
+
+        &self => &self.inner;
+        &mut self => &mut self.inner;
+    }
+}
+
+delegate_signal! {
+    impl<T> * + !IndexedSignal + !Read + !Write + !SignalSeeker for Limiter<T> {
+        Self as T;
+
+        &self => &self.inner;
+        &mut self => &mut self.inner;
+    }
+}
+
+impl<T> SignalReader for Dynamics<T>
+where
+    T: SignalReader,
+    T::Sample: IntoSample<f64>,
+    f64: IntoSample<T::Sample>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let samples = self.inner.read_init(buf)?;
+        let n_channels = self.inner.spec().n_channels;
+
+        for frame in samples.chunks_exact_mut(n_channels) {
+            match self.params.detection {
+                Detection::Linked => {
+                    let peak = frame
+                        .iter()
+                        .map(|s| IntoSample::<f64>::into_sample(*s).abs())
+                        .fold(0.0, f64::max);
+
+                    let gain = self.gain(0, peak);
+                    for s in frame.iter_mut() {
+                        let x: f64 = (*s).into_sample();
+                        *s = (x * gain).into_sample();
+                    }
+                }
+                Detection::PerChannel => {
+                    for (channel, s) in frame.iter_mut().enumerate() {
+                        let x: f64 = (*s).into_sample();
+                        let gain = self.gain(channel, x.abs());
+                        *s = (x * gain).into_sample();
+                    }
+                }
+            }
+        }
+
+        Ok(samples.len())
+    }
+}
+
+impl<T> SignalReader for Limiter<T>
+where
+    T: SignalReader,
+    T::Sample: IntoSample<f64>,
+    f64: IntoSample<T::Sample>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let n_channels = self.inner.spec().n_channels;
+        let buf_len = buf.len() - buf.len() % n_channels;
+        let mut n_output = 0;
+
+        while n_output == 0 && buf_len > 0 {
+            let n_input = if self.exhausted {
+                0
+            } else {
+                match self.inner.read_init(&mut buf[..buf_len]) {
+                    Ok([]) => {
+                        self.exhausted = true;
+                        0
+                    }
+                    Ok(samples) => samples.len(),
+                    Err(e) => return Err(e),
+                }
+            };
+
+            if self.exhausted && self.n_pending == 0 {
+                return Ok(0);
+            }
+
+            // once the inner signal is exhausted the pending frames are pushed out with silence
+            let n_frames = if self.exhausted {
+                self.n_pending.min(buf_len / n_channels)
+            } else {
+                n_input / n_channels
+            };
+
+            for i in 0..n_frames {
+                if self.exhausted {
+                    self.frame.fill(0.0);
+                } else {
+                    let frame = &buf[i * n_channels..(i + 1) * n_channels];
+                    let frame = unsafe { slice_as_init(frame) };
+                    for (x, s) in self.frame.iter_mut().zip(frame) {
+                        *x = (*s).into_sample();
+                    }
+
+                    self.n_pending += 1;
+                }
+
+                if !self.process_frame() {
+                    continue;
+                }
+
+                self.n_pending -= 1;
+
+                for channel in 0..n_channels {
+                    let x = self.delay.pop_front().unwrap_or_default();
+                    let gain = match self.params.detection {
+                        Detection::Linked => self.gains[0],
+                        Detection::PerChannel => self.gains[channel],
+                    };
+
+                    buf[n_output].write((x * gain).into_sample());
+                    n_output += 1;
+                }
+            }
+        }
+
+        Ok(n_output)
+    }
+}
+
+impl<T: IndexedSignal + Signal> IndexedSignal for Limiter<T> {
+    fn pos(&self) -> u64 {
+        self.inner.pos() - self.n_pending as u64
+    }
+}
+
+impl<T: SignalSeeker> SignalSeeker for Dynamics<T> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        self.inner.seek(offset)?;
+        self.reset();
+
+        Ok(())
+    }
+}
+
+impl<T: SignalSeeker> SignalSeeker for Limiter<T> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        self.inner.seek(offset - self.n_pending as i64)?;
+        self.reset();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ops::{Detection, DspOpsExt, DynamicsParams, LimiterParams};
+    use phonic_signal::{utils::Cursor, IndexedSignal, SignalReader, SignalSeeker, SignalSpec};
+    use std::{f64::consts::TAU, mem::MaybeUninit, time::Duration};
+
+    const SAMPLE_RATE: usize = 48000;
+
+    /// A 1 kHz sine with the given peak level of every channel in dBFS
+    fn sine(seconds: f64, levels: &[f64]) -> Cursor<Vec<f64>, f64> {
+        let n_frames = (seconds * SAMPLE_RATE as f64) as usize;
+        let mut buf = Vec::with_capacity(n_frames * levels.len());
+        for i in 0..n_frames {
+            let s = (i as f64 / SAMPLE_RATE as f64 * 1000.0 * TAU).sin();
+            buf.extend(levels.iter().map(|db| s * 10f64.powf(db / 20.0)));
+        }
+
+        Cursor::new(SignalSpec::new(levels.len(), SAMPLE_RATE), buf)
+    }
+
+    fn read_all<T: SignalReader<Sample = f64>>(signal: &mut T) -> Vec<f64> {
+        let mut out = Vec::new();
+        let mut buf = [MaybeUninit::uninit(); 1002];
+        loop {
+            let n = signal.read(&mut buf).unwrap();
+            if n == 0 {
+                return out;
+            }
+
+            out.extend(buf[..n].iter().map(|s| unsafe { s.assume_init() }));
+        }
+    }
+
+    fn peak_db(samples: impl Iterator<Item = f64>) -> f64 {
+        20.0 * samples.map(f64::abs).fold(0.0, f64::max).log10()
+    }
+
+    #[test]
+    fn compressor_steady_state() {
+        let params = DynamicsParams::compressor(-20.0, 4.0)
+            .attack(Duration::from_millis(1))
+            .release(Duration::from_millis(200));
+
+        let out = read_all(&mut sine(1.0, &[-6.0, -6.0]).dynamics(params));
+        let tail = out[out.len() - 9600..].iter().copied();
+        assert!((peak_db(tail) - -16.5).abs() < 0.5);
+
+        let params = DynamicsParams::compressor(-20.0, 4.0).knee(6.0).makeup(3.0);
+        assert_eq!(params.gain_db(-30.0), 0.0);
+        assert!((params.gain_db(0.0) - -15.0).abs() < 1e-9);
+        assert!(params.gain_db(-20.0) < 0.0 && params.gain_db(-20.0) > -1.0);
+    }
+
+    #[test]
+    fn gate_silences_quiet_channel() {
+        let signal = sine(0.5, &[-6.0, -60.0]);
+        let params = DynamicsParams::gate(-40.0).detection(Detection::PerChannel);
+        let out = read_all(&mut signal.dynamics(params));
+
+        let tail = &out[out.len() - 4800..];
+        assert!(peak_db(tail.iter().step_by(2).copied()) > -6.1);
+        assert!(peak_db(tail.iter().skip(1).step_by(2).copied()) < -140.0);
+    }
+
+    #[test]
+    fn limiter_holds_ceiling() {
+        let expected = read_all(&mut sine(0.5, &[6.0, -12.0]));
+        let mut limiter = sine(0.5, &[6.0, -12.0]).limiter(-1.0);
+        let out = read_all(&mut limiter);
+
+        assert_eq!(out.len(), expected.len());
+        assert!(peak_db(out.iter().copied()) <= -1.0 + 1e-9);
+        assert_eq!(limiter.pos(), limiter.as_inner().pos());
+
+        // linked detection reduces the quiet channel by the same amount as the loud one
+        let ratio = out[4825] / expected[4825];
+        assert!((20.0 * ratio.log10() - -7.0).abs() < 0.5);
+
+        let params = LimiterParams::new(-1.0).detection(Detection::PerChannel);
+        let out = read_all(&mut sine(0.5, &[6.0, -12.0]).limiter_params(params));
+        assert!(peak_db(out.iter().step_by(2).copied()) <= -1.0 + 1e-9);
+        assert!(out[1..]
+            .iter()
+            .step_by(2)
+            .eq(expected[1..].iter().step_by(2)));
+    }
+
+    #[test]
+    fn limiter_latency() {
+        let mut limiter = sine(0.1, &[-12.0]).limiter(-1.0);
+        assert_eq!(limiter.latency(), 240 + 6);
+
+        let mut buf = [MaybeUninit::uninit(); 1000];
+        let n = limiter.read(&mut buf).unwrap();
+        assert_eq!(limiter.pos(), n as u64);
+        assert_eq!(limiter.as_inner().pos(), (n + limiter.latency()) as u64);
+
+        limiter.seek(-(n as i64)).unwrap();
+        assert_eq!(limiter.pos(), 0);
+        assert_eq!(read_all(&mut limiter), read_all(&mut sine(0.1, &[-12.0])));
+    }
+}
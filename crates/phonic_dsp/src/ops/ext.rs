@@ -1,6 +1,7 @@
 use crate::ops::{
-    ClipSample, Complement, ComplementSample, Convert, DbRatio, Gain, GainSample, Limit, Mix,
-    Reciprocal,
+    Biquad, BiquadCascade, BiquadCoefficients, ClipSample, Complement, ComplementSample, Convert,
+    DbRatio, Dynamics, DynamicsParams, Gain, GainSample, Limit, Limiter, LimiterParams, Loudness,
+    Mix, Reciprocal, Remix, RemixMatrix, Resample, ResampleQuality,
 };
 use phonic_signal::{
     utils::{DefaultSizedBuf, SizedBuf},
@@ -21,6 +22,82 @@ pub trait DspOpsExt: Sized + Signal {
         Convert::new(self, buf)
     }
 
+    fn biquad(self, coefficients: BiquadCoefficients) -> Biquad<Self> {
+        Biquad::new(self, coefficients)
+    }
+
+    fn lowpass(self, hz: f64, q: f64) -> Biquad<Self> {
+        Biquad::lowpass(self, hz, q)
+    }
+
+    fn highpass(self, hz: f64, q: f64) -> Biquad<Self> {
+        Biquad::highpass(self, hz, q)
+    }
+
+    fn bandpass(self, hz: f64, q: f64) -> Biquad<Self> {
+        Biquad::bandpass(self, hz, q)
+    }
+
+    fn notch(self, hz: f64, q: f64) -> Biquad<Self> {
+        Biquad::notch(self, hz, q)
+    }
+
+    fn allpass(self, hz: f64, q: f64) -> Biquad<Self> {
+        Biquad::allpass(self, hz, q)
+    }
+
+    fn peaking(self, hz: f64, q: f64, gain_db: f64) -> Biquad<Self> {
+        Biquad::peaking(self, hz, q, gain_db)
+    }
+
+    fn low_shelf(self, hz: f64, q: f64, gain_db: f64) -> Biquad<Self> {
+        Biquad::low_shelf(self, hz, q, gain_db)
+    }
+
+    fn high_shelf(self, hz: f64, q: f64, gain_db: f64) -> Biquad<Self> {
+        Biquad::high_shelf(self, hz, q, gain_db)
+    }
+
+    fn butterworth_lowpass(self, hz: f64, order: usize) -> PhonicResult<BiquadCascade<Self>> {
+        BiquadCascade::butterworth_lowpass(self, hz, order)
+    }
+
+    fn butterworth_highpass(self, hz: f64, order: usize) -> PhonicResult<BiquadCascade<Self>> {
+        BiquadCascade::butterworth_highpass(self, hz, order)
+    }
+
+    fn linkwitz_riley_lowpass(self, hz: f64, order: usize) -> PhonicResult<BiquadCascade<Self>> {
+        BiquadCascade::linkwitz_riley_lowpass(self, hz, order)
+    }
+
+    fn linkwitz_riley_highpass(self, hz: f64, order: usize) -> PhonicResult<BiquadCascade<Self>> {
+        BiquadCascade::linkwitz_riley_highpass(self, hz, order)
+    }
+
+    fn dynamics(self, params: DynamicsParams) -> Dynamics<Self> {
+        Dynamics::new(self, params)
+    }
+
+    fn compress(self, threshold_db: f64, ratio: f64) -> Dynamics<Self> {
+        Dynamics::new(self, DynamicsParams::compressor(threshold_db, ratio))
+    }
+
+    fn expand(self, threshold_db: f64, ratio: f64) -> Dynamics<Self> {
+        Dynamics::new(self, DynamicsParams::expander(threshold_db, ratio))
+    }
+
+    fn gate(self, threshold_db: f64) -> Dynamics<Self> {
+        Dynamics::new(self, DynamicsParams::gate(threshold_db))
+    }
+
+    fn limiter(self, ceiling_db: f64) -> Limiter<Self> {
+        Limiter::new(self, LimiterParams::new(ceiling_db))
+    }
+
+    fn limiter_params(self, params: LimiterParams) -> Limiter<Self> {
+        Limiter::new(self, params)
+    }
+
     fn gain_amp(
         self,
         ratio: <Self::Sample as GainSample>::Ratio,
@@ -82,6 +159,10 @@ pub trait DspOpsExt: Sized + Signal {
         Limit::clip(self)
     }
 
+    fn loudness(self) -> Loudness<Self> {
+        Loudness::new(self)
+    }
+
     // TODO: remove 'static bounds for mix methods
 
     fn mix<T>(self, other: T) -> PhonicResult<Mix<(Self, T)>>
@@ -119,6 +200,33 @@ pub trait DspOpsExt: Sized + Signal {
     {
         Mix::cancel(self, other, buf)
     }
+
+    fn remix(self, matrix: RemixMatrix) -> PhonicResult<Remix<Self>> {
+        let buf = DefaultSizedBuf::uninit();
+        Remix::new(self, matrix, buf)
+    }
+
+    fn remix_buf<B>(self, matrix: RemixMatrix, buf: B) -> PhonicResult<Remix<Self, B>> {
+        Remix::new(self, matrix, buf)
+    }
+
+    fn resample(self, sample_rate: usize) -> PhonicResult<Resample<Self>> {
+        let buf = DefaultSizedBuf::uninit();
+        Resample::new(self, sample_rate, buf)
+    }
+
+    fn resample_quality(
+        self,
+        sample_rate: usize,
+        quality: ResampleQuality,
+    ) -> PhonicResult<Resample<Self>> {
+        let buf = DefaultSizedBuf::uninit();
+        Resample::with_quality(self, sample_rate, quality, buf)
+    }
+
+    fn resample_buf<B>(self, sample_rate: usize, buf: B) -> PhonicResult<Resample<Self, B>> {
+        Resample::new(self, sample_rate, buf)
+    }
 }
 
 impl<T: Signal> DspOpsExt for T {}
@@ -0,0 +1,597 @@
+use crate::{
+    ops::{BiquadCoefficients, BiquadState, IntoSample},
+    spectral::Window,
+};
+use phonic_signal::{
+    delegate_signal, ChannelLayout, ChannelPosition, PhonicError, PhonicResult, Signal, SignalExt,
+    SignalReader, SignalSeeker,
+};
+use std::{collections::VecDeque, f64::consts::PI, mem::MaybeUninit};
+
+const N_SUB_BLOCKS_MOMENTARY: usize = 4;
+const N_SUB_BLOCKS_SHORT_TERM: usize = 30;
+
+const ABSOLUTE_GATE: f64 = -70.0;
+const INTEGRATED_RELATIVE_GATE: f64 = -10.0;
+const RANGE_RELATIVE_GATE: f64 = -20.0;
+
+const TRUE_PEAK_FACTOR: usize = 4;
+const TRUE_PEAK_TAPS: usize = 12;
+
+const SURROUND_WEIGHT: f64 = 1.41;
+
+/// A pass-through loudness meter implementing ITU-R BS.1770 and EBU R 128. The signal is
+/// K-weighted and measured in 100ms sub-blocks, from which the momentary (400ms), short-term
+/// (3s), integrated and loudness range measurements are derived. Loudness values are in LUFS
+/// and are negative infinity until enough of the signal has been measured.
+pub struct Loudness<T> {
+    inner: T,
+    weights: Box<[f64]>,
+
+    pre_filter: BiquadCoefficients,
+    rlb_filter: BiquadCoefficients,
+    filter_state: Box<[[BiquadState; 2]]>,
+
+    sub_block_len: usize,
+    sub_block_pos: usize,
+    sub_block_energy: f64,
+    recent_energy: VecDeque<f64>,
+
+    momentary_blocks: Vec<f64>,
+    short_term_blocks: Vec<f64>,
+
+    detector: TruePeakDetector,
+    sample_peak: Box<[f64]>,
+    true_peak: Box<[f64]>,
+}
+
+/// Estimates the peaks between samples by 4x oversampling with a polyphase interpolation filter
+pub(crate) struct TruePeakDetector {
+    phases: Box<[[f64; TRUE_PEAK_TAPS]; TRUE_PEAK_FACTOR]>,
+    history: Box<[[f64; TRUE_PEAK_TAPS]]>,
+}
+
+/// The channel weights of BS.1770 for the conventional channel order of a signal with the given
+/// number of channels. Surround channels are weighted by +1.5 dB and the LFE channel of a 5.1 or
+/// larger layout is excluded.
+pub fn loudness_channel_weights(n_channels: usize) -> Box<[f64]> {
+    match n_channels {
+        5 => vec![1.0, 1.0, 1.0, SURROUND_WEIGHT, SURROUND_WEIGHT].into(),
+        n if n >= 6 => (0..n)
+            .map(|i| match i {
+                0..=2 => 1.0,
+                3 => 0.0,
+                _ => SURROUND_WEIGHT,
+            })
+            .collect(),
+        n => vec![1.0; n].into(),
+    }
+}
+
+/// The channel weights of BS.1770 for the speaker positions of a layout, or `None` if the layout
+/// doesn't assign speaker positions.
+pub fn loudness_layout_weights(layout: &ChannelLayout) -> Option<Box<[f64]>> {
+    use ChannelPosition::*;
+
+    let weights = layout
+        .positions()?
+        .iter()
+        .map(|position| match position {
+            LowFrequency => 0.0,
+            BackLeft | BackRight | BackCenter | SideLeft | SideRight => SURROUND_WEIGHT,
+            _ => 1.0,
+        })
+        .collect();
+
+    Some(weights)
+}
+
+#[inline]
+fn energy_to_lufs(energy: f64) -> f64 {
+    -0.691 + 10.0 * energy.log10()
+}
+
+#[inline]
+fn lufs_to_energy(lufs: f64) -> f64 {
+    10f64.powf((lufs + 0.691) / 10.0)
+}
+
+#[inline]
+fn amplitude_to_db(amplitude: f64) -> f64 {
+    20.0 * amplitude.log10()
+}
+
+fn mean_energy<'a>(blocks: impl IntoIterator<Item = &'a f64>) -> f64 {
+    let (sum, n) = blocks
+        .into_iter()
+        .fold((0.0, 0usize), |(sum, n), energy| (sum + energy, n + 1));
+
+    if n == 0 {
+        0.0
+    } else {
+        sum / n as f64
+    }
+}
+
+impl TruePeakDetector {
+    /// The number of frames by which the interpolated peaks lag behind the input
+    pub(crate) const DELAY: usize = TRUE_PEAK_TAPS / 2;
+
+    pub(crate) fn new(n_channels: usize) -> Self {
+        let n_taps = TRUE_PEAK_FACTOR * TRUE_PEAK_TAPS;
+        let center = (n_taps - 1) as f64 / 2.0;
+        let cutoff = 0.9;
+        let window = Window::Kaiser { beta: 8.0 };
+
+        let mut phases = Box::new([[0.0; TRUE_PEAK_TAPS]; TRUE_PEAK_FACTOR]);
+        for (phase, taps) in phases.iter_mut().enumerate() {
+            for (k, tap) in taps.iter_mut().enumerate() {
+                let n = k * TRUE_PEAK_FACTOR + phase;
+                let x = (n as f64 - center) / TRUE_PEAK_FACTOR as f64 * cutoff;
+                let sinc = if x == 0.0 {
+                    1.0
+                } else {
+                    (PI * x).sin() / (PI * x)
+                };
+
+                *tap = sinc * window.at(n as f64 / (n_taps - 1) as f64);
+            }
+
+            let sum: f64 = taps.iter().sum();
+            taps.iter_mut().for_each(|tap| *tap /= sum);
+        }
+
+        Self {
+            phases,
+            history: vec![[0.0; TRUE_PEAK_TAPS]; n_channels].into(),
+        }
+    }
+
+    /// Returns the largest magnitude of the sample and the interpolated points preceding it
+    #[inline]
+    pub(crate) fn process(&mut self, channel: usize, x: f64) -> f64 {
+        let history = &mut self.history[channel];
+        history.copy_within(..TRUE_PEAK_TAPS - 1, 1);
+        history[0] = x;
+
+        let mut peak = x.abs();
+        for taps in self.phases.iter() {
+            let mut y = 0.0;
+            for k in 0..TRUE_PEAK_TAPS {
+                y += taps[k] * history[k];
+            }
+
+            peak = peak.max(y.abs());
+        }
+
+        peak
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.history.fill([0.0; TRUE_PEAK_TAPS]);
+    }
+}
+
+impl<T: Signal> Loudness<T> {
+    pub fn new(inner: T) -> Self {
+        let spec = inner.spec();
+        let n_channels = spec.n_channels;
+        let sample_rate = spec.sample_rate as f64;
+
+        // the K-weighting filter coefficients of BS.1770 adapted to the sample rate
+        let pre_filter = {
+            let k = (PI * 1681.974450955533 / sample_rate).tan();
+            let q = 0.7071752369554196;
+            let vh = 10f64.powf(3.999843853973347 / 20.0);
+            let vb = vh.powf(0.4996667741545416);
+            let a0 = 1.0 + k / q + k * k;
+
+            BiquadCoefficients {
+                b0: (vh + vb * k / q + k * k) / a0,
+                b1: 2.0 * (k * k - vh) / a0,
+                b2: (vh - vb * k / q + k * k) / a0,
+                a1: 2.0 * (k * k - 1.0) / a0,
+                a2: (1.0 - k / q + k * k) / a0,
+            }
+        };
+
+        let rlb_filter = {
+            let k = (PI * 38.13547087602444 / sample_rate).tan();
+            let q = 0.5003270373238773;
+            let a0 = 1.0 + k / q + k * k;
+
+            BiquadCoefficients {
+                b0: 1.0,
+                b1: -2.0,
+                b2: 1.0,
+                a1: 2.0 * (k * k - 1.0) / a0,
+                a2: (1.0 - k / q + k * k) / a0,
+            }
+        };
+
+        Self {
+            weights: spec
+                .layout
+                .as_ref()
+                .and_then(loudness_layout_weights)
+                .unwrap_or_else(|| loudness_channel_weights(n_channels)),
+
+            pre_filter,
+            rlb_filter,
+            filter_state: vec![[BiquadState::default(); 2]; n_channels].into(),
+
+            sub_block_len: (spec.sample_rate + 5) / 10,
+            sub_block_pos: 0,
+            sub_block_energy: 0.0,
+            recent_energy: VecDeque::with_capacity(N_SUB_BLOCKS_SHORT_TERM),
+
+            momentary_blocks: Vec::new(),
+            short_term_blocks: Vec::new(),
+
+            detector: TruePeakDetector::new(n_channels),
+            sample_peak: vec![0.0; n_channels].into(),
+            true_peak: vec![0.0; n_channels].into(),
+            inner,
+        }
+    }
+}
+
+impl<T> Loudness<T> {
+    /// Overrides the channel weights derived from the number of channels. Returns `InvalidInput`
+    /// if there isn't exactly one weight per channel.
+    pub fn with_weights(mut self, weights: impl Into<Box<[f64]>>) -> PhonicResult<Self> {
+        let weights = weights.into();
+        if weights.len() != self.weights.len() {
+            return Err(PhonicError::invalid_input());
+        }
+
+        self.weights = weights;
+        Ok(self)
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn recent_loudness(&self, n_sub_blocks: usize) -> f64 {
+        if self.recent_energy.len() < n_sub_blocks {
+            return f64::NEG_INFINITY;
+        }
+
+        let recent = self
+            .recent_energy
+            .range(self.recent_energy.len() - n_sub_blocks..);
+        energy_to_lufs(mean_energy(recent))
+    }
+
+    /// The loudness of the last 400ms
+    pub fn momentary(&self) -> f64 {
+        self.recent_loudness(N_SUB_BLOCKS_MOMENTARY)
+    }
+
+    /// The loudness of the last 3s
+    pub fn short_term(&self) -> f64 {
+        self.recent_loudness(N_SUB_BLOCKS_SHORT_TERM)
+    }
+
+    /// The gated loudness of everything measured so far
+    pub fn integrated(&self) -> f64 {
+        let absolute_gate = lufs_to_energy(ABSOLUTE_GATE);
+        let gated = self.momentary_blocks.iter().filter(|e| **e > absolute_gate);
+        let relative_gate =
+            lufs_to_energy(energy_to_lufs(mean_energy(gated.clone())) + INTEGRATED_RELATIVE_GATE);
+
+        let gated = gated.filter(|e| **e > relative_gate);
+        energy_to_lufs(mean_energy(gated))
+    }
+
+    /// The loudness range (LRA) of everything measured so far in LU, as defined by EBU Tech 3342
+    pub fn loudness_range(&self) -> f64 {
+        let absolute_gate = lufs_to_energy(ABSOLUTE_GATE);
+        let gated = self
+            .short_term_blocks
+            .iter()
+            .filter(|e| **e > absolute_gate);
+        let relative_gate =
+            lufs_to_energy(energy_to_lufs(mean_energy(gated.clone())) + RANGE_RELATIVE_GATE);
+
+        let mut gated = gated
+            .filter(|e| **e > relative_gate)
+            .copied()
+            .collect::<Vec<_>>();
+
+        if gated.is_empty() {
+            return 0.0;
+        }
+
+        gated.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let i = ((gated.len() - 1) as f64 * p).round() as usize;
+            energy_to_lufs(gated[i])
+        };
+
+        percentile(0.95) - percentile(0.10)
+    }
+
+    /// The maximum true peak across all channels in dBTP
+    pub fn true_peak(&self) -> f64 {
+        let peak = self.true_peak.iter().fold(0.0, |a: f64, b| a.max(*b));
+        amplitude_to_db(peak)
+    }
+
+    /// The true peak of a single channel in dBTP
+    pub fn channel_true_peak(&self, channel: usize) -> f64 {
+        amplitude_to_db(self.true_peak[channel])
+    }
+
+    /// The maximum sample peak across all channels in dBFS
+    pub fn sample_peak(&self) -> f64 {
+        let peak = self.sample_peak.iter().fold(0.0, |a: f64, b| a.max(*b));
+        amplitude_to_db(peak)
+    }
+
+    /// Clears the filter state and the partially measured sub-block without discarding the
+    /// measurement history.
+    fn reset_filters(&mut self) {
+        self.filter_state.fill([BiquadState::default(); 2]);
+        self.detector.reset();
+        self.sub_block_pos = 0;
+        self.sub_block_energy = 0.0;
+        self.recent_energy.clear();
+    }
+
+    /// Discards all measurements
+    pub fn reset(&mut self) {
+        self.reset_filters();
+        self.momentary_blocks.clear();
+        self.short_term_blocks.clear();
+        self.sample_peak.fill(0.0);
+        self.true_peak.fill(0.0);
+    }
+
+    fn measure_frame(&mut self, frame: impl Iterator<Item = f64>) {
+        for (channel, x) in frame.enumerate() {
+            let peak = self.detector.process(channel, x);
+            self.true_peak[channel] = self.true_peak[channel].max(peak);
+            self.sample_peak[channel] = self.sample_peak[channel].max(x.abs());
+
+            let [pre, rlb] = &mut self.filter_state[channel];
+            let y = rlb.process(&self.rlb_filter, pre.process(&self.pre_filter, x));
+            self.sub_block_energy += self.weights[channel] * y * y;
+        }
+
+        self.sub_block_pos += 1;
+        if self.sub_block_pos < self.sub_block_len {
+            return;
+        }
+
+        if self.recent_energy.len() == N_SUB_BLOCKS_SHORT_TERM {
+            self.recent_energy.pop_front();
+        }
+
+        self.recent_energy
+            .push_back(self.sub_block_energy / self.sub_block_len as f64);
+        self.sub_block_pos = 0;
+        self.sub_block_energy = 0.0;
+
+        let n_recent = self.recent_energy.len();
+        if n_recent >= N_SUB_BLOCKS_MOMENTARY {
+            let recent = self
+                .recent_energy
+                .range(n_recent - N_SUB_BLOCKS_MOMENTARY..);
+            self.momentary_blocks.push(mean_energy(recent));
+        }
+
+        if n_recent >= N_SUB_BLOCKS_SHORT_TERM {
+            self.short_term_blocks
+                .push(mean_energy(&self.recent_energy));
+        }
+    }
+}
+
+delegate_signal! {
+    impl<T> * + !Read + !Write + !SignalSeeker for Loudness<T> {
+        Self as T;
+
+        &self => &self.inner;
+        &mut self => &mut self.inner;
+    }
+}
+
+impl<T> SignalReader for Loudness<T>
+where
+    T: SignalReader,
+    T::Sample: IntoSample<f64>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let samples = self.inner.read_init(buf)?;
+        let n_channels = self.weights.len();
+
+        for frame in samples.chunks_exact(n_channels) {
+            self.measure_frame(frame.iter().map(|s| IntoSample::<f64>::into_sample(*s)));
+        }
+
+        Ok(samples.len())
+    }
+}
+
+impl<T: SignalSeeker> SignalSeeker for Loudness<T> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        self.inner.seek(offset)?;
+        self.reset_filters();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ops::{loudness_channel_weights, loudness_layout_weights, DspOpsExt, Loudness};
+    use phonic_signal::{utils::Cursor, ChannelLayout, SignalReader, SignalSpec};
+    use std::{f64::consts::TAU, mem::MaybeUninit};
+
+    const SAMPLE_RATE: usize = 48000;
+
+    /// Appends a sine with the given peak level of every channel in dBFS
+    fn sine(buf: &mut Vec<f64>, hz: f64, phase: f64, seconds: f64, levels: &[f64]) {
+        let n_frames = (seconds * SAMPLE_RATE as f64).round() as usize;
+        let gains = levels
+            .iter()
+            .map(|db| 10f64.powf(db / 20.0))
+            .collect::<Vec<_>>();
+
+        for i in 0..n_frames {
+            let s = (i as f64 / SAMPLE_RATE as f64 * hz * TAU + phase).sin();
+            buf.extend(gains.iter().map(|gain| s * gain));
+        }
+    }
+
+    /// A stereo 1 kHz sine made of segments given as the level in dBFS and duration in seconds
+    fn stereo(segments: &[(f64, f64)]) -> Cursor<Vec<f64>, f64> {
+        let mut buf = Vec::new();
+        for (db, seconds) in segments {
+            sine(&mut buf, 1000.0, 0.0, *seconds, &[*db, *db]);
+        }
+
+        Cursor::new(SignalSpec::stereo(SAMPLE_RATE), buf)
+    }
+
+    fn assert_near(value: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (value - expected).abs() <= tolerance,
+            "expected {expected} ± {tolerance}, found {value}"
+        );
+    }
+
+    fn measure(signal: Cursor<Vec<f64>, f64>) -> Loudness<Cursor<Vec<f64>, f64>> {
+        let mut meter = signal.loudness();
+        let mut buf = [MaybeUninit::uninit(); 4800];
+        while meter.read(&mut buf).unwrap() > 0 {}
+
+        meter
+    }
+
+    #[test]
+    fn tech_3341_case_1_and_2() {
+        let meter = measure(stereo(&[(-23.0, 20.0)]));
+        assert_near(meter.momentary(), -23.0, 0.1);
+        assert_near(meter.short_term(), -23.0, 0.1);
+        assert_near(meter.integrated(), -23.0, 0.1);
+
+        let meter = measure(stereo(&[(-33.0, 20.0)]));
+        assert_near(meter.momentary(), -33.0, 0.1);
+        assert_near(meter.short_term(), -33.0, 0.1);
+        assert_near(meter.integrated(), -33.0, 0.1);
+    }
+
+    #[test]
+    fn tech_3341_case_3_to_5_gating() {
+        let meter = measure(stereo(&[(-36.0, 10.0), (-23.0, 60.0), (-36.0, 10.0)]));
+        assert_near(meter.integrated(), -23.0, 0.1);
+
+        let meter = measure(stereo(&[
+            (-72.0, 10.0),
+            (-36.0, 10.0),
+            (-23.0, 60.0),
+            (-36.0, 10.0),
+            (-72.0, 10.0),
+        ]));
+        assert_near(meter.integrated(), -23.0, 0.1);
+
+        let meter = measure(stereo(&[(-26.0, 20.0), (-20.0, 20.1), (-26.0, 20.0)]));
+        assert_near(meter.integrated(), -23.0, 0.1);
+    }
+
+    #[test]
+    fn tech_3341_case_6_channel_weights() {
+        for levels in [
+            &[-28.0, -28.0, -24.0, -30.0, -30.0][..],
+            &[-28.0, -28.0, -24.0, 0.0, -30.0, -30.0][..],
+        ] {
+            let mut buf = Vec::new();
+            sine(&mut buf, 1000.0, 0.0, 20.0, levels);
+
+            let spec = SignalSpec::new(levels.len(), SAMPLE_RATE);
+            let meter = measure(Cursor::new(spec, buf));
+            assert_near(meter.integrated(), -23.0, 0.1);
+        }
+    }
+
+    #[test]
+    fn weights_must_match_the_channel_count() {
+        let meter = stereo(&[(-20.0, 1.0)]).loudness();
+        assert!(meter.with_weights([1.0]).is_err());
+
+        let meter = stereo(&[(-20.0, 1.0)]).loudness();
+        assert!(meter.with_weights([1.0, 1.41]).is_ok());
+    }
+
+    #[test]
+    fn layout_weights_follow_speaker_positions() {
+        let surround = loudness_layout_weights(&ChannelLayout::SURROUND_5_1).unwrap();
+        assert_eq!(surround, loudness_channel_weights(6));
+
+        let quad = loudness_layout_weights(&ChannelLayout::QUAD).unwrap();
+        assert_eq!(&quad[..], [1.0, 1.0, 1.41, 1.41]);
+
+        assert_eq!(loudness_layout_weights(&ChannelLayout::ambisonic(1)), None);
+    }
+
+    #[test]
+    fn tech_3342_loudness_range() {
+        let meter = measure(stereo(&[(-20.0, 20.0), (-30.0, 20.0)]));
+        assert_near(meter.loudness_range(), 10.0, 1.0);
+
+        let meter = measure(stereo(&[(-20.0, 20.0), (-15.0, 20.0)]));
+        assert_near(meter.loudness_range(), 5.0, 1.0);
+
+        let meter = measure(stereo(&[(-40.0, 20.0), (-20.0, 20.0)]));
+        assert_near(meter.loudness_range(), 20.0, 1.0);
+
+        let meter = measure(stereo(&[
+            (-50.0, 20.0),
+            (-35.0, 20.0),
+            (-20.0, 20.0),
+            (-35.0, 20.0),
+            (-50.0, 20.0),
+        ]));
+        assert_near(meter.loudness_range(), 15.0, 1.0);
+    }
+
+    #[test]
+    fn tech_3341_true_peak() {
+        let fs = SAMPLE_RATE as f64;
+        let cases = [
+            (fs / 4.0, 0.0),
+            (fs / 4.0, TAU / 8.0),
+            (fs / 6.0, TAU / 6.0),
+            (fs / 8.0, TAU * 67.5 / 360.0),
+        ];
+
+        for (hz, phase) in cases {
+            // a short fade in keeps the onset of the sine from ringing the interpolation filter
+            let mut buf = Vec::new();
+            sine(&mut buf, hz, phase, 1.0, &[-6.0, -6.0]);
+            buf.iter_mut()
+                .take(960)
+                .enumerate()
+                .for_each(|(i, s)| *s *= (i / 2) as f64 / 480.0);
+
+            let meter = measure(Cursor::new(SignalSpec::stereo(SAMPLE_RATE), buf));
+            let true_peak = meter.true_peak();
+
+            assert!(
+                (-6.4..=-5.8).contains(&true_peak),
+                "{hz} hz, {phase} rad: {true_peak} dBTP"
+            );
+
+            if hz == fs / 4.0 && phase != 0.0 {
+                assert_near(meter.sample_peak(), -9.01, 0.01);
+            }
+        }
+    }
+}
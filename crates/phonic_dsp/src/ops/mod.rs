@@ -1,17 +1,29 @@
+mod biquad;
 mod complement;
 mod convert;
+#[cfg(feature = "io")]
 mod convert_known;
+mod dynamics;
 mod ext;
 mod gain;
 mod limit;
+mod loudness;
 mod magnitude;
 mod mix;
+mod remix;
+mod resample;
 
+pub use biquad::*;
 pub use complement::*;
 pub use convert::*;
+#[cfg(feature = "io")]
 pub use convert_known::*;
+pub use dynamics::*;
 pub use ext::*;
 pub use gain::*;
 pub use limit::*;
+pub use loudness::*;
 pub use magnitude::*;
 pub use mix::*;
+pub use remix::*;
+pub use resample::*;
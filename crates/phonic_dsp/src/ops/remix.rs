@@ -0,0 +1,452 @@
+use crate::ops::{FromSample, IntoSample};
+use phonic_signal::{
+    delegate_signal,
+    utils::{slice_as_init, DefaultSizedBuf},
+    ChannelLayout, PhonicError, PhonicResult, Sample, Signal, SignalExt, SignalReader, SignalSpec,
+    SignalWriter,
+};
+use std::{
+    f64::consts::{FRAC_1_SQRT_2, FRAC_PI_4},
+    mem::MaybeUninit,
+};
+
+/// Maps the channels of a signal to a different set of channels with a gain matrix.
+///
+/// The matrix maps the channels of the source to the channels of the destination. When reading,
+/// the inner signal is the source. When writing, the inner signal is the destination.
+pub struct Remix<T: Signal, B = DefaultSizedBuf<MaybeUninit<<T as Signal>::Sample>>> {
+    inner: T,
+    spec: SignalSpec,
+    matrix: RemixMatrix,
+    buf: B,
+}
+
+/// A row major matrix of gains where each row is an output channel and each column is an input
+/// channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemixMatrix {
+    n_inputs: usize,
+    n_outputs: usize,
+    gains: Box<[f64]>,
+    layout: Option<ChannelLayout>,
+}
+
+/// The gain curve used to pan a mono signal between two channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanLaw {
+    /// The gains of both channels sum to 1, giving -6 dB at the center
+    Linear,
+
+    /// The powers of both channels sum to 1, giving -3 dB at the center
+    #[default]
+    ConstantPower,
+}
+
+impl<T: Signal, B> Remix<T, B> {
+    pub fn new(inner: T, matrix: RemixMatrix, buf: B) -> PhonicResult<Self> {
+        let inner_spec = *inner.spec();
+        let (n_channels, source_layout) = if matrix.n_inputs == inner_spec.n_channels {
+            (matrix.n_outputs, inner_spec.layout)
+        } else if matrix.n_outputs == inner_spec.n_channels {
+            (matrix.n_inputs, None)
+        } else {
+            return Err(PhonicError::param_mismatch());
+        };
+
+        let layout = matrix.layout.or_else(|| {
+            let positions = source_layout?.positions()?.to_vec();
+            let mapped = matrix
+                .selection()?
+                .iter()
+                .map(|i| positions[*i])
+                .collect::<Vec<_>>();
+
+            ChannelLayout::from_positions(&mapped).ok()
+        });
+
+        let spec = SignalSpec {
+            n_channels,
+            layout: layout.filter(|layout| layout.n_channels() == n_channels),
+            ..inner_spec
+        };
+
+        Ok(Self {
+            inner,
+            spec,
+            matrix,
+            buf,
+        })
+    }
+
+    pub fn matrix(&self) -> &RemixMatrix {
+        &self.matrix
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl RemixMatrix {
+    /// Creates a matrix where every gain is 0
+    pub fn new(n_inputs: usize, n_outputs: usize) -> Self {
+        Self {
+            n_inputs,
+            n_outputs,
+            gains: vec![0.0; n_inputs * n_outputs].into(),
+            layout: None,
+        }
+    }
+
+    /// Creates a matrix from a row major slice of gains
+    pub fn from_gains(n_inputs: usize, n_outputs: usize, gains: &[f64]) -> PhonicResult<Self> {
+        if gains.len() != n_inputs * n_outputs {
+            return Err(PhonicError::invalid_input());
+        }
+
+        Ok(Self {
+            n_inputs,
+            n_outputs,
+            gains: gains.into(),
+            layout: None,
+        })
+    }
+
+    pub fn identity(n_channels: usize) -> Self {
+        let mut matrix = Self::new(n_channels, n_channels);
+        for i in 0..n_channels {
+            matrix.set_gain(i, i, 1.0);
+        }
+
+        matrix
+    }
+
+    /// Creates a matrix where output `i` is a copy of input `channels[i]`
+    pub fn extract(n_inputs: usize, channels: &[usize]) -> PhonicResult<Self> {
+        if channels.iter().any(|i| *i >= n_inputs) {
+            return Err(PhonicError::out_of_bounds());
+        }
+
+        let mut matrix = Self::new(n_inputs, channels.len());
+        for (output, input) in channels.iter().enumerate() {
+            matrix.set_gain(output, *input, 1.0);
+        }
+
+        Ok(matrix)
+    }
+
+    /// Creates a matrix where output `i` is a copy of input `order[i]`. Every input must appear
+    /// exactly once.
+    pub fn reorder(order: &[usize]) -> PhonicResult<Self> {
+        let mut seen = vec![false; order.len()];
+        for i in order {
+            match seen.get_mut(*i) {
+                Some(seen @ false) => *seen = true,
+                _ => return Err(PhonicError::invalid_input()),
+            }
+        }
+
+        Self::extract(order.len(), order)
+    }
+
+    /// Creates a matrix that repeats every frame of the input `n_copies` times
+    pub fn duplicate(n_inputs: usize, n_copies: usize) -> Self {
+        let channels = (0..n_inputs * n_copies)
+            .map(|i| i % n_inputs)
+            .collect::<Vec<_>>();
+
+        Self::extract(n_inputs, &channels).unwrap()
+    }
+
+    /// The ITU-R BS.775 down-mix from 5.1 to stereo. The center and surround channels are
+    /// attenuated by 3 dB and the LFE channel is discarded.
+    pub fn surround_5_1_to_stereo() -> Self {
+        #[rustfmt::skip]
+        let gains = [
+            1.0, 0.0, FRAC_1_SQRT_2, 0.0, FRAC_1_SQRT_2, 0.0,
+            0.0, 1.0, FRAC_1_SQRT_2, 0.0, 0.0, FRAC_1_SQRT_2,
+        ];
+
+        Self::from_gains(6, 2, &gains)
+            .unwrap()
+            .with_layout(ChannelLayout::STEREO)
+    }
+
+    /// The ITU-R BS.775 down-mix from stereo to mono
+    pub fn stereo_to_mono() -> Self {
+        Self::from_gains(2, 1, &[0.5, 0.5])
+            .unwrap()
+            .with_layout(ChannelLayout::MONO)
+    }
+
+    /// Pans a mono signal into stereo. `pan` ranges from -1 (left) to 1 (right).
+    pub fn mono_to_stereo(pan: f64, law: PanLaw) -> Self {
+        let pan = pan.clamp(-1.0, 1.0);
+        let (left, right) = match law {
+            PanLaw::Linear => ((1.0 - pan) / 2.0, (1.0 + pan) / 2.0),
+            PanLaw::ConstantPower => {
+                let theta = (pan + 1.0) * FRAC_PI_4;
+                (theta.cos(), theta.sin())
+            }
+        };
+
+        Self::from_gains(1, 2, &[left, right])
+            .unwrap()
+            .with_layout(ChannelLayout::STEREO)
+    }
+
+    /// Sets the layout of the output channels
+    pub fn with_layout(mut self, layout: impl Into<Option<ChannelLayout>>) -> Self {
+        self.layout = layout.into();
+        self
+    }
+
+    pub fn with_gain(mut self, output: usize, input: usize, gain: f64) -> Self {
+        self.set_gain(output, input, gain);
+        self
+    }
+
+    pub fn n_inputs(&self) -> usize {
+        self.n_inputs
+    }
+
+    pub fn n_outputs(&self) -> usize {
+        self.n_outputs
+    }
+
+    pub fn layout(&self) -> Option<&ChannelLayout> {
+        self.layout.as_ref()
+    }
+
+    /// # Panics
+    /// Panics if `output` or `input` are out of bounds.
+    pub fn gain(&self, output: usize, input: usize) -> f64 {
+        self.gains[self.index(output, input)]
+    }
+
+    /// # Panics
+    /// Panics if `output` or `input` are out of bounds.
+    pub fn set_gain(&mut self, output: usize, input: usize, gain: f64) {
+        let i = self.index(output, input);
+        self.gains[i] = gain;
+    }
+
+    fn index(&self, output: usize, input: usize) -> usize {
+        assert!(output < self.n_outputs && input < self.n_inputs);
+        output * self.n_inputs + input
+    }
+
+    /// The input copied to each output, or `None` if any output isn't a copy of a single input
+    fn selection(&self) -> Option<Vec<usize>> {
+        self.gains
+            .chunks_exact(self.n_inputs.max(1))
+            .map(|row| {
+                let mut nonzero = row.iter().enumerate().filter(|(_, g)| **g != 0.0);
+                match (nonzero.next(), nonzero.next()) {
+                    (Some((i, 1.0)), None) => Some(i),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn apply<S, D>(&self, src: &[S], dst: &mut [MaybeUninit<D>])
+    where
+        S: Sample + IntoSample<f64>,
+        D: Sample + FromSample<f64>,
+    {
+        let src_frames = src.chunks_exact(self.n_inputs);
+        let dst_frames = dst.chunks_exact_mut(self.n_outputs);
+
+        for (src_frame, dst_frame) in src_frames.zip(dst_frames) {
+            let rows = self.gains.chunks_exact(self.n_inputs);
+            for (row, sample) in rows.zip(dst_frame) {
+                let mixed = row
+                    .iter()
+                    .zip(src_frame)
+                    .map(|(gain, s)| gain * s.into_sample())
+                    .sum::<f64>();
+
+                sample.write(D::from_sample(mixed));
+            }
+        }
+    }
+}
+
+delegate_signal! {
+    impl<T, B> * + !Signal + !Read + !Write for Remix<T, B> {
+        Self as T;
+
+        &self => &self.inner;
+        &mut self => &mut self.inner;
+   }
+}
+
+impl<T: Signal, B> Signal for Remix<T, B> {
+    type Sample = T::Sample;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+impl<T, B> SignalReader for Remix<T, B>
+where
+    T: SignalReader,
+    T::Sample: IntoSample<f64> + FromSample<f64>,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let n_inputs = self.matrix.n_inputs;
+        let n_outputs = self.matrix.n_outputs;
+        if n_inputs != self.inner.spec().n_channels || n_outputs != self.spec.n_channels {
+            return Err(PhonicError::unsupported());
+        }
+
+        let inner_buf = self.buf.as_mut();
+        let n_frames = (buf.len() / n_outputs).min(inner_buf.len() / n_inputs);
+        if n_frames == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let samples = self
+            .inner
+            .read_init(&mut inner_buf[..n_frames * n_inputs])?;
+        let n_read = samples.len() / n_inputs * n_outputs;
+        self.matrix.apply(samples, &mut buf[..n_read]);
+
+        Ok(n_read)
+    }
+}
+
+impl<T, B> SignalWriter for Remix<T, B>
+where
+    T: SignalWriter,
+    T::Sample: IntoSample<f64> + FromSample<f64>,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn write(&mut self, buf: &[Self::Sample]) -> PhonicResult<usize> {
+        let n_inputs = self.matrix.n_inputs;
+        let n_outputs = self.matrix.n_outputs;
+        if n_inputs != self.spec.n_channels || n_outputs != self.inner.spec().n_channels {
+            return Err(PhonicError::unsupported());
+        }
+
+        let inner_buf = self.buf.as_mut();
+        let n_frames = (buf.len() / n_inputs).min(inner_buf.len() / n_outputs);
+        if n_frames == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let uninit_slice = &mut inner_buf[..n_frames * n_outputs];
+        self.matrix.apply(&buf[..n_frames * n_inputs], uninit_slice);
+
+        let init_slice = unsafe { slice_as_init(uninit_slice) };
+        let n_written = self.inner.write(init_slice)?;
+
+        Ok(n_written / n_outputs * n_inputs)
+    }
+
+    fn flush(&mut self) -> PhonicResult<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ops::{DspOpsExt, PanLaw, RemixMatrix};
+    use phonic_signal::{
+        utils::{Cursor, SignalUtilsExt},
+        ChannelLayout, ChannelPosition, Signal, SignalExt, SignalSpec, SignalWriter,
+    };
+    use std::{f64::consts::FRAC_1_SQRT_2, mem::MaybeUninit};
+
+    fn assert_near(actual: &[f64], expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len());
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{actual:?} != {expected:?}");
+        }
+    }
+
+    #[test]
+    fn surround_downmix_discards_lfe() {
+        let spec = SignalSpec::new(6, 48000).with_layout(ChannelLayout::SURROUND_5_1);
+        let frames = [0.1, 0.2, 0.3, 1.0, 0.4, 0.5, -0.1, -0.2, 0.0, 1.0, 0.0, 0.0];
+        let signal = Cursor::new(spec, &frames[..]);
+
+        let mut remix = signal
+            .remix(RemixMatrix::surround_5_1_to_stereo())
+            .unwrap()
+            .polled();
+        assert_eq!(remix.spec().n_channels, 2);
+        assert_eq!(remix.spec().layout, Some(ChannelLayout::STEREO));
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        let samples = remix.read_exact_init(&mut buf).unwrap();
+        let h = FRAC_1_SQRT_2;
+        assert_near(
+            samples,
+            &[0.1 + 0.3 * h + 0.4 * h, 0.2 + 0.3 * h + 0.5 * h, -0.1, -0.2],
+        );
+    }
+
+    #[test]
+    fn mono_pans_into_stereo() {
+        let signal = Cursor::new(SignalSpec::mono(48000), &[1.0, 0.5][..]);
+        let mut remix = signal
+            .remix(RemixMatrix::mono_to_stereo(0.0, PanLaw::ConstantPower))
+            .unwrap()
+            .polled();
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        let samples = remix.read_exact_init(&mut buf).unwrap();
+        let h = FRAC_1_SQRT_2;
+        assert_near(samples, &[h, h, 0.5 * h, 0.5 * h]);
+
+        let linear = RemixMatrix::mono_to_stereo(1.0, PanLaw::Linear);
+        assert_eq!((linear.gain(0, 0), linear.gain(1, 0)), (0.0, 1.0));
+    }
+
+    #[test]
+    fn reorder_maps_the_layout() {
+        use ChannelPosition::*;
+
+        let spec = SignalSpec::new(3, 48000).with_layout(ChannelLayout::SURROUND_2_1);
+        let signal = Cursor::new(spec, &[1i16, 2, 3, 4, 5, 6][..]);
+        let mut remix = signal
+            .remix(RemixMatrix::reorder(&[2, 0, 1]).unwrap())
+            .unwrap()
+            .polled();
+
+        let layout = remix.spec().layout.unwrap();
+        assert_eq!(
+            layout.positions(),
+            Some(&[LowFrequency, FrontLeft, FrontRight][..])
+        );
+
+        let mut buf = [MaybeUninit::uninit(); 6];
+        let samples = remix.read_exact_init(&mut buf).unwrap();
+        assert_eq!(samples, [3, 1, 2, 6, 4, 5]);
+
+        assert!(RemixMatrix::reorder(&[0, 0]).is_err());
+    }
+
+    #[test]
+    fn writes_through_the_matrix() {
+        let mut output = [0.0; 4];
+        let signal = Cursor::new(SignalSpec::stereo(48000), &mut output[..]);
+
+        let mut remix = signal.remix(RemixMatrix::duplicate(1, 2)).unwrap().polled();
+        assert_eq!(remix.spec().n_channels, 1);
+
+        remix.write_exact(&[0.25, -0.5]).unwrap();
+        remix.flush().unwrap();
+        drop(remix);
+
+        assert_eq!(output, [0.25, 0.25, -0.5, -0.5]);
+    }
+}
@@ -0,0 +1,495 @@
+use crate::{ops::IntoSample, spectral::bessel_i0};
+use phonic_signal::{
+    delegate_signal, utils::DefaultSizedBuf, FiniteSignal, IndexedSignal, PhonicError,
+    PhonicResult, Signal, SignalExt, SignalReader, SignalSeeker, SignalSpec,
+};
+use std::{f64::consts::PI, mem::MaybeUninit};
+
+/// A set of parameters for the band-limited interpolation kernel used by `Resample`. Higher
+/// quality presets have a narrower transition band and a higher stopband attenuation at the cost
+/// of a longer kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResampleQuality {
+    Fast,
+    #[default]
+    Balanced,
+    High,
+}
+
+pub struct Resample<T: Signal, B = DefaultSizedBuf<MaybeUninit<<T as Signal>::Sample>>> {
+    inner: T,
+    buf: B,
+    spec: SignalSpec,
+    kernel: Kernel,
+
+    /// interleaved input samples starting at the input frame `window_start`
+    window: Vec<f64>,
+    window_start: u64,
+    n_read: u64,
+    exhausted: bool,
+
+    pos: u64,
+
+    /// an error returned by the inner signal during a read that still produced frames, which is
+    /// returned by the next read
+    error: Option<PhonicError>,
+}
+
+struct Kernel {
+    half_len: usize,
+    n_phases: usize,
+    table: Box<[f64]>,
+}
+
+impl ResampleQuality {
+    fn zero_crossings(self) -> usize {
+        match self {
+            Self::Fast => 8,
+            Self::Balanced => 16,
+            Self::High => 32,
+        }
+    }
+
+    fn n_phases(self) -> usize {
+        match self {
+            Self::Fast => 64,
+            Self::Balanced => 128,
+            Self::High => 256,
+        }
+    }
+
+    fn rolloff(self) -> f64 {
+        match self {
+            Self::Fast => 0.85,
+            Self::Balanced => 0.92,
+            Self::High => 0.96,
+        }
+    }
+
+    fn kaiser_beta(self) -> f64 {
+        match self {
+            Self::Fast => 6.0,
+            Self::Balanced => 8.6,
+            Self::High => 12.0,
+        }
+    }
+}
+
+impl Kernel {
+    fn new(quality: ResampleQuality, from_rate: usize, to_rate: usize) -> Self {
+        let ratio = (to_rate as f64 / from_rate as f64).min(1.0);
+        let cutoff = quality.rolloff() * ratio;
+
+        let half_len = (quality.zero_crossings() as f64 / cutoff).ceil() as usize;
+        let n_taps = half_len * 2;
+        let n_phases = quality.n_phases();
+
+        let beta = quality.kaiser_beta();
+        let i0_beta = bessel_i0(beta);
+
+        let mut table = vec![0.0; (n_phases + 1) * n_taps].into_boxed_slice();
+        for (phase, row) in table.chunks_exact_mut(n_taps).enumerate() {
+            let frac = phase as f64 / n_phases as f64;
+
+            for (k, tap) in row.iter_mut().enumerate() {
+                let x = (k as f64 - half_len as f64 + 1.0) - frac;
+                let r = x / half_len as f64;
+                if r.abs() >= 1.0 {
+                    continue;
+                }
+
+                let window = bessel_i0(beta * (1.0 - r * r).sqrt()) / i0_beta;
+                *tap = cutoff * sinc(cutoff * x) * window;
+            }
+
+            let sum: f64 = row.iter().sum();
+            row.iter_mut().for_each(|tap| *tap /= sum);
+        }
+
+        Self {
+            half_len,
+            n_phases,
+            table,
+        }
+    }
+
+    #[inline]
+    fn phases(&self, frac: f64) -> (&[f64], &[f64], f64) {
+        let n_taps = self.half_len * 2;
+        let p = frac * self.n_phases as f64;
+        let i = (p as usize).min(self.n_phases - 1);
+
+        let lower = &self.table[i * n_taps..(i + 1) * n_taps];
+        let upper = &self.table[(i + 1) * n_taps..(i + 2) * n_taps];
+
+        (lower, upper, p - i as f64)
+    }
+}
+
+impl<T: Signal, B> Resample<T, B> {
+    pub fn new(inner: T, sample_rate: usize, buf: B) -> PhonicResult<Self> {
+        Self::with_quality(inner, sample_rate, ResampleQuality::default(), buf)
+    }
+
+    pub fn with_quality(
+        inner: T,
+        sample_rate: usize,
+        quality: ResampleQuality,
+        buf: B,
+    ) -> PhonicResult<Self> {
+        let inner_spec = *inner.spec();
+        if sample_rate == 0 || inner_spec.sample_rate == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let spec = SignalSpec {
+            sample_rate,
+            ..inner_spec
+        };
+
+        let kernel = Kernel::new(quality, inner_spec.sample_rate, sample_rate);
+
+        Ok(Self {
+            inner,
+            buf,
+            spec,
+            kernel,
+
+            window: Vec::new(),
+            window_start: 0,
+            n_read: 0,
+            exhausted: false,
+
+            pos: 0,
+            error: None,
+        })
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Maps a frame index of this signal onto the frame index of the inner signal and the
+    /// fractional offset between it and the next inner frame.
+    #[inline]
+    fn input_time(&self, pos: u64) -> (u64, f64) {
+        let from_rate = self.inner.spec().sample_rate as u128;
+        let to_rate = self.spec.sample_rate as u128;
+
+        let numerator = pos as u128 * from_rate;
+        let idx = (numerator / to_rate) as u64;
+        let frac = (numerator % to_rate) as f64 / to_rate as f64;
+
+        (idx, frac)
+    }
+
+    fn output_len(&self, input_len: u64) -> u64 {
+        let from_rate = self.inner.spec().sample_rate as u128;
+        let to_rate = self.spec.sample_rate as u128;
+
+        (input_len as u128 * to_rate).div_ceil(from_rate) as u64
+    }
+
+    #[inline]
+    fn input_sample(&self, frame: i64, channel: usize) -> f64 {
+        if frame < self.window_start as i64 || frame >= self.n_read as i64 {
+            return 0.0;
+        }
+
+        let i = (frame as u64 - self.window_start) as usize;
+        self.window[i * self.spec.n_channels + channel]
+    }
+
+    fn discard_window(&mut self) {
+        let (idx, _) = self.input_time(self.pos);
+        let first_needed = (idx + 1).saturating_sub(self.kernel.half_len as u64);
+        let n_frames = first_needed
+            .saturating_sub(self.window_start)
+            .min(self.n_read - self.window_start);
+
+        self.window
+            .drain(..n_frames as usize * self.spec.n_channels);
+        self.window_start += n_frames;
+    }
+}
+
+impl<T, B> Resample<T, B>
+where
+    T: SignalReader,
+    T::Sample: IntoSample<f64>,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn fill_window(&mut self) -> PhonicResult<()> {
+        let samples = self.inner.read_init(self.buf.as_mut())?;
+        if samples.is_empty() {
+            self.exhausted = true;
+            return Ok(());
+        }
+
+        let n_channels = self.spec.n_channels;
+        debug_assert_eq!(samples.len() % n_channels, 0);
+
+        self.window
+            .extend(samples.iter().map(|s| IntoSample::<f64>::into_sample(*s)));
+        self.n_read += (samples.len() / n_channels) as u64;
+
+        Ok(())
+    }
+}
+
+delegate_signal! {
+    impl<T, B> BlockingSignal for Resample<T, B> {
+        Self as T;
+
+        &self => &self.inner;
+    }
+}
+
+impl<T: Signal, B> Signal for Resample<T, B> {
+    type Sample = T::Sample;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+impl<T: Signal, B> IndexedSignal for Resample<T, B> {
+    fn pos(&self) -> u64 {
+        self.pos
+    }
+}
+
+impl<T: FiniteSignal, B> FiniteSignal for Resample<T, B> {
+    fn len(&self) -> u64 {
+        self.output_len(self.inner.len())
+    }
+}
+
+impl<T, B> SignalReader for Resample<T, B>
+where
+    T: SignalReader,
+    T::Sample: IntoSample<f64>,
+    f64: IntoSample<T::Sample>,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
+        let n_channels = self.spec.n_channels;
+        let half_len = self.kernel.half_len as u64;
+        let mut n_samples = 0;
+
+        for frame in buf.chunks_exact_mut(n_channels) {
+            let (idx, frac) = self.input_time(self.pos);
+
+            while !self.exhausted && self.n_read < idx + half_len + 1 {
+                match self.fill_window() {
+                    Ok(()) => continue,
+                    Err(e) if n_samples == 0 => return Err(e),
+                    Err(e) => {
+                        self.error = Some(e);
+                        break;
+                    }
+                }
+            }
+
+            if self.exhausted && idx >= self.n_read {
+                break;
+            }
+
+            if !self.exhausted && self.n_read < idx + half_len + 1 {
+                break;
+            }
+
+            let (lower, upper, t) = self.kernel.phases(frac);
+            let first = idx as i64 - half_len as i64 + 1;
+
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let acc = lower
+                    .iter()
+                    .zip(upper)
+                    .enumerate()
+                    .map(|(k, (l, u))| {
+                        (l + t * (u - l)) * self.input_sample(first + k as i64, channel)
+                    })
+                    .sum::<f64>();
+
+                sample.write(acc.into_sample());
+            }
+
+            self.pos += 1;
+            n_samples += n_channels;
+        }
+
+        self.discard_window();
+        Ok(n_samples)
+    }
+}
+
+impl<T: SignalSeeker, B> SignalSeeker for Resample<T, B> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        let pos = self
+            .pos
+            .checked_add_signed(offset)
+            .ok_or(PhonicError::out_of_bounds())?;
+
+        let (idx, _) = self.input_time(pos);
+        let window_start = (idx + 1).saturating_sub(self.kernel.half_len as u64);
+        self.inner.seek(window_start as i64 - self.n_read as i64)?;
+
+        self.window.clear();
+        self.window_start = window_start;
+        self.n_read = window_start;
+        self.exhausted = false;
+        self.pos = pos;
+        self.error = None;
+
+        Ok(())
+    }
+}
+
+#[inline]
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ops::DspOpsExt;
+    use phonic_signal::{
+        utils::{Cursor, SignalUtilsExt},
+        FiniteSignal, IndexedSignal, PhonicError, PhonicResult, Signal, SignalReader, SignalSeeker,
+        SignalSpec,
+    };
+    use std::{f64::consts::TAU, mem::MaybeUninit};
+
+    /// A mono signal of ones that fails once after `n_samples` samples and then ends
+    struct Failing {
+        spec: SignalSpec,
+        n_samples: usize,
+        failed: bool,
+    }
+
+    impl Signal for Failing {
+        type Sample = f64;
+
+        fn spec(&self) -> &SignalSpec {
+            &self.spec
+        }
+    }
+
+    impl SignalReader for Failing {
+        fn read(&mut self, buf: &mut [MaybeUninit<f64>]) -> PhonicResult<usize> {
+            if self.n_samples == 0 {
+                return match std::mem::replace(&mut self.failed, true) {
+                    false => Err(PhonicError::invalid_state()),
+                    true => Ok(0),
+                };
+            }
+
+            let n = buf.len().min(64).min(self.n_samples);
+            buf[..n].fill(MaybeUninit::new(1.0));
+            self.n_samples -= n;
+            Ok(n)
+        }
+    }
+
+    fn sine(sample_rate: usize, hz: f64, n_frames: usize) -> Cursor<Vec<f64>, f64> {
+        let buf = (0..n_frames)
+            .map(|i| (i as f64 / sample_rate as f64 * hz * TAU).sin())
+            .collect();
+
+        Cursor::new(SignalSpec::mono(sample_rate), buf)
+    }
+
+    fn assert_sine(samples: &[f64], offset: usize, sample_rate: usize, hz: f64, tolerance: f64) {
+        for (i, s) in samples.iter().enumerate().skip(offset) {
+            let expected = (i as f64 / sample_rate as f64 * hz * TAU).sin();
+            assert!(
+                (s - expected).abs() < tolerance,
+                "frame {i}: expected {expected}, found {s}"
+            );
+        }
+    }
+
+    #[test]
+    fn upsampled_sine_keeps_frequency_and_phase() {
+        let signal = sine(44100, 1000.0, 44100).resample(48000).unwrap();
+        assert_eq!(signal.len(), 48000);
+
+        let samples: Vec<f64> = signal.polled().read_all_into().unwrap();
+        assert_eq!(samples.len(), 48000);
+        assert_sine(&samples[..47900], 100, 48000, 1000.0, 1e-3);
+    }
+
+    #[test]
+    fn downsampled_sine_keeps_frequency_and_phase() {
+        let signal = sine(96000, 440.0, 96000).resample(44100).unwrap();
+        assert_eq!(signal.len(), 44100);
+
+        let samples: Vec<f64> = signal.polled().read_all_into().unwrap();
+        assert_eq!(samples.len(), 44100);
+        assert_sine(&samples[..44000], 100, 44100, 440.0, 1e-3);
+    }
+
+    #[test]
+    fn frequencies_above_target_nyquist_are_attenuated() {
+        let signal = sine(48000, 15000.0, 48000).resample(22050).unwrap();
+        let samples: Vec<f64> = signal.polled().read_all_into().unwrap();
+        let peak = samples[200..samples.len() - 200]
+            .iter()
+            .fold(0.0f64, |peak, s| peak.max(s.abs()));
+
+        assert!(peak < 1e-2, "peak: {peak}");
+    }
+
+    #[test]
+    fn seek_matches_continuous_read() {
+        let continuous = sine(44100, 1000.0, 4410).resample(48000).unwrap();
+        let expected: Vec<f64> = continuous.polled().read_all_into().unwrap();
+
+        let mut seeked = sine(44100, 1000.0, 4410).resample(48000).unwrap();
+        seeked.seek(1234).unwrap();
+        assert_eq!(seeked.pos(), 1234);
+
+        let samples: Vec<f64> = seeked.polled().read_all_into().unwrap();
+        assert_eq!(samples.len(), expected.len() - 1234);
+        samples
+            .iter()
+            .zip(&expected[1234..])
+            .for_each(|(a, b)| assert!((a - b).abs() < 1e-9));
+    }
+
+    #[test]
+    fn errors_are_returned_after_a_partial_read() {
+        let signal = Failing {
+            spec: SignalSpec::mono(44100),
+            n_samples: 1024,
+            failed: false,
+        };
+
+        let mut resampled = signal.resample(48000).unwrap();
+        let mut buf = [MaybeUninit::uninit(); 2048];
+        let n_frames = resampled.read(&mut buf).unwrap();
+        assert!(n_frames > 0);
+
+        assert!(matches!(
+            resampled.read(&mut buf),
+            Err(PhonicError::InvalidState { .. })
+        ));
+
+        // the rest of the 1024 * 48000 / 44100 frames are read once the inner signal is exhausted
+        let n_remaining = resampled.read(&mut buf).unwrap();
+        assert_eq!(n_frames + n_remaining, 1115);
+    }
+}
@@ -0,0 +1,211 @@
+use crate::spectral::Complex;
+use phonic_signal::{PhonicError, PhonicResult};
+use std::f64::consts::TAU;
+
+/// A fast fourier transform of real valued input with a power of two length. The spectrum of an
+/// input of `len` samples is made up of the `len / 2 + 1` non-negative frequency bins.
+pub struct RealFft {
+    len: usize,
+
+    /// twiddle factors of the half length complex transform
+    twiddles: Box<[Complex]>,
+
+    /// twiddle factors used to split the half length transform into the real spectrum
+    split: Box<[Complex]>,
+    scratch: Box<[Complex]>,
+}
+
+impl RealFft {
+    pub fn new(len: usize) -> PhonicResult<Self> {
+        if len < 2 || !len.is_power_of_two() {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let half_len = len / 2;
+        let twiddles = (0..half_len / 2)
+            .map(|i| Complex::from_polar(1.0, -TAU * i as f64 / half_len as f64))
+            .collect();
+
+        let split = (0..half_len)
+            .map(|i| Complex::from_polar(1.0, -TAU * i as f64 / len as f64))
+            .collect();
+
+        Ok(Self {
+            len,
+            twiddles,
+            split,
+            scratch: vec![Complex::ZERO; half_len].into(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn n_bins(&self) -> usize {
+        self.len / 2 + 1
+    }
+
+    /// Computes the unnormalized spectrum of `input` which must contain `len` samples. The
+    /// output must contain `n_bins` elements.
+    pub fn forward(&self, input: &[f64], output: &mut [Complex]) -> PhonicResult<()> {
+        if input.len() != self.len || output.len() != self.n_bins() {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let half_len = self.len / 2;
+        let z = &mut output[..half_len];
+        z.iter_mut()
+            .zip(input.chunks_exact(2))
+            .for_each(|(z, x)| *z = Complex::new(x[0], x[1]));
+
+        self.transform(z);
+
+        let z0 = output[0];
+        output[0] = Complex::new(z0.re + z0.im, 0.0);
+
+        for k in 1..=half_len / 2 {
+            let a = output[k];
+            let b = output[half_len - k];
+
+            output[k] = self.split_bin(a, b, k);
+            output[half_len - k] = self.split_bin(b, a, half_len - k);
+        }
+
+        output[half_len] = Complex::new(z0.re - z0.im, 0.0);
+        Ok(())
+    }
+
+    #[inline]
+    fn split_bin(&self, a: Complex, b: Complex, k: usize) -> Complex {
+        let even = (a + b.conj()) * 0.5;
+        let odd = (a - b.conj()) * Complex::new(0.0, -0.5);
+
+        even + self.split[k] * odd
+    }
+
+    /// Computes the signal of the spectrum `input`, which must contain `n_bins` elements, scaled
+    /// such that it is the exact inverse of `forward`. The output must contain `len` samples.
+    pub fn inverse(&mut self, input: &[Complex], output: &mut [f64]) -> PhonicResult<()> {
+        if input.len() != self.n_bins() || output.len() != self.len {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let half_len = self.len / 2;
+        for k in 0..half_len {
+            let a = input[k];
+            let b = input[half_len - k].conj();
+
+            let even = (a + b) * 0.5;
+            let odd = (a - b) * 0.5 * self.split[k].conj();
+            self.scratch[k] = even + Complex::I * odd;
+        }
+
+        let mut z = std::mem::take(&mut self.scratch);
+        z.iter_mut().for_each(|z| *z = z.conj());
+        self.transform(&mut z);
+
+        let scale = 1.0 / half_len as f64;
+        output.chunks_exact_mut(2).zip(z.iter()).for_each(|(x, z)| {
+            x[0] = z.re * scale;
+            x[1] = -z.im * scale;
+        });
+
+        self.scratch = z;
+        Ok(())
+    }
+
+    /// An in place radix-2 decimation in time transform of the half length
+    fn transform(&self, buf: &mut [Complex]) {
+        let n = buf.len();
+        if n < 2 {
+            return;
+        }
+
+        let bits = n.trailing_zeros();
+        for i in 0..n {
+            let j = i.reverse_bits() >> (usize::BITS - bits);
+            if i < j {
+                buf.swap(i, j);
+            }
+        }
+
+        let mut size = 2;
+        while size <= n {
+            let half = size / 2;
+            let step = n / size;
+
+            for chunk in buf.chunks_exact_mut(size) {
+                let (lower, upper) = chunk.split_at_mut(half);
+                for (j, (a, b)) in lower.iter_mut().zip(upper.iter_mut()).enumerate() {
+                    let t = self.twiddles[j * step] * *b;
+                    *b = *a - t;
+                    *a += t;
+                }
+            }
+
+            size *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spectral::{Complex, RealFft};
+    use std::f64::consts::TAU;
+
+    fn input(len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|i| ((i * 7919) % 113) as f64 / 113.0 - 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn forward_matches_dft() {
+        for len in [2, 4, 8, 16, 64, 512] {
+            let x = input(len);
+            let fft = RealFft::new(len).unwrap();
+
+            let mut spectrum = vec![Complex::ZERO; fft.n_bins()];
+            fft.forward(&x, &mut spectrum).unwrap();
+
+            for (k, bin) in spectrum.iter().enumerate() {
+                let expected = x
+                    .iter()
+                    .enumerate()
+                    .map(|(n, x)| Complex::from_polar(*x, -TAU * (k * n) as f64 / len as f64))
+                    .fold(Complex::ZERO, |acc, x| acc + x);
+
+                assert!((*bin - expected).norm() < 1e-9, "len {len}, bin {k}");
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_reconstructs_input() {
+        for len in [2, 4, 32, 1024] {
+            let x = input(len);
+            let mut fft = RealFft::new(len).unwrap();
+
+            let mut spectrum = vec![Complex::ZERO; fft.n_bins()];
+            let mut output = vec![0.0; len];
+            fft.forward(&x, &mut spectrum).unwrap();
+            fft.inverse(&spectrum, &mut output).unwrap();
+
+            x.iter()
+                .zip(&output)
+                .for_each(|(a, b)| assert!((a - b).abs() < 1e-12));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_lengths() {
+        assert!(RealFft::new(0).is_err());
+        assert!(RealFft::new(1).is_err());
+        assert!(RealFft::new(48).is_err());
+    }
+}
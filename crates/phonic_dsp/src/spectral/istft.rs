@@ -0,0 +1,225 @@
+use crate::{
+    ops::IntoSample,
+    spectral::{RealFft, StftFrame, StftParams},
+};
+use phonic_signal::{PhonicError, PhonicResult, Sample, Signal, SignalReader, SignalSpec};
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+/// An inverse short-time fourier transform which resynthesizes a signal from the frames of an
+/// `Stft` using weighted overlap-add. Frames produced by an `Stft` with the same parameters can
+/// be modified before resynthesis to apply spectral effects.
+pub struct Istft<I, S> {
+    frames: I,
+    spec: SignalSpec,
+    params: StftParams,
+    fft: RealFft,
+    window: Box<[f64]>,
+
+    /// the reciprocal of the summed squared windows at each offset within a hop
+    norm: Box<[f64]>,
+    overlap: Vec<f64>,
+    channel_buf: Box<[f64]>,
+
+    output: Vec<f64>,
+    output_pos: usize,
+    n_skip: usize,
+
+    _sample: PhantomData<S>,
+}
+
+impl<I, S> Istft<I, S> {
+    pub fn new(frames: I, spec: SignalSpec, params: StftParams) -> PhonicResult<Self> {
+        params.validate()?;
+
+        let window = params.window.periodic(params.fft_len);
+        let norm = (0..params.hop_len)
+            .map(|i| {
+                let sum = window
+                    .iter()
+                    .skip(i)
+                    .step_by(params.hop_len)
+                    .map(|w| w * w)
+                    .sum::<f64>();
+
+                if sum > f64::EPSILON {
+                    1.0 / sum
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            frames,
+            spec,
+            params,
+            fft: RealFft::new(params.fft_len)?,
+            window,
+
+            norm,
+            overlap: vec![0.0; params.fft_len * spec.n_channels],
+            channel_buf: vec![0.0; params.fft_len].into(),
+
+            output: Vec::with_capacity(params.hop_len * spec.n_channels),
+            output_pos: 0,
+            n_skip: params.padding(),
+
+            _sample: PhantomData,
+        })
+    }
+
+    pub fn params(&self) -> &StftParams {
+        &self.params
+    }
+
+    pub fn as_inner(&self) -> &I {
+        &self.frames
+    }
+
+    pub fn into_inner(self) -> I {
+        self.frames
+    }
+
+    fn synthesize(&mut self, frame: &StftFrame) -> PhonicResult<()> {
+        let n_channels = self.spec.n_channels;
+        if frame.n_channels() != n_channels || frame.n_bins() != self.params.n_bins() {
+            return Err(PhonicError::param_mismatch());
+        }
+
+        for (channel, bins) in frame.channels().enumerate() {
+            self.fft.inverse(bins, &mut self.channel_buf)?;
+
+            self.overlap
+                .iter_mut()
+                .skip(channel)
+                .step_by(n_channels)
+                .zip(self.channel_buf.iter().zip(self.window.iter()))
+                .for_each(|(acc, (x, w))| *acc += x * w);
+        }
+
+        let hop_len = self.params.hop_len;
+        let n_complete = hop_len * n_channels;
+
+        self.output.clear();
+        self.output_pos = 0;
+        self.output.extend(
+            self.overlap[..n_complete]
+                .chunks_exact(n_channels)
+                .zip(self.norm.iter())
+                .flat_map(|(frame, norm)| frame.iter().map(move |s| s * norm)),
+        );
+
+        let n_skipped = self.n_skip.min(hop_len);
+        self.output_pos = n_skipped * n_channels;
+        self.n_skip -= n_skipped;
+
+        self.overlap.drain(..n_complete);
+        self.overlap.resize(self.params.fft_len * n_channels, 0.0);
+
+        Ok(())
+    }
+}
+
+impl<I, S: Sample> Signal for Istft<I, S> {
+    type Sample = S;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+impl<I, S> SignalReader for Istft<I, S>
+where
+    I: Iterator<Item = PhonicResult<StftFrame>>,
+    S: Sample,
+    f64: IntoSample<S>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        while self.output_pos >= self.output.len() {
+            let Some(frame) = self.frames.next() else {
+                return Ok(0);
+            };
+
+            self.synthesize(&frame?)?;
+        }
+
+        let available = &self.output[self.output_pos..];
+        let n_samples = buf.len().min(available.len());
+        let n_samples = n_samples - n_samples % self.spec.n_channels;
+
+        buf.iter_mut()
+            .zip(&available[..n_samples])
+            .for_each(|(out, s)| {
+                out.write(s.into_sample());
+            });
+
+        self.output_pos += n_samples;
+        Ok(n_samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::spectral::{Istft, Stft, StftParams, Window};
+    use phonic_signal::{
+        utils::{Cursor, SignalUtilsExt},
+        SignalSpec,
+    };
+
+    fn round_trip(params: StftParams, n_frames: usize) {
+        let spec = SignalSpec::stereo(48000);
+        let expected = (0..n_frames * 2)
+            .map(|i| ((i * 7919) % 113) as f64 / 113.0 - 0.5)
+            .collect::<Vec<f64>>();
+
+        let stft = Stft::new(Cursor::new(spec, expected.clone()), params).unwrap();
+        let istft = Istft::<_, f64>::new(stft, spec, params).unwrap();
+        let output: Vec<f64> = istft.polled().read_all_into().unwrap();
+
+        assert!(output.len() >= expected.len());
+        assert!(output.len() < expected.len() + params.hop_len * 2);
+        expected
+            .iter()
+            .zip(&output)
+            .enumerate()
+            .for_each(|(i, (a, b))| assert!((a - b).abs() < 1e-9, "sample {i}: {a} != {b}"));
+    }
+
+    #[test]
+    fn istft_reconstructs_stft_input() {
+        for window in [
+            Window::Hann,
+            Window::Hamming,
+            Window::BlackmanHarris,
+            Window::Kaiser { beta: 8.0 },
+        ] {
+            round_trip(StftParams::new(256, 64, window).unwrap(), 1000);
+            round_trip(StftParams::new(512, 128, window).unwrap(), 100);
+        }
+
+        round_trip(StftParams::new(64, 64, Window::Rectangular).unwrap(), 1001);
+        round_trip(StftParams::new(64, 32, Window::Hamming).unwrap(), 1);
+    }
+
+    #[test]
+    fn stft_frames_resolve_sine_frequency() {
+        let spec = SignalSpec::mono(48000);
+        let buf = (0..48000)
+            .map(|i| (i as f64 / 48000.0 * 3000.0 * std::f64::consts::TAU).sin())
+            .collect::<Vec<f64>>();
+
+        let params = StftParams::new(1024, 256, Window::Hann).unwrap();
+        let mut stft = Stft::new(Cursor::new(spec, buf), params).unwrap();
+        let frame = stft.nth(10).unwrap().unwrap();
+
+        let peak = frame
+            .channel(0)
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.norm().total_cmp(&b.norm()))
+            .map(|(i, _)| i)
+            .unwrap();
+
+        assert_eq!(params.bin_hz(peak, 48000), 3000.0);
+    }
+}
@@ -0,0 +1,11 @@
+mod complex;
+mod fft;
+mod istft;
+mod stft;
+mod window;
+
+pub use complex::*;
+pub use fft::*;
+pub use istft::*;
+pub use stft::*;
+pub use window::*;
@@ -0,0 +1,220 @@
+use crate::{
+    ops::IntoSample,
+    spectral::{Complex, RealFft, Window},
+};
+use phonic_signal::{PhonicError, PhonicResult, Signal, SignalExt, SignalReader, SignalSpec};
+use std::mem::MaybeUninit;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StftParams {
+    pub fft_len: usize,
+    pub hop_len: usize,
+    pub window: Window,
+}
+
+/// The spectra of all channels of a signal for a single analysis window
+#[derive(Debug, Clone, PartialEq)]
+pub struct StftFrame {
+    pos: i64,
+    n_bins: usize,
+    bins: Box<[Complex]>,
+}
+
+/// A short-time fourier transform of a signal. Each item is the spectrum of a window starting
+/// `hop_len` frames after the previous one. The signal is padded with `fft_len - hop_len` frames
+/// of silence at the start and enough silence at the end that every frame of the signal is
+/// covered by the same number of windows, which allows for perfect reconstruction by `Istft`.
+pub struct Stft<T: Signal> {
+    inner: T,
+    params: StftParams,
+    fft: RealFft,
+    window: Box<[f64]>,
+
+    buf: Box<[MaybeUninit<T::Sample>]>,
+    frames: Vec<f64>,
+    frames_start: i64,
+    channel_buf: Box<[f64]>,
+
+    n_read: i64,
+    exhausted: bool,
+}
+
+impl StftParams {
+    pub fn new(fft_len: usize, hop_len: usize, window: Window) -> PhonicResult<Self> {
+        let params = Self {
+            fft_len,
+            hop_len,
+            window,
+        };
+
+        params.validate()?;
+        Ok(params)
+    }
+
+    pub fn n_bins(&self) -> usize {
+        self.fft_len / 2 + 1
+    }
+
+    /// The center frequency of a bin in hz
+    pub fn bin_hz(&self, bin: usize, sample_rate: usize) -> f64 {
+        bin as f64 * sample_rate as f64 / self.fft_len as f64
+    }
+
+    pub(super) fn validate(&self) -> PhonicResult<()> {
+        if self.fft_len < 2 || !self.fft_len.is_power_of_two() {
+            return Err(PhonicError::invalid_input());
+        }
+
+        if self.hop_len == 0 || self.hop_len > self.fft_len {
+            return Err(PhonicError::invalid_input());
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn padding(&self) -> usize {
+        self.fft_len - self.hop_len
+    }
+}
+
+impl StftFrame {
+    pub fn new(pos: i64, n_channels: usize, n_bins: usize) -> Self {
+        Self {
+            pos,
+            n_bins,
+            bins: vec![Complex::ZERO; n_channels * n_bins].into(),
+        }
+    }
+
+    /// The position of the start of the analysis window in frames of the analyzed signal. The
+    /// leading windows start before the signal.
+    pub fn pos(&self) -> i64 {
+        self.pos
+    }
+
+    pub fn n_channels(&self) -> usize {
+        self.bins.len() / self.n_bins
+    }
+
+    pub fn n_bins(&self) -> usize {
+        self.n_bins
+    }
+
+    pub fn channel(&self, channel: usize) -> &[Complex] {
+        &self.bins[channel * self.n_bins..(channel + 1) * self.n_bins]
+    }
+
+    pub fn channel_mut(&mut self, channel: usize) -> &mut [Complex] {
+        &mut self.bins[channel * self.n_bins..(channel + 1) * self.n_bins]
+    }
+
+    pub fn channels(&self) -> impl Iterator<Item = &[Complex]> {
+        self.bins.chunks_exact(self.n_bins)
+    }
+
+    pub fn channels_mut(&mut self) -> impl Iterator<Item = &mut [Complex]> {
+        self.bins.chunks_exact_mut(self.n_bins)
+    }
+}
+
+impl<T: Signal> Stft<T> {
+    pub fn new(inner: T, params: StftParams) -> PhonicResult<Self> {
+        params.validate()?;
+
+        let n_channels = inner.spec().n_channels;
+        let padding = params.padding();
+
+        Ok(Self {
+            inner,
+            params,
+            fft: RealFft::new(params.fft_len)?,
+            window: params.window.periodic(params.fft_len),
+
+            buf: Box::new_uninit_slice(params.hop_len * n_channels),
+            frames: vec![0.0; padding * n_channels],
+            frames_start: -(padding as i64),
+            channel_buf: vec![0.0; params.fft_len].into(),
+
+            n_read: 0,
+            exhausted: false,
+        })
+    }
+
+    pub fn spec(&self) -> &SignalSpec {
+        self.inner.spec()
+    }
+
+    pub fn params(&self) -> &StftParams {
+        &self.params
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn n_buffered(&self) -> usize {
+        self.frames.len() / self.inner.spec().n_channels
+    }
+
+    fn analyze(&mut self) -> StftFrame {
+        let n_channels = self.inner.spec().n_channels;
+        let mut frame = StftFrame::new(self.frames_start, n_channels, self.params.n_bins());
+
+        for (channel, bins) in frame.channels_mut().enumerate() {
+            let samples = self.frames.iter().skip(channel).step_by(n_channels);
+            self.channel_buf
+                .iter_mut()
+                .zip(self.window.iter())
+                .zip(samples.chain(std::iter::repeat(&0.0)))
+                .for_each(|((x, w), s)| *x = w * s);
+
+            self.fft
+                .forward(&self.channel_buf, bins)
+                .expect("analysis buffers should match the fft length");
+        }
+
+        let hop_len = self.params.hop_len.min(self.n_buffered());
+        self.frames.drain(..hop_len * n_channels);
+        self.frames_start += self.params.hop_len as i64;
+
+        frame
+    }
+}
+
+impl<T> Iterator for Stft<T>
+where
+    T: SignalReader,
+    T::Sample: IntoSample<f64>,
+{
+    type Item = PhonicResult<StftFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n_channels = self.inner.spec().n_channels;
+
+        while !self.exhausted && self.n_buffered() < self.params.fft_len {
+            let n_needed = (self.params.fft_len - self.n_buffered()) * n_channels;
+            let buf_len = n_needed.min(self.buf.len());
+            let buf = &mut self.buf[..buf_len];
+
+            match self.inner.read_init(buf) {
+                Ok([]) => self.exhausted = true,
+                Ok(samples) => {
+                    self.frames
+                        .extend(samples.iter().map(|s| IntoSample::<f64>::into_sample(*s)));
+                    self.n_read += (samples.len() / n_channels) as i64;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        if self.exhausted && self.frames_start >= self.n_read {
+            return None;
+        }
+
+        Some(Ok(self.analyze()))
+    }
+}
@@ -0,0 +1,62 @@
+use std::f64::consts::TAU;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    BlackmanHarris,
+    Kaiser { beta: f64 },
+}
+
+impl Window {
+    /// Computes the periodic form of the window, which is the form used for spectral analysis
+    /// since it overlaps evenly at hop sizes that divide the length.
+    pub fn periodic(&self, len: usize) -> Box<[f64]> {
+        (0..len).map(|i| self.at(i as f64 / len as f64)).collect()
+    }
+
+    /// Computes the symmetric form of the window, which is the form used for filter design.
+    pub fn symmetric(&self, len: usize) -> Box<[f64]> {
+        if len == 1 {
+            return Box::new([1.0]);
+        }
+
+        (0..len)
+            .map(|i| self.at(i as f64 / (len - 1) as f64))
+            .collect()
+    }
+
+    /// The value of the window at `x` in the range `[0, 1]`
+    pub fn at(&self, x: f64) -> f64 {
+        match self {
+            Self::Rectangular => 1.0,
+            Self::Hann => 0.5 - 0.5 * (TAU * x).cos(),
+            Self::Hamming => 0.54 - 0.46 * (TAU * x).cos(),
+            Self::BlackmanHarris => {
+                0.35875 - 0.48829 * (TAU * x).cos() + 0.14128 * (2.0 * TAU * x).cos()
+                    - 0.01168 * (3.0 * TAU * x).cos()
+            }
+            Self::Kaiser { beta } => {
+                let r = 2.0 * x - 1.0;
+                bessel_i0(beta * (1.0 - r * r).max(0.0).sqrt()) / bessel_i0(*beta)
+            }
+        }
+    }
+}
+
+/// The zeroth order modified bessel function of the first kind
+pub(crate) fn bessel_i0(x: f64) -> f64 {
+    let half_x = x / 2.0;
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let mut k = 1.0;
+
+    while term > sum * 1e-12 {
+        term *= (half_x / k) * (half_x / k);
+        sum += term;
+        k += 1.0;
+    }
+
+    sum
+}
@@ -20,11 +20,9 @@ pub trait SignalList {
             return Err(PhonicError::missing_data());
         }
 
-        let spec = *self.signal(0).spec();
+        let mut spec = *self.signal(0).spec();
         for idx in 1..self.len() {
-            if self.signal(idx).spec() != &spec {
-                return Err(PhonicError::param_mismatch());
-            }
+            spec = spec.merged(self.signal(idx).spec())?;
         }
 
         Ok(spec)
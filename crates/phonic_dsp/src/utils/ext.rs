@@ -1,6 +1,6 @@
-use crate::utils::{Concat, Delay, Repeat, Slice};
+use crate::utils::{Concat, Delay, Interleave, Repeat, Slice, Split};
 use phonic_signal::{
-    utils::{IntoDuration, NFrames},
+    utils::{DefaultSizedBuf, IntoDuration, NFrames, SizedBuf},
     FiniteSignal, IndexedSignal, PhonicResult, Signal,
 };
 
@@ -23,6 +23,13 @@ pub trait DspUtilsExt: Sized + Signal {
         Delay::new_seeked(self, duration)
     }
 
+    fn interleave<T>(self, other: T) -> PhonicResult<Interleave<(Self, T)>>
+    where
+        T: Signal<Sample = Self::Sample>,
+    {
+        Interleave::new((self, other), DefaultSizedBuf::uninit())
+    }
+
     fn repeat_n(self, reps: u32) -> Repeat<Self> {
         Repeat::new(self, reps)
     }
@@ -70,6 +77,14 @@ pub trait DspUtilsExt: Sized + Signal {
     {
         Slice::to_end_offset(self, start)
     }
+
+    fn split_channels(self) -> Vec<Split<Self>> {
+        Split::channels(self)
+    }
+
+    fn split_groups(self, groups: &[usize], capacity: usize) -> PhonicResult<Vec<Split<Self>>> {
+        Split::new(self, groups, capacity)
+    }
 }
 
 impl<T: Signal> DspUtilsExt for T {}
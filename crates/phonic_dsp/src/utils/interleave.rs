@@ -0,0 +1,236 @@
+use crate::types::{SignalList, SignalListMut};
+use phonic_signal::{
+    utils::DefaultSizedBuf, FiniteSignal, IndexedSignal, PhonicError, PhonicResult, Signal,
+    SignalReader, SignalSpec,
+};
+use std::mem::MaybeUninit;
+
+/// Zips the channels of several signals into a single interleaved signal. Every signal must have
+/// the same spec and the output has the channels of each signal in list order. The output ends
+/// when the shortest signal is exhausted.
+pub struct Interleave<T: SignalList, B = DefaultSizedBuf<MaybeUninit<<T as SignalList>::Sample>>> {
+    inner: T,
+    spec: SignalSpec,
+    n_buffered: Box<[usize]>,
+    buf: B,
+
+    /// an error returned by one of the signals during a read that still produced frames, which
+    /// is returned by the next read
+    error: Option<PhonicError>,
+}
+
+impl<T: SignalList, B> Interleave<T, B> {
+    pub fn new(inner: T, buf: B) -> PhonicResult<Self> {
+        let merged = inner.merged_spec()?;
+        let spec = SignalSpec {
+            n_channels: merged.n_channels * inner.len(),
+            layout: None,
+            ..merged
+        };
+
+        Ok(Self {
+            n_buffered: vec![0; inner.len()].into(),
+            inner,
+            spec,
+            buf,
+            error: None,
+        })
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: SignalList, B> Signal for Interleave<T, B> {
+    type Sample = T::Sample;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+impl<T: SignalList, B> IndexedSignal for Interleave<T, B>
+where
+    for<'a> T::Signal<'a>: IndexedSignal,
+{
+    fn pos(&self) -> u64 {
+        self.inner.signal(0).pos() - self.n_buffered[0] as u64
+    }
+}
+
+impl<T: SignalList, B> FiniteSignal for Interleave<T, B>
+where
+    for<'a> T::Signal<'a>: FiniteSignal,
+{
+    fn len(&self) -> u64 {
+        let range = 0..self.inner.len();
+
+        range
+            .map(|i| self.inner.signal(i).len())
+            .min()
+            .unwrap_or_default()
+    }
+}
+
+impl<T, B> SignalReader for Interleave<T, B>
+where
+    T: SignalListMut,
+    for<'a> T::SignalMut<'a>: SignalReader,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
+        let n_signals = self.inner.len();
+        let group_len = self.spec.n_channels / n_signals;
+        let inner_buf = self.buf.as_mut();
+        let plane_len = inner_buf.len() / self.spec.n_channels;
+
+        let n_frames = (buf.len() / self.spec.n_channels).min(plane_len);
+        if n_frames == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let mut error = None;
+        for (i, plane) in inner_buf
+            .chunks_exact_mut(plane_len * group_len)
+            .enumerate()
+        {
+            let n_buffered = self.n_buffered[i];
+            if n_buffered >= n_frames {
+                continue;
+            }
+
+            let uninit = &mut plane[n_buffered * group_len..n_frames * group_len];
+            match self.inner.signal_mut(i).read(uninit) {
+                Ok(0) => break,
+                Ok(n) => self.n_buffered[i] += n / group_len,
+                Err(e) => {
+                    error = Some(e);
+                    break;
+                }
+            }
+        }
+
+        let n_ready = self.n_buffered.iter().copied().min().unwrap_or_default();
+        let n_ready = n_ready.min(n_frames);
+        match error {
+            Some(e) if n_ready == 0 => return Err(e),
+            error => self.error = error,
+        }
+
+        let planes = inner_buf.chunks_exact_mut(plane_len * group_len);
+        for (i, plane) in planes.enumerate() {
+            let groups = plane.chunks_exact(group_len).take(n_ready);
+            for (frame, group) in groups.enumerate() {
+                let start = (frame * n_signals + i) * group_len;
+                buf[start..start + group_len].copy_from_slice(group);
+            }
+
+            let n_buffered = self.n_buffered[i];
+            plane.copy_within(n_ready * group_len..n_buffered * group_len, 0);
+            self.n_buffered[i] -= n_ready;
+        }
+
+        Ok(n_ready * self.spec.n_channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{DspUtilsExt, Interleave};
+    use phonic_signal::{
+        utils::Cursor, PhonicError, PhonicResult, Signal, SignalExt, SignalReader, SignalSpec,
+    };
+    use std::mem::MaybeUninit;
+
+    /// A mono signal of `value` that reads at most `chunk` samples at a time, fails once after
+    /// `n_samples` samples and then ends
+    struct Failing {
+        spec: SignalSpec,
+        value: i32,
+        chunk: usize,
+        n_samples: usize,
+        failed: bool,
+    }
+
+    impl Failing {
+        fn new(value: i32, chunk: usize, n_samples: usize) -> Self {
+            Self {
+                spec: SignalSpec::mono(48000),
+                value,
+                chunk,
+                n_samples,
+                failed: false,
+            }
+        }
+    }
+
+    impl Signal for Failing {
+        type Sample = i32;
+
+        fn spec(&self) -> &SignalSpec {
+            &self.spec
+        }
+    }
+
+    impl SignalReader for Failing {
+        fn read(&mut self, buf: &mut [MaybeUninit<i32>]) -> PhonicResult<usize> {
+            if self.n_samples == 0 {
+                return match std::mem::replace(&mut self.failed, true) {
+                    false => Err(PhonicError::invalid_state()),
+                    true => Ok(0),
+                };
+            }
+
+            let n = buf.len().min(self.chunk).min(self.n_samples);
+            buf[..n].fill(MaybeUninit::new(self.value));
+            self.n_samples -= n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn mono_signals_are_zipped_into_frames() {
+        let spec = SignalSpec::mono(48000);
+        let left = Cursor::new(spec, &[1, 2, 3][..]);
+        let right = Cursor::new(spec, &[-1, -2][..]);
+
+        let mut interleave = left.interleave(right).unwrap();
+        assert_eq!(interleave.spec().n_channels, 2);
+
+        let mut buf = [MaybeUninit::uninit(); 6];
+        assert_eq!(interleave.read_init(&mut buf).unwrap(), [1, -1, 2, -2]);
+        assert_eq!(interleave.read_init(&mut buf).unwrap(), []);
+    }
+
+    #[test]
+    fn sample_rates_must_match() {
+        let left = Cursor::<_, f64>::new(SignalSpec::mono(48000), &[0.0][..]);
+        let right = Cursor::new(SignalSpec::mono(44100), &[0.0][..]);
+
+        let result = Interleave::new(vec![left, right], [MaybeUninit::<f64>::uninit(); 4]);
+        assert!(matches!(result, Err(PhonicError::ParamMismatch { .. })));
+    }
+
+    #[test]
+    fn errors_are_returned_after_a_partial_read() {
+        let signals = vec![Failing::new(1, 1, 8), Failing::new(2, 2, 2)];
+        let mut interleave = Interleave::new(signals, [MaybeUninit::uninit(); 4]).unwrap();
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        assert_eq!(interleave.read_init(&mut buf).unwrap(), [1, 2]);
+        assert_eq!(interleave.read_init(&mut buf).unwrap(), [1, 2]);
+        assert!(matches!(
+            interleave.read(&mut buf),
+            Err(PhonicError::InvalidState { .. })
+        ));
+    }
+}
@@ -2,14 +2,20 @@ mod bus;
 mod concat;
 mod delay;
 mod ext;
+mod interleave;
+mod noise;
 mod osc;
 mod repeat;
 mod slice;
+mod split;
 
 pub use bus::*;
 pub use concat::*;
 pub use delay::*;
 pub use ext::*;
+pub use interleave::*;
+pub use noise::*;
 pub use osc::*;
 pub use repeat::*;
 pub use slice::*;
+pub use split::*;
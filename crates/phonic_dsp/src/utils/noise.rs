@@ -0,0 +1,386 @@
+use crate::ops::IntoSample;
+use phonic_signal::{
+    IndexedSignal, PhonicError, PhonicResult, Sample, Signal, SignalReader, SignalSeeker,
+    SignalSpec,
+};
+use std::{marker::PhantomData, mem::MaybeUninit};
+
+/// The number of white noise rows summed by pink noise. The slowest row changes every
+/// 2^(PINK_ROWS - 1) frames.
+const PINK_ROWS: u32 = 16;
+
+/// The coefficient of the leaky integrator that produces brown noise
+const BROWN_LEAK: f64 = 1.0 - 1.0 / 1024.0;
+
+/// Scales brown noise to a standard deviation of about 0.3
+const BROWN_GAIN: f64 = 23.5;
+
+/// The number of frames brown noise is integrated over to recover its state after a seek
+const BROWN_WARMUP: u64 = 1 << 15;
+
+pub struct Noise {
+    pub amplitude: f64,
+    pub seed: u64,
+}
+
+/// Uniformly distributed noise. Every channel is independent.
+pub struct White<S> {
+    pub spec: SignalSpec,
+    pub _sample: PhantomData<S>,
+
+    pub amplitude: f64,
+    pub seed: u64,
+
+    pub pos: u64,
+}
+
+/// Noise with equal energy per octave using the Voss-McCartney algorithm. Every channel is
+/// independent.
+pub struct Pink<S> {
+    pub spec: SignalSpec,
+    pub _sample: PhantomData<S>,
+
+    pub amplitude: f64,
+    pub seed: u64,
+
+    pub pos: u64,
+}
+
+/// Noise with energy falling 6 dB per octave produced by integrating white noise. Every
+/// channel is independent.
+pub struct Brown<S> {
+    pub spec: SignalSpec,
+    pub _sample: PhantomData<S>,
+
+    pub amplitude: f64,
+    pub seed: u64,
+
+    pos: u64,
+    state: Box<[f64]>,
+}
+
+impl Default for Noise {
+    fn default() -> Self {
+        Self::seed(0)
+    }
+}
+
+impl Noise {
+    pub fn seed(seed: u64) -> Self {
+        Self {
+            amplitude: 1.0,
+            seed,
+        }
+    }
+
+    pub fn amp(mut self, amplitude: f64) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    pub fn white<S>(self, spec: SignalSpec) -> White<S> {
+        White::new(spec, self.amplitude, self.seed)
+    }
+
+    pub fn pink<S>(self, spec: SignalSpec) -> Pink<S> {
+        Pink::new(spec, self.amplitude, self.seed)
+    }
+
+    pub fn brown<S>(self, spec: SignalSpec) -> Brown<S> {
+        Brown::new(spec, self.amplitude, self.seed)
+    }
+}
+
+#[inline]
+fn splitmix(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// A uniformly distributed value in [-1, 1) determined only by its arguments, so noise can be
+/// generated from any position without replaying it.
+#[inline]
+fn uniform(seed: u64, stream: u64, i: u64) -> f64 {
+    let x = splitmix(seed ^ splitmix(stream.wrapping_mul(0xd1b54a32d192ed03) ^ i));
+    (x >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+}
+
+#[inline]
+fn pink(seed: u64, channel: u64, pos: u64) -> f64 {
+    let stream = channel * (PINK_ROWS as u64 + 1);
+    let rows = (0..PINK_ROWS)
+        .map(|row| uniform(seed, stream + row as u64, pos >> row))
+        .sum::<f64>();
+
+    (rows + uniform(seed, stream + PINK_ROWS as u64, pos)) / (PINK_ROWS + 1) as f64
+}
+
+#[inline]
+fn seek_pos(pos: u64, offset: i64) -> PhonicResult<u64> {
+    pos.checked_add_signed(offset)
+        .ok_or(PhonicError::out_of_bounds())
+}
+
+macro_rules! impl_noise {
+    ($($struct:ident),+) => {$(
+        impl<S: Sample> Signal for $struct<S> {
+            type Sample = S;
+
+            fn spec(&self) -> &SignalSpec {
+                &self.spec
+            }
+        }
+
+        impl<S: Sample> IndexedSignal for $struct<S> {
+            fn pos(&self) -> u64 {
+                self.pos
+            }
+        }
+
+        impl<S: Sample> SignalReader for $struct<S>
+        where
+            f64: IntoSample<S>,
+        {
+            fn read(&mut self, buf: &mut [MaybeUninit<S>]) -> PhonicResult<usize> {
+                let frames = buf.chunks_exact_mut(self.spec.n_channels);
+                let n_frames = frames.len();
+
+                for frame in frames {
+                    for (channel, s) in frame.iter_mut().enumerate() {
+                        let sample = self.sample(channel) * self.amplitude;
+                        s.write(sample.into_sample());
+                    }
+
+                    self.pos += 1;
+                }
+
+                Ok(n_frames * self.spec.n_channels)
+            }
+        }
+    )+};
+}
+
+impl_noise!(White, Pink, Brown);
+
+impl<S> White<S> {
+    pub fn new(spec: SignalSpec, amplitude: f64, seed: u64) -> Self {
+        Self {
+            spec,
+            _sample: PhantomData,
+
+            amplitude,
+            seed,
+
+            pos: 0,
+        }
+    }
+
+    #[inline]
+    fn sample(&self, channel: usize) -> f64 {
+        uniform(self.seed, channel as u64, self.pos)
+    }
+}
+
+impl<S> Pink<S> {
+    pub fn new(spec: SignalSpec, amplitude: f64, seed: u64) -> Self {
+        Self {
+            spec,
+            _sample: PhantomData,
+
+            amplitude,
+            seed,
+
+            pos: 0,
+        }
+    }
+
+    #[inline]
+    fn sample(&self, channel: usize) -> f64 {
+        pink(self.seed, channel as u64, self.pos)
+    }
+}
+
+impl<S> Brown<S> {
+    pub fn new(spec: SignalSpec, amplitude: f64, seed: u64) -> Self {
+        Self {
+            state: vec![0.0; spec.n_channels].into(),
+            spec,
+            _sample: PhantomData,
+
+            amplitude,
+            seed,
+
+            pos: 0,
+        }
+    }
+
+    #[inline]
+    fn integrate(&mut self, channel: usize, pos: u64) -> f64 {
+        let white = uniform(self.seed, channel as u64, pos);
+        let state = &mut self.state[channel];
+        *state = (*state * BROWN_LEAK + white * (1.0 - BROWN_LEAK) * BROWN_GAIN).clamp(-1.0, 1.0);
+
+        *state
+    }
+
+    #[inline]
+    fn sample(&mut self, channel: usize) -> f64 {
+        self.integrate(channel, self.pos)
+    }
+}
+
+impl<S: Sample> SignalSeeker for White<S> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        self.pos = seek_pos(self.pos, offset)?;
+        Ok(())
+    }
+}
+
+impl<S: Sample> SignalSeeker for Pink<S> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        self.pos = seek_pos(self.pos, offset)?;
+        Ok(())
+    }
+}
+
+/// The integrator can't be rewound, so seeking brown noise integrates the frames leading up to
+/// the new position until the contribution of any earlier frames is negligible.
+impl<S: Sample> SignalSeeker for Brown<S> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        let pos = seek_pos(self.pos, offset)?;
+        self.state.fill(0.0);
+
+        for i in pos.saturating_sub(BROWN_WARMUP)..pos {
+            for channel in 0..self.spec.n_channels {
+                self.integrate(channel, i);
+            }
+        }
+
+        self.pos = pos;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        spectral::{Complex, RealFft},
+        utils::Noise,
+    };
+    use phonic_signal::{IndexedSignal, SignalReader, SignalSeeker, SignalSpec};
+    use std::mem::MaybeUninit;
+
+    const FFT_LEN: usize = 8192;
+
+    fn read<T: SignalReader<Sample = f64>>(signal: &mut T, n_samples: usize) -> Vec<f64> {
+        let mut buf = vec![MaybeUninit::uninit(); n_samples];
+        let n = signal.read(&mut buf).unwrap();
+        buf[..n]
+            .iter()
+            .map(|s| unsafe { s.assume_init() })
+            .collect()
+    }
+
+    /// The energy below an eighth of the sample rate relative to the energy above it in dB
+    fn tilt_db(samples: &[f64]) -> f64 {
+        let fft = RealFft::new(FFT_LEN).unwrap();
+        let mut spectrum = vec![Complex::ZERO; fft.n_bins()];
+        let (mut low, mut high) = (0.0, 0.0);
+
+        for block in samples.chunks_exact(FFT_LEN) {
+            fft.forward(block, &mut spectrum).unwrap();
+            for (i, x) in spectrum.iter().enumerate().skip(1) {
+                if i < FFT_LEN / 8 {
+                    low += x.norm_sqr();
+                } else {
+                    high += x.norm_sqr();
+                }
+            }
+        }
+
+        10.0 * (low / high).log10()
+    }
+
+    /// The least squares slope of the power spectral density in dB per octave, measured over the
+    /// octaves between FFT bins 16 and 4096
+    fn slope_db_per_octave(samples: &[f64]) -> f64 {
+        let fft = RealFft::new(FFT_LEN).unwrap();
+        let mut spectrum = vec![Complex::ZERO; fft.n_bins()];
+        let mut power = vec![0.0; fft.n_bins()];
+
+        for block in samples.chunks_exact(FFT_LEN) {
+            fft.forward(block, &mut spectrum).unwrap();
+            for (p, x) in power.iter_mut().zip(&spectrum) {
+                *p += x.norm_sqr();
+            }
+        }
+
+        let points = (4..12)
+            .map(|octave| {
+                let bins = &power[1 << octave..2 << octave];
+                let density = bins.iter().sum::<f64>() / bins.len() as f64;
+                (octave as f64, 10.0 * density.log10())
+            })
+            .collect::<Vec<_>>();
+
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+        let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+        let covariance = points
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<f64>();
+        let variance = points
+            .iter()
+            .map(|(x, _)| (x - mean_x).powi(2))
+            .sum::<f64>();
+
+        covariance / variance
+    }
+
+    #[test]
+    fn seeded_and_seekable() {
+        let spec = SignalSpec::stereo(48000);
+        let white = read(&mut Noise::seed(7).white(spec), 2000);
+        assert_eq!(white, read(&mut Noise::seed(7).white(spec), 2000));
+        assert_ne!(white, read(&mut Noise::seed(8).white(spec), 2000));
+        assert_ne!(white[0], white[1]);
+        assert!(white.iter().all(|s| (-1.0..1.0).contains(s)));
+
+        let mut pink = Noise::seed(7).pink(spec);
+        let samples = read(&mut pink, 2000);
+        pink.seek(-500).unwrap();
+        assert_eq!(read(&mut pink, 1000), samples[1000..]);
+
+        let mut brown = Noise::seed(7).amp(0.5).brown(spec);
+        let samples = read(&mut brown, 200000);
+        brown.seek(-50000).unwrap();
+        assert_eq!(brown.pos(), 50000);
+        assert!(read(&mut brown, 100000)
+            .iter()
+            .zip(&samples[100000..])
+            .all(|(a, b)| (a - b).abs() < 1e-9));
+    }
+
+    #[test]
+    fn spectral_tilt() {
+        let spec = SignalSpec::mono(48000);
+        let n_samples = FFT_LEN * 16;
+
+        // white noise has a third as much energy below an eighth of the sample rate as above it
+        let white = tilt_db(&read(&mut Noise::default().white(spec), n_samples));
+        assert!((white - -4.77).abs() < 0.5);
+
+        let pink_samples = read(&mut Noise::default().pink(spec), n_samples);
+        let pink = tilt_db(&pink_samples);
+        assert!(pink > 0.0);
+
+        let slope = slope_db_per_octave(&pink_samples);
+        assert!((slope - -3.01).abs() < 0.3);
+
+        let brown = tilt_db(&read(&mut Noise::default().brown(spec), n_samples));
+        assert!(brown > pink + 10.0);
+    }
+}
@@ -1,9 +1,12 @@
 use crate::ops::IntoSample;
 use phonic_signal::{
-    IndexedSignal, PhonicError, PhonicResult, Sample, Signal, SignalReader, SignalSeeker,
-    SignalSpec,
+    utils::NullSignal, IndexedSignal, PhonicError, PhonicResult, Sample, Signal, SignalExt,
+    SignalReader, SignalSeeker, SignalSpec,
 };
-use std::{f64::consts::PI, marker::PhantomData, mem::MaybeUninit};
+use std::{f64::consts::TAU, marker::PhantomData, mem::MaybeUninit};
+
+/// The number of modulator samples read on the stack at a time
+const MOD_BUF_LEN: usize = 512;
 
 pub struct Osc {
     pub frequency: f64,
@@ -11,6 +14,13 @@ pub struct Osc {
     pub phase: f64,
 }
 
+/// A signal whose first channel modulates the frequency of an oscillator, and the width of a
+/// `Pulse`. A modulated oscillator ends when its modulator ends.
+pub struct Modulation<M> {
+    pub signal: M,
+    pub frequency_depth: f64,
+}
+
 impl Osc {
     pub fn hz(frequency: f64) -> Self {
         Self {
@@ -29,39 +39,136 @@ impl Osc {
         self.phase = phase;
         self
     }
+
+    pub fn pulse<S>(self, spec: SignalSpec, width: f64) -> Pulse<S> {
+        let Self {
+            frequency,
+            amplitude,
+            phase,
+        } = self;
+
+        Pulse::new(spec, frequency, amplitude, phase).width(width)
+    }
+}
+
+#[inline]
+fn wrap(phase: f64) -> f64 {
+    phase - phase.floor()
+}
+
+/// The distance in samples from the discontinuity at phase `at`
+#[inline]
+fn distance(t: f64, at: f64, dt: f64) -> f64 {
+    if dt == 0.0 {
+        return f64::INFINITY;
+    }
+
+    (wrap(t - at + 0.5) - 0.5) / dt
+}
+
+/// The polynomial approximation of the difference between a band-limited and a naive unit step
+#[inline]
+fn poly_blep(x: f64) -> f64 {
+    if x <= -1.0 || x >= 1.0 {
+        0.0
+    } else if x < 0.0 {
+        (x + 1.0) * (x + 1.0) / 2.0
+    } else {
+        -(1.0 - x) * (1.0 - x) / 2.0
+    }
+}
+
+/// The integral of `poly_blep`, which corrects a unit change in slope per sample
+#[inline]
+fn poly_blamp(x: f64) -> f64 {
+    let x = 1.0 - x.abs();
+    if x <= 0.0 {
+        0.0
+    } else {
+        x * x * x / 6.0
+    }
+}
+
+#[inline]
+fn sin_sample(t: f64, _dt: f64) -> f64 {
+    (t * TAU).sin()
+}
+
+#[inline]
+fn tri_sample(t: f64, dt: f64) -> f64 {
+    let naive = if t < 0.25 {
+        4.0 * t
+    } else if t < 0.75 {
+        2.0 - 4.0 * t
+    } else {
+        4.0 * t - 4.0
+    };
+
+    let slope = 8.0 * dt;
+    naive - slope * poly_blamp(distance(t, 0.25, dt)) + slope * poly_blamp(distance(t, 0.75, dt))
+}
+
+#[inline]
+fn saw_sample(t: f64, dt: f64) -> f64 {
+    2.0 * wrap(t + 0.5) - 1.0 - 2.0 * poly_blep(distance(t, 0.5, dt))
+}
+
+#[inline]
+fn ramp_sample(t: f64, dt: f64) -> f64 {
+    -saw_sample(t, dt)
+}
+
+#[inline]
+fn pulse_sample(t: f64, dt: f64, width: f64) -> f64 {
+    let naive = if t < width { 1.0 } else { -1.0 };
+    naive + 2.0 * poly_blep(distance(t, 0.0, dt)) - 2.0 * poly_blep(distance(t, width, dt))
+}
+
+#[inline]
+fn sqr_sample(t: f64, dt: f64) -> f64 {
+    pulse_sample(t, dt, 0.5)
 }
 
 macro_rules! osc {
-    ($($struct:ident : $fn:ident ($self:ident, $buf:ident) $sample:expr);+;) => {
-        $(osc!($struct : ($self, $buf) $sample);)*
+    ($($(#[$attrs:meta])* $struct:ident : $fn:ident => $sample:ident);+;) => {
+        $(osc!($(#[$attrs])* $struct => $sample);)*
 
         impl Osc {
             $(pub fn $fn<S>(self, spec: SignalSpec) -> $struct<S> {
                 let Self { frequency, amplitude, phase } = self;
-
-                $struct {
-                    spec,
-                    _sample: PhantomData,
-
-                    frequency,
-                    amplitude,
-                    phase,
-
-                    pos: 0
-                }
+                $struct::new(spec, frequency, amplitude, phase)
             })+
         }
     };
-    ($struct:ident : ($self:ident, $buf:ident) $sample:expr) => {
-        pub struct $struct<S> {
+    ($(#[$attrs:meta])* $struct:ident => $sample:ident) => {
+        osc!($(#[$attrs])* $struct {});
+
+        impl<S, M> $struct<S, M> {
+            #[inline]
+            fn shape(&self, t: f64, dt: f64, _modulator: f64) -> f64 {
+                $sample(t, dt)
+            }
+        }
+    };
+    (
+        $(#[$attrs:meta])* $struct:ident {
+            $($(#[$field_attrs:meta])* $field:ident: $field_ty:ty = $default:expr),* $(,)?
+        }
+    ) => {
+        $(#[$attrs])*
+        pub struct $struct<S, M = NullSignal<f64>> {
             pub spec: SignalSpec,
             pub _sample: PhantomData<S>,
 
             pub frequency: f64,
             pub amplitude: f64,
             pub phase: f64,
+            $($(#[$field_attrs])* pub $field: $field_ty,)*
 
             pub pos: u64,
+
+            modulation: Option<Modulation<M>>,
+            modulated_phase: f64,
         }
 
         impl<S> $struct<S> {
@@ -73,8 +180,12 @@ macro_rules! osc {
                     frequency,
                     amplitude,
                     phase,
+                    $($field: $default,)*
+
+                    pos: 0,
 
-                    pos: 0
+                    modulation: None,
+                    modulated_phase: wrap(phase),
                 }
             }
 
@@ -82,18 +193,68 @@ macro_rules! osc {
                 Self::new(spec, frequency, 1.0, 0.0)
             }
 
+            /// Modulates the frequency by `depth_hz` times the first channel of `signal`
+            pub fn fm<M: Signal>(self, signal: M, depth_hz: f64) -> PhonicResult<$struct<S, M>> {
+                self.modulate(Modulation {
+                    signal,
+                    frequency_depth: depth_hz,
+                })
+            }
+
+            pub fn modulate<M: Signal>(
+                self,
+                modulation: Modulation<M>,
+            ) -> PhonicResult<$struct<S, M>> {
+                if modulation.signal.spec().sample_rate != self.spec.sample_rate {
+                    return Err(PhonicError::param_mismatch());
+                }
+
+                Ok($struct {
+                    spec: self.spec,
+                    _sample: PhantomData,
+
+                    frequency: self.frequency,
+                    amplitude: self.amplitude,
+                    phase: self.phase,
+                    $($field: self.$field,)*
+
+                    pos: self.pos,
+
+                    modulated_phase: self.unmodulated_phase(),
+                    modulation: Some(modulation),
+                })
+            }
+        }
+
+        impl<S, M> $struct<S, M> {
+            pub fn modulation(&self) -> Option<&Modulation<M>> {
+                self.modulation.as_ref()
+            }
+
             #[inline]
-            fn seconds(&self) -> f64 {
-                self.pos as f64 / self.spec.sample_rate as f64
+            fn unmodulated_phase(&self) -> f64 {
+                wrap(self.pos as f64 / self.spec.sample_rate as f64 * self.frequency + self.phase)
             }
 
             #[inline]
-            fn sample(&$self) -> f64 {
-                $sample
+            fn sample(&mut self, modulator: f64) -> f64 {
+                let sample_rate = self.spec.sample_rate as f64;
+                let Some(modulation) = &self.modulation else {
+                    let dt = (self.frequency / sample_rate).abs();
+                    return self.shape(self.unmodulated_phase(), dt, 0.0) * self.amplitude;
+                };
+
+                let frequency = self.frequency + modulation.frequency_depth * modulator;
+                let dt = frequency / sample_rate;
+
+                let t = self.modulated_phase;
+                self.modulated_phase = wrap(t + dt);
+
+                self.shape(t, dt.abs(), modulator) * self.amplitude
             }
         }
 
-        impl<S: Sample> Signal for $struct<S> {
+        impl<S: Sample, M> Signal for $struct<S, M> {
             type Sample = S;
 
             fn spec(&self) -> &SignalSpec {
@@ -101,36 +262,67 @@ macro_rules! osc {
             }
         }
 
-        impl<S: Sample> IndexedSignal for $struct<S> {
+        impl<S: Sample, M> IndexedSignal for $struct<S, M> {
             fn pos(&self) -> u64 {
                 self.pos
             }
         }
 
-        impl<S: Sample> SignalReader for $struct<S>
+        impl<S: Sample, M> SignalReader for $struct<S, M>
         where
+            M: SignalReader,
+            M::Sample: IntoSample<f64>,
             f64: IntoSample<S>
         {
-            fn read(&mut $self, $buf: &mut [MaybeUninit<S>]) -> PhonicResult<usize> {
-                let frames = $buf.chunks_exact_mut($self.spec.n_channels);
-                let n_frames = frames.len();
+            fn read(&mut self, buf: &mut [MaybeUninit<S>]) -> PhonicResult<usize> {
+                let n_channels = self.spec.n_channels;
+                let frames = buf.chunks_exact_mut(n_channels);
+
+                let Some(modulation) = &mut self.modulation else {
+                    let n_frames = frames.len();
+                    for frame in frames {
+                        let sample = self.sample(0.0).into_sample();
+                        frame.fill(MaybeUninit::new(sample));
+                        self.pos += 1;
+                    }
+
+                    return Ok(n_frames * n_channels);
+                };
+
+                let mut mod_buf = [MaybeUninit::<M::Sample>::uninit(); MOD_BUF_LEN];
+                let mod_channels = modulation.signal.spec().n_channels;
+                let n_mod_frames = frames.len().min(MOD_BUF_LEN / mod_channels);
+                let mod_samples = modulation
+                    .signal
+                    .read_init(&mut mod_buf[..n_mod_frames * mod_channels])?;
 
-                for frame in frames {
-                    let sample = $self.sample().into_sample();
+                let mut n_frames = 0;
+                for (frame, m) in frames.zip(mod_samples.chunks_exact(mod_channels)) {
+                    let sample = self.sample(m[0].into_sample()).into_sample();
                     frame.fill(MaybeUninit::new(sample));
-                    $self.pos += 1;
+                    self.pos += 1;
+                    n_frames += 1;
                 }
 
-                Ok(n_frames * $self.spec.n_channels)
+                Ok(n_frames * n_channels)
             }
         }
 
-        impl<S: Sample> SignalSeeker for $struct<S> {
+        /// Seeking a modulated oscillator seeks the modulator and restarts the phase from where
+        /// it would be without modulation.
+        impl<S: Sample, M: SignalSeeker> SignalSeeker for $struct<S, M> {
             fn seek(&mut self, offset: i64) -> PhonicResult<()> {
-                self.pos = self.pos
+                let pos = self.pos
                     .checked_add_signed(offset)
                     .ok_or(PhonicError::out_of_bounds())?;
 
+                if let Some(modulation) = &mut self.modulation {
+                    modulation.signal.seek(offset)?;
+                }
+
+                self.pos = pos;
+                self.modulated_phase = self.unmodulated_phase();
+
                 Ok(())
             }
         }
@@ -138,29 +330,164 @@ macro_rules! osc {
 }
 
 osc! {
-    Sin: sin(self, buf) {
-        ((self.seconds() * self.frequency + self.phase) * PI * 2.0).sin() * self.amplitude
-    };
+    Sin: sin => sin_sample;
 
-    // Sqr: sqr(self, buf) {
-    //     self.sin_sample().signum() * self.amplitude
-    // };
+    /// A band-limited triangle wave using PolyBLAMP corrections at the corners
+    Tri: tri => tri_sample;
 
-    Tri: tri(self, buf) {
-        (2.0 * self.amplitude / PI)
-            * ((self.seconds() * self.frequency + self.phase) * PI * 2.0)
-                .sin()
-                .asin()
-    };
+    /// A band-limited rising sawtooth wave using PolyBLEP corrections
+    Saw: saw => saw_sample;
+
+    /// A band-limited falling sawtooth wave using PolyBLEP corrections
+    Ramp: ramp => ramp_sample;
+
+    /// A band-limited square wave using PolyBLEP corrections
+    Sqr: sqr => sqr_sample;
+}
+
+osc! {
+    /// A band-limited pulse wave with a variable width using PolyBLEP corrections
+    Pulse {
+        /// The fraction of each cycle spent high
+        width: f64 = 0.5,
+
+        /// How far the first channel of the modulator moves the width
+        width_depth: f64 = 0.0,
+    }
+}
+
+impl<S> Pulse<S> {
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Modulates the width by `depth` times the first channel of `signal`
+    pub fn pwm<M: Signal>(mut self, signal: M, depth: f64) -> PhonicResult<Pulse<S, M>> {
+        self.width_depth = depth;
+        self.modulate(Modulation {
+            signal,
+            frequency_depth: 0.0,
+        })
+    }
+}
+
+impl<S, M> Pulse<S, M> {
+    #[inline]
+    fn shape(&self, t: f64, dt: f64, modulator: f64) -> f64 {
+        let width = (self.width + self.width_depth * modulator).clamp(0.0, 1.0);
+        pulse_sample(t, dt, width)
+    }
+}
 
-    Saw: saw(self, buf) {
-        (2.0 * self.amplitude / PI)
-            * ((self.seconds() * self.frequency + self.phase) * PI)
-                .tan()
-                .atan()
+#[cfg(test)]
+mod tests {
+    use crate::{
+        spectral::{Complex, RealFft},
+        utils::Osc,
     };
+    use phonic_signal::{utils::Cursor, IndexedSignal, SignalReader, SignalSeeker, SignalSpec};
+    use std::mem::MaybeUninit;
 
-    // Ramp: ramp(self, buf) {
-    //     self.saw_p_sample().neg()
-    // };
+    const SAMPLE_RATE: usize = 48000;
+    const FFT_LEN: usize = 4096;
+
+    fn read<T: SignalReader<Sample = f64>>(signal: &mut T, n_samples: usize) -> Vec<f64> {
+        let mut samples = Vec::with_capacity(n_samples);
+        let mut buf = [MaybeUninit::uninit(); 1000];
+
+        while samples.len() < n_samples {
+            let n = (n_samples - samples.len()).min(buf.len());
+            let n = signal.read(&mut buf[..n]).unwrap();
+            if n == 0 {
+                break;
+            }
+
+            samples.extend(buf[..n].iter().map(|s| unsafe { s.assume_init() }));
+        }
+
+        samples
+    }
+
+    /// The energy outside the harmonics of a signal with `bin` periods per FFT_LEN samples,
+    /// relative to the total energy in dB
+    fn alias_db(samples: &[f64], bin: usize) -> f64 {
+        let fft = RealFft::new(FFT_LEN).unwrap();
+        let mut spectrum = vec![Complex::ZERO; fft.n_bins()];
+        fft.forward(samples, &mut spectrum).unwrap();
+
+        let (mut alias, mut total) = (0.0, 0.0);
+        for (i, x) in spectrum.iter().enumerate().skip(1) {
+            total += x.norm_sqr();
+            if i % bin != 0 {
+                alias += x.norm_sqr();
+            }
+        }
+
+        10.0 * (alias / total).log10()
+    }
+
+    #[test]
+    fn band_limited_waveforms() {
+        let bin = 427;
+        let hz = (bin * SAMPLE_RATE) as f64 / FFT_LEN as f64;
+        let spec = SignalSpec::mono(SAMPLE_RATE);
+
+        let naive = (0..FFT_LEN)
+            .map(|i| {
+                let t = i as f64 * hz / SAMPLE_RATE as f64;
+                2.0 * (t + 0.5).fract() - 1.0
+            })
+            .collect::<Vec<_>>();
+
+        let naive_db = alias_db(&naive, bin);
+        assert!(naive_db > -10.0);
+
+        let saw = read(&mut Osc::hz(hz).saw(spec), FFT_LEN);
+        assert!(alias_db(&saw, bin) < -20.0);
+
+        let ramp = read(&mut Osc::hz(hz).ramp(spec), FFT_LEN);
+        assert!(ramp.iter().zip(&saw).all(|(r, s)| *r == -s));
+
+        let sqr = read(&mut Osc::hz(hz).sqr(spec), FFT_LEN);
+        assert!(alias_db(&sqr, bin) < -20.0);
+
+        let pulse = read(&mut Osc::hz(hz).pulse(spec, 0.25), FFT_LEN);
+        assert!(alias_db(&pulse, bin) < -20.0);
+
+        let tri = read(&mut Osc::hz(hz).tri(spec), FFT_LEN);
+        assert!(alias_db(&tri, bin) < -35.0);
+    }
+
+    #[test]
+    fn frequency_modulation() {
+        let spec = SignalSpec::mono(SAMPLE_RATE);
+        let modulator = Cursor::new(spec, vec![1.0; 4800]);
+        let mut modulated = Osc::hz(440.0).saw(spec).fm(modulator, 100.0).unwrap();
+
+        let samples = read(&mut modulated, 10000);
+        let expected = read(&mut Osc::hz(540.0).saw(spec), 4800);
+
+        assert_eq!(samples.len(), 4800);
+        assert_eq!(modulated.pos(), 4800);
+        assert!(samples
+            .iter()
+            .zip(&expected)
+            .all(|(a, b)| (a - b).abs() < 1e-6));
+
+        let modulator = Cursor::<_, f64>::new(SignalSpec::mono(44100), vec![1.0; 4800]);
+        assert!(Osc::hz(440.0).saw::<f64>(spec).fm(modulator, 100.0).is_err());
+    }
+
+    #[test]
+    fn seek() {
+        let spec = SignalSpec::stereo(SAMPLE_RATE);
+        let mut osc = Osc::hz(1000.0).amp(0.5).phase(0.25).pulse(spec, 0.3);
+        let samples = read(&mut osc, 2000);
+
+        osc.seek(-500).unwrap();
+        assert_eq!(osc.pos(), 500);
+        assert_eq!(read(&mut osc, 1000), samples[1000..]);
+        assert!(osc.seek(-2000).is_err());
+    }
 }
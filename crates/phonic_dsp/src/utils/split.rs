@@ -0,0 +1,223 @@
+use phonic_signal::{
+    utils::{copy_to_uninit_slice, slice_as_init, TeeBuf, DEFAULT_BUF_LEN},
+    BlockingSignal, ChannelLayout, FiniteSignal, IndexedSignal, PhonicError, PhonicResult, Signal,
+    SignalReader, SignalSpec,
+};
+use std::{cell::RefCell, mem::MaybeUninit, ops::Range, rc::Rc};
+
+/// One of several readers that each receive a group of channels from a shared interleaved
+/// signal.
+///
+/// Frames are buffered until every live reader has consumed them. When a reader would need to
+/// read past the capacity of the buffer because another reader has fallen behind,
+/// `Err(PhonicError::NotReady)` is returned until the other reader catches up.
+pub struct Split<T: Signal> {
+    shared: Rc<RefCell<SplitState<T>>>,
+    id: usize,
+    spec: SignalSpec,
+}
+
+struct SplitState<T: Signal> {
+    tee: TeeBuf<T>,
+    channels: Box<[Range<usize>]>,
+    scratch: Box<[MaybeUninit<T::Sample>]>,
+}
+
+impl<T: Signal> Split<T> {
+    /// Splits the signal into readers that each receive `groups[i]` adjacent channels. Up to
+    /// `capacity` frames are buffered for readers that fall behind.
+    pub fn new(inner: T, groups: &[usize], capacity: usize) -> PhonicResult<Vec<Self>> {
+        let spec = *inner.spec();
+        if groups.contains(&0) {
+            return Err(PhonicError::invalid_input());
+        }
+
+        if groups.iter().sum::<usize>() != spec.n_channels {
+            return Err(PhonicError::param_mismatch());
+        }
+
+        let channels = groups
+            .iter()
+            .scan(0, |start, len| {
+                *start += len;
+                Some(*start - len..*start)
+            })
+            .collect::<Box<[_]>>();
+
+        let specs = channels
+            .iter()
+            .map(|range| {
+                let positions = spec.layout.as_ref().and_then(ChannelLayout::positions);
+                let layout =
+                    positions.and_then(|p| ChannelLayout::from_positions(&p[range.clone()]).ok());
+
+                SignalSpec {
+                    n_channels: range.len(),
+                    layout,
+                    ..spec
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let shared = Rc::new(RefCell::new(SplitState {
+            tee: TeeBuf::new(inner, channels.len(), capacity)?,
+            channels,
+            scratch: Box::new_uninit_slice(capacity * spec.n_channels),
+        }));
+
+        let readers = specs
+            .into_iter()
+            .enumerate()
+            .map(|(id, spec)| Self {
+                shared: shared.clone(),
+                id,
+                spec,
+            })
+            .collect();
+
+        Ok(readers)
+    }
+
+    /// Splits the signal into a mono reader for each channel
+    pub fn channels(inner: T) -> Vec<Self> {
+        let groups = vec![1; inner.spec().n_channels];
+        Self::new(inner, &groups, DEFAULT_BUF_LEN).unwrap()
+    }
+
+    /// The number of frames this reader is behind the reader that is furthest ahead
+    pub fn lag(&self) -> u64 {
+        self.shared.borrow().tee.lag(self.id)
+    }
+}
+
+impl<T: SignalReader> SplitState<T> {
+    fn read(&mut self, id: usize, buf: &mut [MaybeUninit<T::Sample>]) -> PhonicResult<usize> {
+        let n_channels = self.tee.as_inner().spec().n_channels;
+        let channels = self.channels[id].clone();
+        let n_frames = (buf.len() / channels.len()).min(self.scratch.len() / n_channels);
+        if n_frames == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let n_samples = self
+            .tee
+            .read(id, &mut self.scratch[..n_frames * n_channels])?;
+        let samples = unsafe { slice_as_init(&self.scratch[..n_samples]) };
+        let frames = samples.chunks_exact(n_channels);
+
+        for (frame, outer) in frames.zip(buf.chunks_exact_mut(channels.len())) {
+            copy_to_uninit_slice(&frame[channels.clone()], outer);
+        }
+
+        Ok(n_samples / n_channels * channels.len())
+    }
+}
+
+impl<T: Signal> Drop for Split<T> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.try_borrow_mut() {
+            state.tee.release(self.id);
+        }
+    }
+}
+
+impl<T: Signal> Signal for Split<T> {
+    type Sample = T::Sample;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+impl<T: BlockingSignal> BlockingSignal for Split<T> {
+    fn block(&self) {
+        self.shared.borrow().tee.as_inner().block()
+    }
+}
+
+impl<T: IndexedSignal> IndexedSignal for Split<T> {
+    fn pos(&self) -> u64 {
+        self.shared.borrow().tee.pos(self.id)
+    }
+}
+
+impl<T: FiniteSignal> FiniteSignal for Split<T> {
+    fn len(&self) -> u64 {
+        self.shared.borrow().tee.as_inner().len()
+    }
+}
+
+impl<T: SignalReader> SignalReader for Split<T> {
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        self.shared.borrow_mut().read(self.id, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::Split;
+    use phonic_signal::{
+        utils::Cursor, ChannelLayout, ChannelPosition, PhonicError, Signal, SignalExt,
+        SignalReader, SignalSpec,
+    };
+    use std::mem::MaybeUninit;
+
+    #[test]
+    fn readers_receive_their_channels() {
+        let spec = SignalSpec::new(3, 48000).with_layout(ChannelLayout::SURROUND_2_1);
+        let samples = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut readers = Split::new(Cursor::new(spec, &samples[..]), &[2, 1], 8).unwrap();
+        let [front, lfe] = readers.as_mut_slice() else {
+            unreachable!()
+        };
+
+        assert_eq!(front.spec().layout, Some(ChannelLayout::STEREO));
+        assert_eq!(
+            lfe.spec().layout.and_then(|l| l.position(0)),
+            Some(ChannelPosition::LowFrequency)
+        );
+
+        let mut buf = [MaybeUninit::uninit(); 2];
+        assert_eq!(front.read_init(&mut buf).unwrap(), [1, 2]);
+        assert_eq!(lfe.lag(), 1);
+
+        let mut buf = [MaybeUninit::uninit(); 3];
+        assert_eq!(lfe.read_init(&mut buf).unwrap(), [3, 6, 9]);
+        assert_eq!(front.lag(), 2);
+
+        let mut buf = [MaybeUninit::uninit(); 6];
+        assert_eq!(front.read_init(&mut buf).unwrap(), [4, 5, 7, 8]);
+        assert_eq!(front.read_init(&mut buf).unwrap(), []);
+    }
+
+    #[test]
+    fn readers_that_get_too_far_ahead_are_not_ready() {
+        let samples = [0.0; 16];
+        let signal = Cursor::new(SignalSpec::stereo(48000), &samples[..]);
+        let mut readers = Split::new(signal, &[1, 1], 2).unwrap();
+        let [left, right] = readers.as_mut_slice() else {
+            unreachable!()
+        };
+
+        let mut buf = [MaybeUninit::uninit(); 8];
+        assert_eq!(left.read(&mut buf).unwrap(), 2);
+        assert!(matches!(
+            left.read(&mut buf),
+            Err(PhonicError::NotReady { .. })
+        ));
+
+        assert_eq!(right.read(&mut buf).unwrap(), 2);
+        assert_eq!(left.read(&mut buf).unwrap(), 2);
+
+        readers.pop();
+        let mut n_read = 0;
+        loop {
+            match readers[0].read(&mut buf).unwrap() {
+                0 => break,
+                n => n_read += n,
+            }
+        }
+
+        assert_eq!(n_read, 4);
+    }
+}
@@ -0,0 +1,544 @@
+use crate::Node;
+use phonic_dsp::ops::MixSample;
+use phonic_signal::{
+    IndexedSignal, PhonicError, PhonicResult, Sample, Signal, SignalReader, SignalSpec,
+};
+use std::{collections::VecDeque, mem::MaybeUninit};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+/// A connection from an output port of one node to an input port of another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: NodeId,
+    pub output: usize,
+    pub to: NodeId,
+    pub input: usize,
+}
+
+/// A set of nodes pulled in topological order one block at a time. Output ports can be connected
+/// to any number of inputs, and inputs with more than one connection mix their sources the same
+/// way `Mix` does. Unconnected inputs receive silence. Reading the graph processes every node and
+/// returns the samples of the output port selected with `Graph::set_output`. Once every node
+/// connected to a node has ended, the node only processes the frames its sources produced, so
+/// the block in which the sources end is not padded with silence.
+pub struct Graph<S> {
+    spec: SignalSpec,
+    block_len: usize,
+    nodes: Vec<Option<NodeSlot<S>>>,
+    edges: Vec<Edge>,
+    order: Option<Vec<NodeId>>,
+    output: Option<(NodeId, usize)>,
+    pos: u64,
+}
+
+struct NodeSlot<S> {
+    node: Box<dyn Node<Sample = S>>,
+    inputs: Vec<Box<[S]>>,
+    outputs: Vec<Box<[S]>>,
+    ended: bool,
+
+    /// the number of frames of the last block that hold signal
+    n_frames: usize,
+}
+
+impl<S: Sample> Graph<S> {
+    /// Creates an empty graph producing `spec` and processing at most `block_len` frames at a
+    /// time
+    pub fn new(spec: SignalSpec, block_len: usize) -> PhonicResult<Self> {
+        if block_len == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        Ok(Self {
+            spec,
+            block_len,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            order: None,
+            output: None,
+            pos: 0,
+        })
+    }
+
+    pub fn block_len(&self) -> usize {
+        self.block_len
+    }
+
+    pub fn add_node<N>(&mut self, node: N) -> NodeId
+    where
+        N: Node<Sample = S> + 'static,
+    {
+        let buffer = |spec: &SignalSpec| vec![S::ORIGIN; self.block_len * spec.n_channels].into();
+        let slot = NodeSlot {
+            inputs: node.inputs().iter().map(buffer).collect(),
+            outputs: node.outputs().iter().map(buffer).collect(),
+            node: Box::new(node),
+            ended: false,
+            n_frames: 0,
+        };
+
+        self.nodes.push(Some(slot));
+        self.order = None;
+
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Removes a node and every edge connected to it
+    pub fn remove_node(&mut self, id: NodeId) -> PhonicResult<Box<dyn Node<Sample = S>>> {
+        let slot = self
+            .nodes
+            .get_mut(id.0)
+            .and_then(Option::take)
+            .ok_or(PhonicError::not_found())?;
+
+        self.edges.retain(|edge| edge.from != id && edge.to != id);
+        if self.output.is_some_and(|(node, _)| node == id) {
+            self.output = None;
+        }
+
+        self.order = None;
+        Ok(slot.node)
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<&dyn Node<Sample = S>> {
+        self.slot(id).map(|slot| slot.node.as_ref())
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Connects an output port to an input port. Returns `InvalidInput` if the connection would
+    /// create a cycle and `ParamMismatch` if the specs of the ports are not compatible.
+    pub fn connect(
+        &mut self,
+        from: NodeId,
+        output: usize,
+        to: NodeId,
+        input: usize,
+    ) -> PhonicResult<()> {
+        let from_spec = self.output_spec(from, output)?;
+        let to_spec = self.input_spec(to, input)?;
+        if !from_spec.is_compatible(&to_spec) {
+            return Err(PhonicError::param_mismatch());
+        }
+
+        if self.is_reachable(to, from) {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let edge = Edge {
+            from,
+            output,
+            to,
+            input,
+        };
+
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+            self.order = None;
+        }
+
+        Ok(())
+    }
+
+    pub fn disconnect(
+        &mut self,
+        from: NodeId,
+        output: usize,
+        to: NodeId,
+        input: usize,
+    ) -> PhonicResult<()> {
+        let edge = Edge {
+            from,
+            output,
+            to,
+            input,
+        };
+
+        let i = self
+            .edges
+            .iter()
+            .position(|e| *e == edge)
+            .ok_or(PhonicError::not_found())?;
+
+        self.edges.remove(i);
+        self.order = None;
+
+        Ok(())
+    }
+
+    /// Selects the output port read by the graph
+    pub fn set_output(&mut self, node: NodeId, output: usize) -> PhonicResult<()> {
+        if !self.output_spec(node, output)?.is_compatible(&self.spec) {
+            return Err(PhonicError::param_mismatch());
+        }
+
+        self.output = Some((node, output));
+        Ok(())
+    }
+
+    /// Processes every node for a block of `n_frames` frames
+    pub fn process(&mut self, n_frames: usize) -> PhonicResult<()>
+    where
+        S: MixSample,
+    {
+        if n_frames > self.block_len {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let order = match self.order.take() {
+            Some(order) => order,
+            None => self.sort()?,
+        };
+
+        for &id in &order {
+            let mut slot = self.nodes[id.0]
+                .take()
+                .ok_or(PhonicError::invalid_state())?;
+
+            let mut n_connected = 0;
+            let mut n_ended = 0;
+            let mut n_source_frames = 0;
+
+            let input_bufs = slot.inputs.iter_mut().zip(slot.node.inputs());
+            for (input, (buf, spec)) in input_bufs.enumerate() {
+                let buf = &mut buf[..n_frames * spec.n_channels];
+                buf.fill(S::ORIGIN);
+
+                for edge in self.edges.iter().filter(|e| e.to == id && e.input == input) {
+                    let Some(source) = &self.nodes[edge.from.0] else {
+                        continue;
+                    };
+
+                    let source_buf = &source.outputs[edge.output][..buf.len()];
+                    buf.iter_mut()
+                        .zip(source_buf)
+                        .for_each(|(mix, s)| *mix = mix.mix(*s));
+
+                    n_connected += 1;
+                    n_ended += source.ended as usize;
+                    n_source_frames = n_source_frames.max(source.n_frames);
+                }
+            }
+
+            let inputs_ended = n_connected > 0 && n_ended == n_connected;
+            let n_frames = if inputs_ended {
+                n_source_frames
+            } else {
+                n_frames
+            };
+
+            let inputs = slot
+                .inputs
+                .iter()
+                .zip(slot.node.inputs())
+                .map(|(buf, spec)| &buf[..n_frames * spec.n_channels])
+                .collect::<Vec<_>>();
+
+            let mut outputs = slot
+                .outputs
+                .iter_mut()
+                .zip(slot.node.outputs())
+                .map(|(buf, spec)| &mut buf[..n_frames * spec.n_channels])
+                .collect::<Vec<_>>();
+
+            let result = slot.node.process(n_frames, &inputs, &mut outputs);
+            slot.ended = slot.node.is_ended() || inputs_ended;
+
+            let result = result.map(|n| slot.n_frames = n.min(n_frames));
+            self.nodes[id.0] = Some(slot);
+            if let Err(e) = result {
+                self.order = Some(order);
+                return Err(e);
+            }
+        }
+
+        self.order = Some(order);
+        Ok(())
+    }
+
+    fn slot(&self, id: NodeId) -> Option<&NodeSlot<S>> {
+        self.nodes.get(id.0).and_then(Option::as_ref)
+    }
+
+    fn input_spec(&self, id: NodeId, input: usize) -> PhonicResult<SignalSpec> {
+        let slot = self.slot(id).ok_or(PhonicError::not_found())?;
+        slot.node
+            .inputs()
+            .get(input)
+            .copied()
+            .ok_or(PhonicError::not_found())
+    }
+
+    fn output_spec(&self, id: NodeId, output: usize) -> PhonicResult<SignalSpec> {
+        let slot = self.slot(id).ok_or(PhonicError::not_found())?;
+        slot.node
+            .outputs()
+            .get(output)
+            .copied()
+            .ok_or(PhonicError::not_found())
+    }
+
+    /// Returns true if there is a path of edges from `from` to `to`
+    fn is_reachable(&self, from: NodeId, to: NodeId) -> bool {
+        let mut visited = vec![false; self.nodes.len()];
+        let mut stack = vec![from];
+
+        while let Some(id) = stack.pop() {
+            if id == to {
+                return true;
+            }
+
+            if std::mem::replace(&mut visited[id.0], true) {
+                continue;
+            }
+
+            stack.extend(self.edges.iter().filter(|e| e.from == id).map(|e| e.to));
+        }
+
+        false
+    }
+
+    /// Orders the nodes so every node comes after the nodes connected to its inputs
+    fn sort(&self) -> PhonicResult<Vec<NodeId>> {
+        let mut n_incoming = vec![0usize; self.nodes.len()];
+        for edge in &self.edges {
+            n_incoming[edge.to.0] += 1;
+        }
+
+        let mut queue = (0..self.nodes.len())
+            .filter(|i| self.nodes[*i].is_some() && n_incoming[*i] == 0)
+            .map(NodeId)
+            .collect::<VecDeque<_>>();
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+
+            for edge in self.edges.iter().filter(|e| e.from == id) {
+                n_incoming[edge.to.0] -= 1;
+                if n_incoming[edge.to.0] == 0 {
+                    queue.push_back(edge.to);
+                }
+            }
+        }
+
+        if order.len() != self.nodes.iter().flatten().count() {
+            return Err(PhonicError::invalid_state());
+        }
+
+        Ok(order)
+    }
+}
+
+impl<S: Sample> Signal for Graph<S> {
+    type Sample = S;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+impl<S: Sample> IndexedSignal for Graph<S> {
+    fn pos(&self) -> u64 {
+        self.pos
+    }
+}
+
+impl<S: Sample + MixSample> SignalReader for Graph<S> {
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let (node, output) = self.output.ok_or(PhonicError::invalid_state())?;
+        if self.slot(node).is_some_and(|slot| slot.ended) {
+            return Ok(0);
+        }
+
+        let n_channels = self.spec.n_channels;
+        let n_frames = (buf.len() / n_channels).min(self.block_len);
+        self.process(n_frames)?;
+
+        let slot = self.slot(node).ok_or(PhonicError::invalid_state())?;
+        let n_samples = slot.n_frames * n_channels;
+        for (sample, s) in buf[..n_samples].iter_mut().zip(&slot.outputs[output][..]) {
+            sample.write(*s);
+        }
+
+        self.pos += slot.n_frames as u64;
+        Ok(n_samples)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Graph, ProcessorNode, SinkNode, SourceNode};
+    use phonic_dsp::ops::DspOpsExt;
+    use phonic_signal::{
+        utils::Cursor, ChannelLayout, IndexedSignal, PhonicError, PhonicResult, Signal,
+        SignalReader, SignalSpec, SignalWriter,
+    };
+    use std::{cell::RefCell, mem::MaybeUninit, rc::Rc};
+
+    const SPEC: SignalSpec = SignalSpec {
+        sample_rate: 48000,
+        n_channels: 1,
+        layout: None,
+    };
+
+    struct Recorder(Rc<RefCell<Vec<f64>>>);
+
+    impl Signal for Recorder {
+        type Sample = f64;
+
+        fn spec(&self) -> &SignalSpec {
+            &SPEC
+        }
+    }
+
+    impl SignalWriter for Recorder {
+        fn write(&mut self, buf: &[f64]) -> PhonicResult<usize> {
+            self.0.borrow_mut().extend(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> PhonicResult<()> {
+            Ok(())
+        }
+    }
+
+    fn read_block(graph: &mut Graph<f64>) -> Vec<f64> {
+        let mut buf = [MaybeUninit::uninit(); 200];
+        let n = graph.read(&mut buf).unwrap();
+        buf[..n]
+            .iter()
+            .map(|s| unsafe { s.assume_init() })
+            .collect()
+    }
+
+    #[test]
+    fn fan_out_and_mix() {
+        let mut graph = Graph::new(SPEC, 128).unwrap();
+        let source = graph.add_node(SourceNode::new(Cursor::new(SPEC, vec![0.25; 1000])));
+        let gain = graph.add_node(ProcessorNode::new(SPEC, |input| input.gain_amp(2.0)));
+        let bus = graph.add_node(ProcessorNode::new(SPEC, |input| input));
+
+        let recorded = Rc::default();
+        let sink = graph.add_node(SinkNode::new(Recorder(Rc::clone(&recorded))));
+
+        graph.connect(source, 0, gain, 0).unwrap();
+        graph.connect(source, 0, bus, 0).unwrap();
+        graph.connect(gain, 0, bus, 0).unwrap();
+        graph.connect(bus, 0, sink, 0).unwrap();
+        graph.set_output(bus, 0).unwrap();
+
+        let mut samples = Vec::new();
+        loop {
+            let block = read_block(&mut graph);
+            if block.is_empty() {
+                break;
+            }
+
+            assert!(block.len() <= 128);
+            samples.extend(block);
+        }
+
+        assert_eq!(samples.len(), 1000);
+        assert!(samples.iter().all(|s| *s == 0.75));
+        assert_eq!(*recorded.borrow(), samples);
+    }
+
+    #[test]
+    fn final_partial_block_is_not_padded() {
+        let stereo = SignalSpec::stereo(48000);
+        let mut graph = Graph::new(stereo, 64).unwrap();
+        let source = graph.add_node(SourceNode::new(Cursor::new(stereo, vec![0.5; 500])));
+        let gain = graph.add_node(ProcessorNode::new(stereo, |input| input.gain_amp(2.0)));
+
+        graph.connect(source, 0, gain, 0).unwrap();
+        graph.set_output(gain, 0).unwrap();
+
+        let mut samples = Vec::new();
+        loop {
+            let block = read_block(&mut graph);
+            if block.is_empty() {
+                break;
+            }
+
+            samples.extend(block);
+        }
+
+        assert_eq!(samples.len(), 500);
+        assert!(samples.iter().all(|s| *s == 1.0));
+        assert_eq!(graph.pos(), 250);
+    }
+
+    #[test]
+    fn rejects_cycles_and_mismatched_ports() {
+        let mut graph = Graph::<f64>::new(SPEC, 128).unwrap();
+        let a = graph.add_node(ProcessorNode::new(SPEC, |input| input));
+        let b = graph.add_node(ProcessorNode::new(SPEC, |input| input));
+        let stereo = SignalSpec::stereo(48000);
+        let c = graph.add_node(ProcessorNode::new(stereo, |input| input));
+
+        graph.connect(a, 0, b, 0).unwrap();
+        assert!(matches!(
+            graph.connect(b, 0, a, 0),
+            Err(PhonicError::InvalidInput { .. })
+        ));
+
+        assert!(matches!(
+            graph.connect(a, 0, a, 0),
+            Err(PhonicError::InvalidInput { .. })
+        ));
+
+        assert!(matches!(
+            graph.connect(b, 0, c, 0),
+            Err(PhonicError::ParamMismatch { .. })
+        ));
+
+        assert!(matches!(
+            graph.connect(b, 1, c, 0),
+            Err(PhonicError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_layouts_are_compatible_with_any_layout() {
+        let mut graph = Graph::<f64>::new(SPEC, 128).unwrap();
+        let unknown = graph.add_node(ProcessorNode::new(SPEC, |input| input));
+        let center = SPEC.with_layout(ChannelLayout::MONO);
+        let a = graph.add_node(ProcessorNode::new(center, |input| input));
+        let left = SPEC.with_layout(ChannelLayout::from_mask(0x1));
+        let b = graph.add_node(ProcessorNode::new(left, |input| input));
+
+        graph.connect(unknown, 0, a, 0).unwrap();
+        graph.connect(unknown, 0, b, 0).unwrap();
+        graph.set_output(a, 0).unwrap();
+
+        assert!(matches!(
+            graph.connect(a, 0, b, 0),
+            Err(PhonicError::ParamMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn edit_between_blocks() {
+        let mut graph = Graph::new(SPEC, 100).unwrap();
+        let source = graph.add_node(SourceNode::new(Cursor::new(SPEC, vec![0.25; 1000])));
+        let gain = graph.add_node(ProcessorNode::new(SPEC, |input| input.gain_amp(2.0)));
+        let bus = graph.add_node(ProcessorNode::new(SPEC, |input| input));
+
+        graph.connect(source, 0, gain, 0).unwrap();
+        graph.connect(gain, 0, bus, 0).unwrap();
+        graph.set_output(bus, 0).unwrap();
+        assert!(read_block(&mut graph).iter().all(|s| *s == 0.5));
+
+        graph.remove_node(gain).unwrap();
+        assert!(graph.edges().is_empty());
+        assert!(read_block(&mut graph).iter().all(|s| *s == 0.0));
+
+        graph.connect(source, 0, bus, 0).unwrap();
+        assert!(read_block(&mut graph).iter().all(|s| *s == 0.25));
+    }
+}
@@ -0,0 +1,5 @@
+mod graph;
+mod node;
+
+pub use graph::*;
+pub use node::*;
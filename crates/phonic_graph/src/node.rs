@@ -0,0 +1,285 @@
+use phonic_signal::{
+    utils::slice_as_uninit_mut, PhonicError, PhonicResult, Sample, Signal, SignalReader,
+    SignalSpec, SignalWriter,
+};
+use std::{cell::RefCell, collections::VecDeque, mem::MaybeUninit, rc::Rc};
+
+/// A unit of processing in a `Graph`. Every block a node receives one interleaved buffer per
+/// input port and fills one interleaved buffer per output port, each holding the same number of
+/// frames in the spec of its port.
+pub trait Node {
+    type Sample: Sample;
+
+    fn inputs(&self) -> &[SignalSpec];
+    fn outputs(&self) -> &[SignalSpec];
+
+    /// Fills every buffer in `outputs` from the buffers in `inputs`. Every buffer holds
+    /// `n_frames` frames. Returns the number of frames at the start of the outputs that hold
+    /// signal, which is only less than `n_frames` in the block in which the node ends.
+    fn process(
+        &mut self,
+        n_frames: usize,
+        inputs: &[&[Self::Sample]],
+        outputs: &mut [&mut [Self::Sample]],
+    ) -> PhonicResult<usize>;
+
+    /// Returns true once this node will only produce silence regardless of its inputs. Nodes
+    /// with inputs are also considered ended once every node connected to them has ended.
+    fn is_ended(&self) -> bool {
+        false
+    }
+}
+
+/// A node with no inputs that reads its output from a signal. The block in which the signal is
+/// exhausted is padded with silence, which is not counted in the frames returned by `process`.
+pub struct SourceNode<T: Signal> {
+    inner: T,
+    outputs: [SignalSpec; 1],
+    ended: bool,
+}
+
+/// A node with no outputs that writes its input to a signal
+pub struct SinkNode<T: Signal> {
+    inner: T,
+    inputs: [SignalSpec; 1],
+    ended: bool,
+}
+
+/// A node that reads its output from a signal built on top of its input, such as a chain of
+/// ops. The signal must produce a frame for every frame it reads from the `NodeInput`.
+pub struct ProcessorNode<T: Signal> {
+    inner: T,
+    input: NodeInput<T::Sample>,
+    inputs: [SignalSpec; 1],
+    outputs: [SignalSpec; 1],
+}
+
+/// A signal that reads the input of a `ProcessorNode`. Reading returns `NotReady` once the
+/// samples of the current block have been consumed.
+pub struct NodeInput<S> {
+    spec: SignalSpec,
+    buf: Rc<RefCell<VecDeque<S>>>,
+}
+
+impl<T: Signal> SourceNode<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            outputs: [*inner.spec()],
+            inner,
+            ended: false,
+        }
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Signal> SinkNode<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inputs: [*inner.spec()],
+            inner,
+            ended: false,
+        }
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Signal> ProcessorNode<T> {
+    pub fn new<F>(spec: SignalSpec, build: F) -> Self
+    where
+        F: FnOnce(NodeInput<T::Sample>) -> T,
+    {
+        let input = NodeInput {
+            spec,
+            buf: Rc::default(),
+        };
+
+        let inner = build(input.clone());
+
+        Self {
+            outputs: [*inner.spec()],
+            inputs: [spec],
+            inner,
+            input,
+        }
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<S> Clone for NodeInput<S> {
+    fn clone(&self) -> Self {
+        Self {
+            spec: self.spec,
+            buf: self.buf.clone(),
+        }
+    }
+}
+
+impl<T: SignalReader> Node for SourceNode<T> {
+    type Sample = T::Sample;
+
+    fn inputs(&self) -> &[SignalSpec] {
+        &[]
+    }
+
+    fn outputs(&self) -> &[SignalSpec] {
+        &self.outputs
+    }
+
+    fn process(
+        &mut self,
+        _n_frames: usize,
+        _inputs: &[&[Self::Sample]],
+        outputs: &mut [&mut [Self::Sample]],
+    ) -> PhonicResult<usize> {
+        let buf = &mut *outputs[0];
+        let n_read = if self.ended {
+            0
+        } else {
+            read_into(&mut self.inner, buf)?
+        };
+
+        self.ended = n_read < buf.len();
+        buf[n_read..].fill(Self::Sample::ORIGIN);
+
+        Ok(n_read / self.outputs[0].n_channels)
+    }
+
+    fn is_ended(&self) -> bool {
+        self.ended
+    }
+}
+
+impl<T: SignalWriter> Node for SinkNode<T> {
+    type Sample = T::Sample;
+
+    fn inputs(&self) -> &[SignalSpec] {
+        &self.inputs
+    }
+
+    fn outputs(&self) -> &[SignalSpec] {
+        &[]
+    }
+
+    fn process(
+        &mut self,
+        n_frames: usize,
+        inputs: &[&[Self::Sample]],
+        _outputs: &mut [&mut [Self::Sample]],
+    ) -> PhonicResult<usize> {
+        let mut buf = inputs[0];
+        while !buf.is_empty() && !self.ended {
+            match self.inner.write(buf) {
+                Ok(0) => self.ended = true,
+                Ok(n) => buf = &buf[n..],
+                Err(PhonicError::Interrupted { .. }) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(n_frames)
+    }
+
+    fn is_ended(&self) -> bool {
+        self.ended
+    }
+}
+
+impl<T: SignalReader> Node for ProcessorNode<T> {
+    type Sample = T::Sample;
+
+    fn inputs(&self) -> &[SignalSpec] {
+        &self.inputs
+    }
+
+    fn outputs(&self) -> &[SignalSpec] {
+        &self.outputs
+    }
+
+    fn process(
+        &mut self,
+        n_frames: usize,
+        inputs: &[&[Self::Sample]],
+        outputs: &mut [&mut [Self::Sample]],
+    ) -> PhonicResult<usize> {
+        {
+            let mut input = self.input.buf.borrow_mut();
+            input.clear();
+            input.extend(inputs[0]);
+        }
+
+        let buf = &mut *outputs[0];
+        let n_read = match read_into(&mut self.inner, buf) {
+            Ok(n) => n,
+            Err(PhonicError::NotReady { .. }) => 0,
+            Err(e) => return Err(e),
+        };
+
+        buf[n_read..].fill(Self::Sample::ORIGIN);
+        Ok(n_frames)
+    }
+}
+
+impl<S: Sample> Signal for NodeInput<S> {
+    type Sample = S;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+impl<S: Sample> SignalReader for NodeInput<S> {
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let mut input = self.buf.borrow_mut();
+        if input.is_empty() {
+            return Err(PhonicError::not_ready());
+        }
+
+        let mut len = buf.len().min(input.len());
+        len -= len % self.spec.n_channels;
+
+        for (sample, s) in buf[..len].iter_mut().zip(input.drain(..len)) {
+            sample.write(s);
+        }
+
+        Ok(len)
+    }
+}
+
+/// Reads from `signal` until `buf` is full or the signal is exhausted
+fn read_into<T: SignalReader>(signal: &mut T, buf: &mut [T::Sample]) -> PhonicResult<usize> {
+    let buf = slice_as_uninit_mut(buf);
+    let mut n_read = 0;
+
+    while n_read < buf.len() {
+        match signal.read(&mut buf[n_read..]) {
+            Ok(0) => break,
+            Ok(n) => n_read += n,
+            Err(PhonicError::Interrupted { .. }) => continue,
+            Err(PhonicError::NotReady { .. }) if n_read > 0 => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(n_read)
+}
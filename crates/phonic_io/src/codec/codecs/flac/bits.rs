@@ -0,0 +1,361 @@
+use phonic_signal::{PhonicError, PhonicResult};
+
+const CRC8_TABLE: [u8; 256] = crc8_table();
+const CRC16_TABLE: [u16; 256] = crc16_table();
+
+/// Reads big endian bit fields from a byte slice. Reading past the end of the slice returns
+/// `MissingData`, which lets callers distinguish a truncated frame from an invalid one.
+pub(super) struct BitReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+/// Writes big endian bit fields to a byte vector
+pub(super) struct BitWriter<'a> {
+    buf: &'a mut Vec<u8>,
+    acc: u64,
+    n_bits: u32,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Returns the number of whole or partially read bytes
+    pub fn byte_pos(&self) -> usize {
+        self.pos.div_ceil(8)
+    }
+
+    pub fn align(&mut self) {
+        self.pos = self.byte_pos() * 8;
+    }
+
+    #[inline]
+    fn window(&self) -> u64 {
+        let i = self.pos / 8;
+        let mut bytes = [0u8; 8];
+        let end = self.buf.len().min(i + 8);
+        bytes[..end - i].copy_from_slice(&self.buf[i..end]);
+
+        u64::from_be_bytes(bytes) << (self.pos % 8)
+    }
+
+    #[inline]
+    pub fn read(&mut self, n_bits: u32) -> PhonicResult<u64> {
+        debug_assert!(n_bits <= 64);
+        if n_bits == 0 {
+            return Ok(0);
+        }
+
+        if n_bits > 56 {
+            let high = self.read(n_bits - 32)?;
+            let low = self.read(32)?;
+            return Ok(high << 32 | low);
+        }
+
+        if self.pos + n_bits as usize > self.buf.len() * 8 {
+            return Err(PhonicError::missing_data());
+        }
+
+        let value = self.window() >> (64 - n_bits);
+        self.pos += n_bits as usize;
+
+        Ok(value)
+    }
+
+    #[inline]
+    pub fn read_signed(&mut self, n_bits: u32) -> PhonicResult<i64> {
+        if n_bits == 0 {
+            return Ok(0);
+        }
+
+        let value = self.read(n_bits)?;
+        let shift = 64 - n_bits;
+
+        Ok(((value << shift) as i64) >> shift)
+    }
+
+    /// Reads the number of zero bits preceding the next set bit
+    #[inline]
+    pub fn read_unary(&mut self) -> PhonicResult<u64> {
+        let mut n = 0;
+        loop {
+            let remaining = (self.buf.len() * 8).saturating_sub(self.pos);
+            if remaining == 0 {
+                return Err(PhonicError::missing_data());
+            }
+
+            let available = remaining.min(64 - self.pos % 8) as u32;
+            let zeros = self.window().leading_zeros();
+
+            if zeros < available {
+                self.pos += zeros as usize + 1;
+                return Ok(n + zeros as u64);
+            }
+
+            self.pos += available as usize;
+            n += available as u64;
+        }
+    }
+
+    /// Reads a UTF-8 style coded number of up to 36 bits
+    pub fn read_coded_number(&mut self) -> PhonicResult<u64> {
+        let first = self.read(8)? as u8;
+        let n_bytes = first.leading_ones();
+
+        let (mut value, n_continuation) = match n_bytes {
+            0 => return Ok(first as u64),
+            2..=6 => ((first & (0x7F >> n_bytes)) as u64, n_bytes - 1),
+            7 if first == 0xFE => (0, 6),
+            _ => return Err(PhonicError::invalid_data()),
+        };
+
+        for _ in 0..n_continuation {
+            let byte = self.read(8)? as u8;
+            if byte & 0xC0 != 0x80 {
+                return Err(PhonicError::invalid_data());
+            }
+
+            value = value << 6 | (byte & 0x3F) as u64;
+        }
+
+        Ok(value)
+    }
+}
+
+impl<'a> BitWriter<'a> {
+    pub fn new(buf: &'a mut Vec<u8>) -> Self {
+        Self {
+            buf,
+            acc: 0,
+            n_bits: 0,
+        }
+    }
+
+    #[inline]
+    pub fn write(&mut self, value: u64, n_bits: u32) {
+        debug_assert!(n_bits <= 64);
+        if n_bits > 32 {
+            self.write(value >> 32, n_bits - 32);
+            self.write(value & 0xFFFF_FFFF, 32);
+            return;
+        }
+
+        if n_bits == 0 {
+            return;
+        }
+
+        let mask = (1u64 << n_bits) - 1;
+        self.acc = self.acc << n_bits | (value & mask);
+        self.n_bits += n_bits;
+
+        while self.n_bits >= 8 {
+            self.n_bits -= 8;
+            self.buf.push((self.acc >> self.n_bits) as u8);
+        }
+    }
+
+    #[inline]
+    pub fn write_signed(&mut self, value: i64, n_bits: u32) {
+        self.write(value as u64, n_bits)
+    }
+
+    /// Writes `n` zero bits followed by a set bit
+    #[inline]
+    pub fn write_unary(&mut self, mut n: u64) {
+        while n >= 32 {
+            self.write(0, 32);
+            n -= 32;
+        }
+
+        self.write(1, n as u32 + 1);
+    }
+
+    pub fn write_coded_number(&mut self, value: u64) {
+        debug_assert!(value < 1 << 36);
+        if value < 0x80 {
+            self.write(value, 8);
+            return;
+        }
+
+        let n_continuation = match value {
+            0x80..0x800 => 1,
+            0x800..0x10000 => 2,
+            0x10000..0x200000 => 3,
+            0x200000..0x4000000 => 4,
+            0x4000000..0x80000000 => 5,
+            _ => 6,
+        };
+
+        let prefix = !(0xFFu8 >> (n_continuation + 1));
+        let first = prefix as u64 | value >> (6 * n_continuation);
+        self.write(first & 0xFF, 8);
+
+        for i in (0..n_continuation).rev() {
+            self.write(0x80 | (value >> (6 * i)) & 0x3F, 8);
+        }
+    }
+
+    /// Pads the last byte with zero bits
+    pub fn align(&mut self) {
+        if self.n_bits > 0 {
+            self.write(0, 8 - self.n_bits);
+        }
+    }
+}
+
+impl Drop for BitWriter<'_> {
+    fn drop(&mut self) {
+        self.align()
+    }
+}
+
+const fn crc8_table() -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = i as u8;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 0x80 != 0 {
+                crc << 1 ^ 0x07
+            } else {
+                crc << 1
+            };
+
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+const fn crc16_table() -> [u16; 256] {
+    let mut table = [0u16; 256];
+    let mut i = 0;
+
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                crc << 1 ^ 0x8005
+            } else {
+                crc << 1
+            };
+
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+}
+
+pub(super) fn crc8(buf: &[u8]) -> u8 {
+    buf.iter()
+        .fold(0, |crc, byte| CRC8_TABLE[(crc ^ byte) as usize])
+}
+
+#[inline]
+pub(super) fn crc16_update(crc: u16, byte: u8) -> u16 {
+    crc << 8 ^ CRC16_TABLE[((crc >> 8) as u8 ^ byte) as usize]
+}
+
+pub(super) fn crc16(buf: &[u8]) -> u16 {
+    buf.iter().fold(0, |crc, byte| crc16_update(crc, *byte))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc16, crc8, BitReader, BitWriter};
+
+    #[test]
+    fn written_fields_are_read_back() {
+        let fields = [
+            (0b101, 3),
+            (0, 0),
+            (0x1234_5678_9ABC_DEF0, 64),
+            (1, 1),
+            (0x3FF, 10),
+        ];
+        let signed = [(-1, 1), (-5, 4), (7, 4), (i32::MIN as i64, 32), (-3, 33)];
+        let unary = [0, 1, 31, 32, 100];
+
+        let mut buf = Vec::new();
+        let mut writer = BitWriter::new(&mut buf);
+        for (value, n_bits) in fields {
+            writer.write(value, n_bits);
+        }
+
+        for (value, n_bits) in signed {
+            writer.write_signed(value, n_bits);
+        }
+
+        for n in unary {
+            writer.write_unary(n);
+        }
+
+        drop(writer);
+
+        let mut reader = BitReader::new(&buf);
+        for (value, n_bits) in fields {
+            assert_eq!(reader.read(n_bits).unwrap(), value);
+        }
+
+        for (value, n_bits) in signed {
+            assert_eq!(reader.read_signed(n_bits).unwrap(), value);
+        }
+
+        for n in unary {
+            assert_eq!(reader.read_unary().unwrap(), n);
+        }
+
+        // the last byte is padded with zero bits
+        reader.align();
+        assert_eq!(reader.byte_pos(), buf.len());
+        assert!(reader.read(1).is_err());
+    }
+
+    #[test]
+    fn coded_numbers_match_utf8() {
+        for c in [
+            '\0',
+            'a',
+            '\u{7F}',
+            '\u{80}',
+            'ß',
+            '\u{7FF}',
+            '\u{800}',
+            '€',
+            '\u{10FFFF}',
+        ] {
+            let mut buf = Vec::new();
+            BitWriter::new(&mut buf).write_coded_number(c as u64);
+            assert_eq!(buf, c.to_string().as_bytes());
+            assert_eq!(BitReader::new(&buf).read_coded_number().unwrap(), c as u64);
+        }
+
+        // the longest forms exceed the range of unicode
+        for value in [0x20_0000, 0x400_0000, 0x8000_0000, (1 << 36) - 1] {
+            let mut buf = Vec::new();
+            BitWriter::new(&mut buf).write_coded_number(value);
+            assert_eq!(BitReader::new(&buf).read_coded_number().unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn crcs_match_the_check_values() {
+        assert_eq!(crc8(b"123456789"), 0xF4);
+        assert_eq!(crc16(b"123456789"), 0xFEE8);
+    }
+}
@@ -0,0 +1,481 @@
+use crate::{
+    codecs::flac::{decode_frame, FlacCodecTag, FlacCompression, FlacSample, FrameEncoder},
+    CodecFromSignal, CodecFromStream, CodecTag, FiniteStream, IndexedStream, Stream, StreamReader,
+    StreamSeeker, StreamSpec, StreamSpecBuilder, StreamWriter, WakingStream,
+};
+use phonic_signal::{
+    utils::slice_as_uninit_mut, FiniteSignal, IndexedSignal, PhonicError, PhonicResult, Sample,
+    Signal, SignalReader, SignalSeeker, SignalSpec, SignalWriter, WakingSignal,
+};
+use std::{marker::PhantomData, mem::MaybeUninit, task::Waker};
+
+/// The number of encoded bytes requested from the inner stream at a time while decoding
+const READ_LEN: usize = 1 << 14;
+
+/// Decodes a flac stream into a signal, or encodes a signal into a flac stream. Samples written
+/// to the codec are buffered until a block of `FlacCompression::block_size` frames is complete.
+/// `SignalWriter::flush` encodes the remaining partial block, so it has to be called before the
+/// inner stream is finalized.
+pub struct FlacCodec<T, S: Sample, C: CodecTag = FlacCodecTag> {
+    inner: T,
+    spec: StreamSpec<C>,
+    bits_per_sample: u32,
+    encoder: FrameEncoder,
+
+    /// Encoded bytes that have been read from the inner stream but not decoded, or that have
+    /// been encoded but not yet read or written.
+    bytes: Vec<u8>,
+    n_bytes_consumed: usize,
+
+    /// The channels of the current decoded frame and the number of frames that have been read
+    /// from it. While encoding `block_pos` is the number of samples buffered for the next frame.
+    channels: Vec<Vec<i64>>,
+    samples: Vec<S>,
+    block_bits: u32,
+    block_len: usize,
+    block_pos: usize,
+
+    /// The number of frames to discard after a seek
+    n_skip: u64,
+
+    /// The number of interleaved samples that have been decoded, encoded or buffered for encoding
+    pos: u64,
+    _sample: PhantomData<S>,
+}
+
+impl<T, S: Sample, C: CodecTag> FlacCodec<T, S, C> {
+    fn new(inner: T, spec: StreamSpec<C>) -> PhonicResult<Self> {
+        let bits_per_sample =
+            FlacCodecTag::bits_per_sample(spec.sample).ok_or(PhonicError::unsupported())?;
+
+        Ok(Self {
+            inner,
+            spec,
+            bits_per_sample,
+            encoder: FrameEncoder::new(FlacCompression::default()),
+
+            bytes: Vec::new(),
+            n_bytes_consumed: 0,
+
+            channels: Vec::new(),
+            samples: Vec::new(),
+            block_bits: bits_per_sample,
+            block_len: 0,
+            block_pos: 0,
+
+            n_skip: 0,
+            pos: 0,
+            _sample: PhantomData,
+        })
+    }
+
+    pub fn with_compression(mut self, compression: FlacCompression) -> PhonicResult<Self> {
+        compression.validate()?;
+        self.encoder = FrameEncoder::new(compression);
+
+        Ok(self)
+    }
+
+    pub fn compression(&self) -> &FlacCompression {
+        self.encoder.compression()
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Encodes interleaved samples as a frame starting at the frame `first_sample`
+    fn encode(&mut self, samples: &[S], first_sample: u64)
+    where
+        S: FlacSample,
+    {
+        let n_channels = self.spec.decoded.n_channels;
+        self.encoder.encode(
+            samples,
+            first_sample,
+            n_channels,
+            self.spec.decoded.sample_rate,
+            self.bits_per_sample,
+            &mut self.bytes,
+        );
+    }
+
+    /// Encodes the samples buffered by `SignalWriter::write` as a frame
+    fn encode_buffered(&mut self)
+    where
+        S: FlacSample,
+    {
+        if self.block_pos == 0 {
+            return;
+        }
+
+        let n_channels = self.spec.decoded.n_channels as u64;
+        let first_sample = (self.pos - self.block_pos as u64) / n_channels;
+
+        let samples = std::mem::take(&mut self.samples);
+        self.encode(&samples[..self.block_pos], first_sample);
+        self.samples = samples;
+        self.block_pos = 0;
+    }
+
+    /// Decodes the next frame, returning false at the end of the stream
+    fn decode_frame(&mut self) -> PhonicResult<bool>
+    where
+        T: StreamReader,
+    {
+        loop {
+            match decode_frame(&self.bytes, self.bits_per_sample, &mut self.channels) {
+                Ok((header, len)) => {
+                    self.bytes.drain(..len);
+                    if header.n_channels() != self.spec.decoded.n_channels {
+                        return Err(PhonicError::invalid_data());
+                    }
+
+                    self.block_bits = header.bits_per_sample.unwrap_or(self.bits_per_sample);
+                    self.block_len = header.block_size;
+                    self.block_pos = self.n_skip.min(self.block_len as u64) as usize;
+                    self.n_skip -= self.block_pos as u64;
+
+                    if self.block_pos < self.block_len {
+                        return Ok(true);
+                    }
+                }
+                Err(PhonicError::MissingData { .. }) => {
+                    let len = self.bytes.len();
+                    self.bytes.reserve(READ_LEN);
+
+                    let spare = &mut self.bytes.spare_capacity_mut()[..READ_LEN];
+                    let n = self.inner.read(spare)?;
+                    unsafe { self.bytes.set_len(len + n) };
+
+                    match n {
+                        0 if self.bytes.is_empty() => return Ok(false),
+                        0 => return Err(PhonicError::invalid_data()),
+                        _ => continue,
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Writes any encoded bytes that haven't been accepted by the inner stream
+    fn write_pending(&mut self) -> PhonicResult<()>
+    where
+        T: StreamWriter,
+    {
+        while self.n_bytes_consumed < self.bytes.len() {
+            match self.inner.write(&self.bytes[self.n_bytes_consumed..])? {
+                0 => return Err(PhonicError::out_of_bounds()),
+                n => self.n_bytes_consumed += n,
+            }
+        }
+
+        self.bytes.clear();
+        self.n_bytes_consumed = 0;
+
+        Ok(())
+    }
+}
+
+impl<T, S, C> CodecFromSignal<T, C> for FlacCodec<T, S, C>
+where
+    T: Signal<Sample = S>,
+    S: Sample,
+    C: CodecTag + TryInto<FlacCodecTag>,
+    FlacCodecTag: TryInto<C>,
+    PhonicError: From<<C as TryInto<FlacCodecTag>>::Error>,
+    PhonicError: From<<FlacCodecTag as TryInto<C>>::Error>,
+{
+    fn from_signal(tag: C, inner: T) -> PhonicResult<Self> {
+        let spec_builder = StreamSpecBuilder::from(&inner).with_codec(tag);
+        let spec = FlacCodecTag::infer_tagged_spec(spec_builder)?;
+
+        Self::new(inner, spec)
+    }
+}
+
+impl<T, S, C> CodecFromStream<T, C> for FlacCodec<T, S, C>
+where
+    T: Stream<Tag = C>,
+    S: Sample,
+    C: CodecTag + TryInto<FlacCodecTag>,
+    FlacCodecTag: TryInto<C>,
+    PhonicError: From<<C as TryInto<FlacCodecTag>>::Error>,
+    PhonicError: From<<FlacCodecTag as TryInto<C>>::Error>,
+{
+    fn from_stream(inner: T) -> PhonicResult<Self> {
+        let spec_builder = inner.stream_spec().into_builder();
+        let spec = FlacCodecTag::infer_tagged_spec(spec_builder)?;
+
+        Self::new(inner, spec)
+    }
+}
+
+impl<T, S: Sample, C: CodecTag> Signal for FlacCodec<T, S, C> {
+    type Sample = S;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec.decoded
+    }
+}
+
+impl<T: WakingStream, S: Sample, C: CodecTag> WakingSignal for FlacCodec<T, S, C> {
+    fn register_waker(&self, waker: &Waker) {
+        self.inner.register_waker(waker)
+    }
+}
+
+impl<T: IndexedStream, S: Sample, C: CodecTag> IndexedSignal for FlacCodec<T, S, C> {
+    fn pos(&self) -> u64 {
+        self.pos
+    }
+}
+
+impl<T: FiniteStream, S: Sample, C: CodecTag> FiniteSignal for FlacCodec<T, S, C> {
+    fn len(&self) -> u64 {
+        self.inner.len() / self.spec.sample.size() as u64
+    }
+}
+
+impl<T, S, C> SignalReader for FlacCodec<T, S, C>
+where
+    T: StreamReader,
+    S: Sample + FlacSample,
+    C: CodecTag,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let n_channels = self.spec.decoded.n_channels;
+        if buf.len() < n_channels {
+            return Err(PhonicError::invalid_input());
+        }
+
+        if self.block_pos == self.block_len && !self.decode_frame()? {
+            return Ok(0);
+        }
+
+        let n_frames = (buf.len() / n_channels).min(self.block_len - self.block_pos);
+        for (i, frame) in buf.chunks_exact_mut(n_channels).take(n_frames).enumerate() {
+            for (sample, channel) in frame.iter_mut().zip(&self.channels) {
+                let value = channel[self.block_pos + i];
+                sample.write(S::from_flac(value, self.block_bits));
+            }
+        }
+
+        self.block_pos += n_frames;
+
+        let n_samples = n_frames * n_channels;
+        self.pos += n_samples as u64;
+
+        Ok(n_samples)
+    }
+}
+
+impl<T, S, C> SignalWriter for FlacCodec<T, S, C>
+where
+    T: StreamWriter,
+    S: Sample + FlacSample,
+    C: CodecTag,
+{
+    fn write(&mut self, buf: &[Self::Sample]) -> PhonicResult<usize> {
+        let n_channels = self.spec.decoded.n_channels;
+        let block_len = self.encoder.compression().block_size * n_channels;
+        if buf.len() < n_channels {
+            return Err(PhonicError::invalid_input());
+        }
+
+        self.write_pending()?;
+
+        // the block may have shrunk if the compression was changed while samples were buffered
+        if self.block_pos >= block_len {
+            self.encode_buffered();
+            self.write_pending()?;
+        }
+
+        let len = buf.len() - buf.len() % n_channels;
+        let len = len.min(block_len - self.block_pos);
+
+        self.samples.truncate(self.block_pos);
+        self.samples.extend_from_slice(&buf[..len]);
+        self.block_pos += len;
+        self.pos += len as u64;
+
+        if self.block_pos < block_len {
+            return Ok(len);
+        }
+
+        self.encode_buffered();
+        match self.write_pending() {
+            Err(PhonicError::Interrupted { .. } | PhonicError::NotReady { .. }) => Ok(len),
+            result => result.map(|_| len),
+        }
+    }
+
+    fn flush(&mut self) -> PhonicResult<()> {
+        self.encode_buffered();
+        self.write_pending()?;
+        self.inner.flush()
+    }
+}
+
+impl<T, S, C> SignalSeeker for FlacCodec<T, S, C>
+where
+    T: IndexedStream + StreamSeeker,
+    S: Sample,
+    C: CodecTag,
+{
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        let n_channels = self.spec.decoded.n_channels as u64;
+        if offset % n_channels as i64 != 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let pos = self
+            .pos
+            .checked_add_signed(offset)
+            .ok_or(PhonicError::out_of_bounds())?;
+
+        let target = pos / n_channels;
+        let block_start = self.pos / n_channels - self.block_pos as u64;
+        if self.n_skip == 0 && (block_start..block_start + self.block_len as u64).contains(&target)
+        {
+            self.block_pos = (target - block_start) as usize;
+            self.pos = pos;
+            return Ok(());
+        }
+
+        let block_align = self.spec.block_align as u64;
+        let stream_offset = (target * block_align) as i64 - self.inner.pos() as i64;
+        self.inner.seek(stream_offset)?;
+
+        let frame_start = self.inner.pos() / block_align;
+        if frame_start > target {
+            return Err(PhonicError::invalid_state());
+        }
+
+        self.bytes.clear();
+        self.block_len = 0;
+        self.block_pos = 0;
+        self.n_skip = target - frame_start;
+        self.pos = pos;
+
+        Ok(())
+    }
+}
+
+impl<T, S: Sample, C: CodecTag> Stream for FlacCodec<T, S, C> {
+    type Tag = C;
+
+    fn stream_spec(&self) -> &StreamSpec<Self::Tag> {
+        &self.spec
+    }
+}
+
+impl<T: WakingSignal, S: Sample, C: CodecTag> WakingStream for FlacCodec<T, S, C> {
+    fn register_waker(&self, waker: &Waker) {
+        self.inner.register_waker(waker)
+    }
+}
+
+impl<T, S, C> IndexedStream for FlacCodec<T, S, C>
+where
+    T: IndexedSignal<Sample = S>,
+    S: Sample,
+    C: CodecTag,
+{
+    fn pos(&self) -> u64 {
+        self.pos * self.spec.sample.size() as u64
+    }
+}
+
+impl<T, S, C> FiniteStream for FlacCodec<T, S, C>
+where
+    T: FiniteSignal<Sample = S>,
+    S: Sample,
+    C: CodecTag,
+{
+    fn len(&self) -> u64 {
+        self.inner.len() * self.spec.sample.size() as u64
+    }
+}
+
+impl<T, S, C> StreamReader for FlacCodec<T, S, C>
+where
+    T: SignalReader<Sample = S>,
+    S: Sample + FlacSample,
+    C: CodecTag,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<u8>]) -> PhonicResult<usize> {
+        if self.n_bytes_consumed == self.bytes.len() {
+            self.bytes.clear();
+            self.n_bytes_consumed = 0;
+
+            let n_channels = self.spec.decoded.n_channels;
+            let block_len = self.encoder.compression().block_size * n_channels;
+            self.samples.resize(block_len, S::ORIGIN);
+
+            while self.block_pos < block_len {
+                let samples = slice_as_uninit_mut(&mut self.samples[self.block_pos..]);
+                match self.inner.read(samples)? {
+                    0 => break,
+                    n => self.block_pos += n,
+                }
+            }
+
+            if self.block_pos == 0 {
+                return Ok(0);
+            }
+
+            let samples = std::mem::take(&mut self.samples);
+            self.encode(&samples[..self.block_pos], self.pos / n_channels as u64);
+            self.samples = samples;
+        }
+
+        let bytes = &self.bytes[self.n_bytes_consumed..];
+        let n = buf.len().min(bytes.len());
+        for (byte, value) in buf.iter_mut().zip(&bytes[..n]) {
+            byte.write(*value);
+        }
+
+        self.n_bytes_consumed += n;
+        if self.n_bytes_consumed == self.bytes.len() {
+            self.pos += self.block_pos as u64;
+            self.block_pos = 0;
+        }
+
+        Ok(n)
+    }
+}
+
+impl<T, S, C> StreamSeeker for FlacCodec<T, S, C>
+where
+    T: SignalSeeker<Sample = S>,
+    S: Sample,
+    C: CodecTag,
+{
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        if offset % self.spec.block_align as i64 != 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let n_samples = offset / self.spec.sample.size() as i64;
+        let pos = self
+            .pos
+            .checked_add_signed(n_samples)
+            .ok_or(PhonicError::out_of_bounds())?;
+
+        // the inner signal is ahead of the stream by the samples of the frame being encoded
+        self.inner.seek(n_samples - self.block_pos as i64)?;
+
+        self.bytes.clear();
+        self.n_bytes_consumed = 0;
+        self.block_pos = 0;
+        self.pos = pos;
+
+        Ok(())
+    }
+}
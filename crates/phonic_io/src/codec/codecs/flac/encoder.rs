@@ -0,0 +1,775 @@
+use crate::codecs::flac::{
+    crc16, BitWriter, ChannelAssignment, FlacSample, FrameHeader, FIXED_COEFFICIENTS,
+};
+use phonic_signal::{PhonicError, PhonicResult};
+use std::f64::consts::PI;
+
+const MAX_LPC_ORDER: usize = 32;
+const MAX_RICE_PARAM: u32 = 30;
+
+/// The settings used to encode flac frames. Higher compression levels search more predictors
+/// and partitionings, producing smaller frames at the cost of encoding time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FlacCompression {
+    /// The number of frames in each block
+    pub block_size: usize,
+
+    /// The highest order of linear predictor to try. Zero only uses fixed predictors.
+    pub max_lpc_order: usize,
+
+    /// The highest rice partition order to try
+    pub max_partition_order: u32,
+
+    /// Whether to try coding stereo frames as mid/side, left/side and side/right
+    pub stereo_decorrelation: bool,
+
+    /// Whether to try every linear predictor order rather than the one with the lowest
+    /// estimated size
+    pub exhaustive_search: bool,
+}
+
+pub(crate) struct FrameEncoder {
+    compression: FlacCompression,
+    channels: Vec<Vec<i64>>,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Copy)]
+enum SubframeKind {
+    Constant,
+    Verbatim,
+    Fixed(usize),
+    Lpc {
+        precision: u32,
+        shift: u32,
+        coefficients: [i64; MAX_LPC_ORDER],
+        order: usize,
+    },
+}
+
+struct Subframe {
+    kind: SubframeKind,
+    wasted_bits: u32,
+    bits_per_sample: u32,
+    samples: Vec<i64>,
+    residual: Vec<i64>,
+    rice: Rice,
+    size: u64,
+}
+
+#[derive(Default)]
+struct Rice {
+    partition_order: u32,
+    param_bits: u32,
+    params: Vec<u32>,
+    size: u64,
+}
+
+impl FlacCompression {
+    pub const LEVELS: [Self; 9] = [
+        Self::preset(1152, 0, 3, false, false),
+        Self::preset(1152, 0, 3, true, false),
+        Self::preset(1152, 0, 4, true, false),
+        Self::preset(4096, 6, 4, false, false),
+        Self::preset(4096, 8, 4, true, false),
+        Self::preset(4096, 8, 5, true, false),
+        Self::preset(4096, 8, 6, true, false),
+        Self::preset(4096, 8, 6, true, true),
+        Self::preset(4096, 12, 6, true, true),
+    ];
+
+    pub const FASTEST: Self = Self::LEVELS[0];
+    pub const BEST: Self = Self::LEVELS[8];
+
+    const fn preset(
+        block_size: usize,
+        max_lpc_order: usize,
+        max_partition_order: u32,
+        stereo_decorrelation: bool,
+        exhaustive_search: bool,
+    ) -> Self {
+        Self {
+            block_size,
+            max_lpc_order,
+            max_partition_order,
+            stereo_decorrelation,
+            exhaustive_search,
+        }
+    }
+
+    /// Returns the settings of a compression level from 0 to 8
+    pub fn level(level: usize) -> PhonicResult<Self> {
+        Self::LEVELS
+            .get(level)
+            .copied()
+            .ok_or(PhonicError::invalid_input())
+    }
+
+    pub fn validate(&self) -> PhonicResult<()> {
+        if !(16..=u16::MAX as usize).contains(&self.block_size)
+            || self.max_lpc_order > MAX_LPC_ORDER
+            || self.max_partition_order > 15
+        {
+            return Err(PhonicError::invalid_input());
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for FlacCompression {
+    fn default() -> Self {
+        Self::LEVELS[5]
+    }
+}
+
+impl FrameEncoder {
+    pub fn new(compression: FlacCompression) -> Self {
+        Self {
+            compression,
+            channels: Vec::new(),
+        }
+    }
+
+    pub fn compression(&self) -> &FlacCompression {
+        &self.compression
+    }
+
+    /// Encodes a block of interleaved samples as a single frame starting at `first_sample`
+    pub fn encode<S: FlacSample>(
+        &mut self,
+        samples: &[S],
+        first_sample: u64,
+        n_channels: usize,
+        sample_rate: usize,
+        bits_per_sample: u32,
+        buf: &mut Vec<u8>,
+    ) {
+        let block_size = samples.len() / n_channels;
+        debug_assert!(block_size > 0 && block_size <= u16::MAX as usize);
+
+        self.channels.resize_with(n_channels, Vec::new);
+        for (i, channel) in self.channels.iter_mut().enumerate() {
+            channel.clear();
+            channel.extend(
+                samples[i..]
+                    .iter()
+                    .step_by(n_channels)
+                    .map(|s| s.into_flac(bits_per_sample)),
+            );
+        }
+
+        let (channels, subframes) =
+            if n_channels == 2 && self.compression.stereo_decorrelation && bits_per_sample < 32 {
+                self.decorrelate(bits_per_sample)
+            } else {
+                let subframes = self
+                    .channels
+                    .iter()
+                    .map(|channel| Subframe::analyze(&self.compression, channel, bits_per_sample))
+                    .collect();
+
+                (ChannelAssignment::Independent(n_channels), subframes)
+            };
+
+        let header = FrameHeader {
+            variable_block_size: true,
+            number: first_sample,
+            block_size,
+            sample_rate: Some(sample_rate),
+            channels,
+            bits_per_sample: Some(bits_per_sample),
+        };
+
+        let start = buf.len();
+        header.write(buf);
+
+        let mut writer = BitWriter::new(buf);
+        for subframe in subframes {
+            subframe.write(&mut writer);
+        }
+
+        drop(writer);
+        let crc = crc16(&buf[start..]);
+        buf.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    fn decorrelate(&self, bits_per_sample: u32) -> (ChannelAssignment, Vec<Subframe>) {
+        let (left, right) = (&self.channels[0], &self.channels[1]);
+        let mid = left
+            .iter()
+            .zip(right)
+            .map(|(l, r)| (l + r) >> 1)
+            .collect::<Vec<_>>();
+
+        let side = left
+            .iter()
+            .zip(right)
+            .map(|(l, r)| l - r)
+            .collect::<Vec<_>>();
+
+        let compression = &self.compression;
+        let left = Subframe::analyze(compression, left, bits_per_sample);
+        let right = Subframe::analyze(compression, right, bits_per_sample);
+        let mid = Subframe::analyze(compression, &mid, bits_per_sample);
+        let side = Subframe::analyze(compression, &side, bits_per_sample + 1);
+
+        let independent = left.size + right.size;
+        let left_side = left.size + side.size;
+        let side_right = side.size + right.size;
+        let mid_side = mid.size + side.size;
+        let min = independent.min(left_side).min(side_right).min(mid_side);
+
+        if min == independent {
+            (ChannelAssignment::Independent(2), vec![left, right])
+        } else if min == mid_side {
+            (ChannelAssignment::MidSide, vec![mid, side])
+        } else if min == left_side {
+            (ChannelAssignment::LeftSide, vec![left, side])
+        } else {
+            (ChannelAssignment::SideRight, vec![side, right])
+        }
+    }
+}
+
+impl Subframe {
+    fn analyze(compression: &FlacCompression, samples: &[i64], bits_per_sample: u32) -> Self {
+        let n = samples.len();
+        if samples.iter().all(|s| *s == samples[0]) {
+            return Self {
+                kind: SubframeKind::Constant,
+                wasted_bits: 0,
+                bits_per_sample,
+                samples: vec![samples[0]],
+                residual: Vec::new(),
+                rice: Rice::default(),
+                size: 8 + bits_per_sample as u64,
+            };
+        }
+
+        let wasted_bits = samples
+            .iter()
+            .fold(0, |acc, s| acc | s)
+            .trailing_zeros()
+            .min(bits_per_sample - 1);
+
+        let bits = bits_per_sample - wasted_bits;
+        let samples = samples.iter().map(|s| s >> wasted_bits).collect::<Vec<_>>();
+        let header_size = 8 + wasted_bits as u64;
+
+        let mut best = Self {
+            kind: SubframeKind::Verbatim,
+            wasted_bits,
+            bits_per_sample: bits,
+            residual: Vec::new(),
+            rice: Rice::default(),
+            size: header_size + n as u64 * bits as u64,
+            samples,
+        };
+
+        let mut residual = vec![0; n];
+        for (order, coefficients) in FIXED_COEFFICIENTS.iter().enumerate() {
+            if order >= n || !compute_residual(&best.samples, coefficients, 0, &mut residual) {
+                continue;
+            }
+
+            let rice = Rice::analyze(&residual, order, compression.max_partition_order);
+            let size = header_size + (order as u32 * bits) as u64 + rice.size;
+            best.try_replace(SubframeKind::Fixed(order), size, rice, &mut residual);
+        }
+
+        let max_order = compression.max_lpc_order.min(n - 1);
+        if max_order == 0 {
+            return best;
+        }
+
+        let Some((predictors, errors)) = lpc_analysis(&best.samples, max_order) else {
+            return best;
+        };
+
+        let precision = match n {
+            0..=192 => 7,
+            193..=384 => 8,
+            385..=576 => 9,
+            577..=1152 => 10,
+            1153..=2304 => 11,
+            2305..=4608 => 12,
+            _ => 13,
+        };
+
+        let orders = if compression.exhaustive_search {
+            1..max_order + 1
+        } else {
+            let error_scale = 0.5 / n as f64;
+            let estimate = |order: usize| {
+                let error = errors[order - 1] * error_scale;
+                let residual_bits = if error > 0.0 {
+                    (0.5 * error.log2()).max(0.0)
+                } else {
+                    0.0
+                };
+
+                residual_bits * (n - order) as f64 + (order as u32 * (bits + precision)) as f64
+            };
+
+            let order = (1..=max_order)
+                .min_by(|a, b| estimate(*a).total_cmp(&estimate(*b)))
+                .unwrap_or(1);
+
+            order..order + 1
+        };
+
+        for order in orders {
+            let Some((coefficients, shift)) = quantize(&predictors[order - 1][..order], precision)
+            else {
+                continue;
+            };
+
+            if !compute_residual(&best.samples, &coefficients[..order], shift, &mut residual) {
+                continue;
+            }
+
+            let rice = Rice::analyze(&residual, order, compression.max_partition_order);
+            let size = header_size + (order as u32 * (bits + precision)) as u64 + 9 + rice.size;
+
+            let kind = SubframeKind::Lpc {
+                precision,
+                shift,
+                coefficients,
+                order,
+            };
+
+            best.try_replace(kind, size, rice, &mut residual);
+        }
+
+        best
+    }
+
+    fn try_replace(&mut self, kind: SubframeKind, size: u64, rice: Rice, residual: &mut Vec<i64>) {
+        if size < self.size {
+            self.kind = kind;
+            self.size = size;
+            self.rice = rice;
+            std::mem::swap(&mut self.residual, residual);
+            residual.resize(self.samples.len(), 0);
+        }
+    }
+
+    fn write(&self, writer: &mut BitWriter) {
+        let kind = match self.kind {
+            SubframeKind::Constant => 0b000000,
+            SubframeKind::Verbatim => 0b000001,
+            SubframeKind::Fixed(order) => 0b001000 | order as u64,
+            SubframeKind::Lpc { order, .. } => 0b100000 | (order as u64 - 1),
+        };
+
+        writer.write(0, 1);
+        writer.write(kind, 6);
+        match self.wasted_bits {
+            0 => writer.write(0, 1),
+            n => {
+                writer.write(1, 1);
+                writer.write_unary(n as u64 - 1);
+            }
+        }
+
+        let bits = self.bits_per_sample;
+        match self.kind {
+            SubframeKind::Constant => writer.write_signed(self.samples[0], bits),
+            SubframeKind::Verbatim => {
+                for sample in &self.samples {
+                    writer.write_signed(*sample, bits);
+                }
+            }
+            SubframeKind::Fixed(order) => {
+                for sample in &self.samples[..order] {
+                    writer.write_signed(*sample, bits);
+                }
+
+                self.rice.write(&self.residual, order, writer);
+            }
+            SubframeKind::Lpc {
+                precision,
+                shift,
+                coefficients,
+                order,
+            } => {
+                for sample in &self.samples[..order] {
+                    writer.write_signed(*sample, bits);
+                }
+
+                writer.write(precision as u64 - 1, 4);
+                writer.write(shift as u64, 5);
+                for coefficient in &coefficients[..order] {
+                    writer.write_signed(*coefficient, precision);
+                }
+
+                self.rice.write(&self.residual, order, writer);
+            }
+        }
+    }
+}
+
+impl Rice {
+    fn analyze(residual: &[i64], order: usize, max_partition_order: u32) -> Self {
+        let n = residual.len();
+        let mut max_order = max_partition_order;
+        while max_order > 0 && (!n.is_multiple_of(1 << max_order) || n >> max_order <= order) {
+            max_order -= 1;
+        }
+
+        let partition_len = n >> max_order;
+        let mut sums = (0..1usize << max_order)
+            .map(|i| {
+                let start = (i * partition_len).max(order);
+                let end = (i + 1) * partition_len;
+                residual[start..end].iter().map(|s| zigzag(*s)).sum::<u64>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut best = Self {
+            size: u64::MAX,
+            ..Self::default()
+        };
+
+        for partition_order in (0..=max_order).rev() {
+            let partition_len = n >> partition_order;
+            let params = sums
+                .iter()
+                .enumerate()
+                .map(|(i, sum)| {
+                    let count = partition_len - if i == 0 { order } else { 0 };
+                    best_param(*sum, count as u64)
+                })
+                .collect::<Vec<_>>();
+
+            let param_bits = match params.iter().any(|(param, _)| *param > 14) {
+                true => 5,
+                false => 4,
+            };
+
+            let size = 6 + params
+                .iter()
+                .map(|(_, size)| size + param_bits as u64)
+                .sum::<u64>();
+
+            if size < best.size {
+                best = Self {
+                    partition_order,
+                    param_bits,
+                    params: params.into_iter().map(|(param, _)| param).collect(),
+                    size,
+                };
+            }
+
+            sums = sums.chunks(2).map(|pair| pair.iter().sum()).collect();
+        }
+
+        best
+    }
+
+    fn write(&self, residual: &[i64], order: usize, writer: &mut BitWriter) {
+        let method = match self.param_bits {
+            4 => 0b00,
+            _ => 0b01,
+        };
+
+        writer.write(method, 2);
+        writer.write(self.partition_order as u64, 4);
+
+        let partition_len = residual.len() >> self.partition_order;
+        for (i, param) in self.params.iter().enumerate() {
+            let start = (i * partition_len).max(order);
+            let end = (i + 1) * partition_len;
+
+            writer.write(*param as u64, self.param_bits);
+            for sample in &residual[start..end] {
+                let value = zigzag(*sample);
+                writer.write_unary(value >> param);
+                writer.write(value, *param);
+            }
+        }
+    }
+}
+
+#[inline]
+fn zigzag(sample: i64) -> u64 {
+    (sample << 1 ^ sample >> 63) as u64
+}
+
+/// Returns the rice parameter with the lowest estimated size for a partition along with the
+/// estimated size in bits
+fn best_param(sum: u64, count: u64) -> (u32, u64) {
+    (0..=MAX_RICE_PARAM)
+        .map(|param| (param, count * (param as u64 + 1) + (sum >> param)))
+        .min_by_key(|(_, size)| *size)
+        .unwrap_or_default()
+}
+
+/// Computes the residual of a predictor, returning false if it doesn't fit in 32 bits
+fn compute_residual(samples: &[i64], coefficients: &[i64], shift: u32, buf: &mut [i64]) -> bool {
+    let order = coefficients.len();
+    buf[..order].fill(0);
+
+    for i in order..samples.len() {
+        let prediction = coefficients
+            .iter()
+            .zip(samples[i - order..i].iter().rev())
+            .map(|(c, s)| c * s)
+            .sum::<i64>();
+
+        let residual = samples[i] - (prediction >> shift);
+        if residual < i32::MIN as i64 || residual > i32::MAX as i64 {
+            return false;
+        }
+
+        buf[i] = residual;
+    }
+
+    true
+}
+
+/// Computes the linear predictors of every order up to `max_order` from the autocorrelation of
+/// the windowed samples, along with their prediction errors.
+#[allow(clippy::type_complexity)]
+fn lpc_analysis(
+    samples: &[i64],
+    max_order: usize,
+) -> Option<(Vec<[f64; MAX_LPC_ORDER]>, Vec<f64>)> {
+    let n = samples.len();
+    let taper = (n / 4).max(1) as f64;
+    let windowed = samples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let edge = i.min(n - 1 - i) as f64;
+            let window = match edge < taper {
+                true => 0.5 - 0.5 * (PI * edge / taper).cos(),
+                false => 1.0,
+            };
+
+            *s as f64 * window
+        })
+        .collect::<Vec<_>>();
+
+    let autocorrelation = (0..=max_order)
+        .map(|lag| {
+            windowed[lag..]
+                .iter()
+                .zip(&windowed)
+                .map(|(a, b)| a * b)
+                .sum::<f64>()
+        })
+        .collect::<Vec<_>>();
+
+    if autocorrelation[0] <= 0.0 {
+        return None;
+    }
+
+    let mut predictors = Vec::with_capacity(max_order);
+    let mut errors = Vec::with_capacity(max_order);
+    let mut lpc = [0f64; MAX_LPC_ORDER];
+    let mut error = autocorrelation[0];
+
+    for i in 0..max_order {
+        let mut r = -autocorrelation[i + 1];
+        for j in 0..i {
+            r -= lpc[j] * autocorrelation[i - j];
+        }
+
+        r /= error;
+        lpc[i] = r;
+
+        for j in 0..i / 2 {
+            let tmp = lpc[j];
+            lpc[j] += r * lpc[i - 1 - j];
+            lpc[i - 1 - j] += r * tmp;
+        }
+
+        if i % 2 == 1 {
+            lpc[i / 2] += lpc[i / 2] * r;
+        }
+
+        error *= 1.0 - r * r;
+
+        let mut predictor = [0f64; MAX_LPC_ORDER];
+        for (p, c) in predictor.iter_mut().zip(&lpc[..=i]) {
+            *p = -c;
+        }
+
+        predictors.push(predictor);
+        errors.push(error);
+    }
+
+    Some((predictors, errors))
+}
+
+/// Quantizes predictor coefficients to `precision` bits, returning the coefficients and the shift
+/// applied to their sum.
+fn quantize(predictor: &[f64], precision: u32) -> Option<([i64; MAX_LPC_ORDER], u32)> {
+    let max = predictor.iter().fold(0f64, |max, c| max.max(c.abs()));
+    if max <= 0.0 || !max.is_finite() {
+        return None;
+    }
+
+    let magnitude_bits = max.log2().floor() as i32 + 1;
+    let shift = (precision as i32 - 1 - magnitude_bits).min(15);
+    if shift < 0 {
+        return None;
+    }
+
+    let q_max = (1i64 << (precision - 1)) - 1;
+    let q_min = -(1i64 << (precision - 1));
+    let scale = (1u32 << shift) as f64;
+
+    let mut coefficients = [0i64; MAX_LPC_ORDER];
+    let mut error = 0.0;
+    for (q, c) in coefficients.iter_mut().zip(predictor) {
+        error += c * scale;
+        *q = (error.round() as i64).clamp(q_min, q_max);
+        error -= *q as f64;
+    }
+
+    Some((coefficients, shift as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FlacCompression, FrameEncoder, Subframe, SubframeKind};
+    use crate::codecs::flac::{
+        decode_frame, decode_subframe, BitReader, BitWriter, ChannelAssignment,
+    };
+
+    fn noise(n_samples: usize, bits_per_sample: u32) -> Vec<i64> {
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        (0..n_samples)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as i64 >> (64 - bits_per_sample)
+            })
+            .collect()
+    }
+
+    fn tone(n_samples: usize, amplitude: f64) -> Vec<i64> {
+        (0..n_samples)
+            .map(|i| ((i as f64 * 0.05).sin() * amplitude).round() as i64)
+            .collect()
+    }
+
+    /// Encodes and decodes a subframe, returning its kind and number of wasted bits
+    fn round_trip_subframe(samples: &[i64], bits_per_sample: u32) -> (SubframeKind, u32) {
+        let subframe = Subframe::analyze(&FlacCompression::default(), samples, bits_per_sample);
+
+        let mut buf = Vec::new();
+        subframe.write(&mut BitWriter::new(&mut buf));
+
+        let mut decoded = vec![0; samples.len()];
+        let mut reader = BitReader::new(&buf);
+        decode_subframe(&mut reader, bits_per_sample, &mut decoded).unwrap();
+
+        assert_eq!(decoded, samples);
+        assert_eq!(reader.byte_pos(), buf.len());
+
+        (subframe.kind, subframe.wasted_bits)
+    }
+
+    #[test]
+    fn subframes_use_the_smallest_predictor() {
+        let constant = vec![-5; 64];
+        let (kind, _) = round_trip_subframe(&constant, 16);
+        assert!(matches!(kind, SubframeKind::Constant));
+
+        let ramp = (0..256).map(|i| i * 3 - 100).collect::<Vec<_>>();
+        let (kind, _) = round_trip_subframe(&ramp, 16);
+        assert!(matches!(kind, SubframeKind::Fixed(2)));
+
+        let (kind, _) = round_trip_subframe(&noise(256, 16), 16);
+        assert!(matches!(kind, SubframeKind::Verbatim));
+
+        // noise through a resonant filter is predicted better by its own coefficients than by
+        // any fixed polynomial
+        let mut resonance = noise(4096, 9);
+        for i in 2..resonance.len() {
+            resonance[i] += (1.6 * resonance[i - 1] as f64 - 0.8 * resonance[i - 2] as f64) as i64;
+        }
+
+        let (kind, _) = round_trip_subframe(&resonance, 16);
+        assert!(matches!(kind, SubframeKind::Lpc { .. }));
+
+        let shifted = tone(4096, 1000.0)
+            .into_iter()
+            .map(|s| s << 4)
+            .collect::<Vec<_>>();
+
+        let (_, wasted_bits) = round_trip_subframe(&shifted, 20);
+        assert_eq!(wasted_bits, 4);
+
+        let (kind, _) = round_trip_subframe(&noise(64, 32), 32);
+        assert!(matches!(kind, SubframeKind::Verbatim));
+    }
+
+    #[test]
+    fn frames_round_trip_at_every_bit_depth() {
+        let mut encoder = FrameEncoder::new(FlacCompression::BEST);
+
+        for bits_per_sample in [8, 12, 16, 20, 24, 32] {
+            for (n_channels, block_size) in [(1, 1), (2, 17), (2, 4096), (3, 1000)] {
+                let amplitude = (1u64 << (bits_per_sample - 2)) as f64;
+                let tone = tone(block_size, amplitude);
+                let noise = noise(block_size * n_channels, bits_per_sample - 4);
+
+                let expected = (0..n_channels)
+                    .map(|channel| {
+                        (0..block_size)
+                            .map(|i| tone[i] + noise[i * n_channels + channel])
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>();
+
+                // i64 samples are aligned to their most significant bit
+                let samples = (0..block_size * n_channels)
+                    .map(|i| expected[i % n_channels][i / n_channels] << (64 - bits_per_sample))
+                    .collect::<Vec<i64>>();
+
+                let mut buf = Vec::new();
+                encoder.encode(&samples, 100, n_channels, 44100, bits_per_sample, &mut buf);
+
+                let mut channels = Vec::new();
+                let (header, len) = decode_frame(&buf, 0, &mut channels).unwrap();
+                assert_eq!(len, buf.len());
+                assert_eq!(header.number, 100);
+                assert_eq!(header.block_size, block_size);
+                assert_eq!(header.bits_per_sample, Some(bits_per_sample));
+                assert_eq!(channels, expected, "{bits_per_sample} bits");
+            }
+        }
+    }
+
+    #[test]
+    fn identical_stereo_channels_are_coded_as_mid_side() {
+        let tone = tone(4096, 8000.0);
+        let samples = tone
+            .iter()
+            .flat_map(|s| [*s as i16, *s as i16])
+            .collect::<Vec<_>>();
+
+        let mut buf = Vec::new();
+        let mut encoder = FrameEncoder::new(FlacCompression::default());
+        encoder.encode(&samples, 0, 2, 48000, 16, &mut buf);
+
+        let mut channels = Vec::new();
+        let (header, _) = decode_frame(&buf, 16, &mut channels).unwrap();
+        assert_eq!(header.channels, ChannelAssignment::MidSide);
+        assert_eq!(channels, [tone.clone(), tone]);
+
+        // without decorrelation the channels are coded independently
+        let mut buf = Vec::new();
+        let mut encoder = FrameEncoder::new(FlacCompression::FASTEST);
+        encoder.encode(&samples, 0, 2, 48000, 16, &mut buf);
+
+        let (header, _) = decode_frame(&buf, 16, &mut channels).unwrap();
+        assert_eq!(header.channels, ChannelAssignment::Independent(2));
+    }
+}
@@ -0,0 +1,409 @@
+// https://www.rfc-editor.org/rfc/rfc9639.html#name-frame-structure
+
+use crate::codecs::flac::{crc16, crc16_update, crc8, decode_subframe, BitReader, BitWriter};
+use phonic_signal::{PhonicError, PhonicResult};
+
+const SYNC_CODE: u64 = 0b11111111111110;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChannelAssignment {
+    Independent(usize),
+    LeftSide,
+    SideRight,
+    MidSide,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FrameHeader {
+    /// Whether `number` is the first sample of the frame rather than the index of the frame
+    pub variable_block_size: bool,
+    pub number: u64,
+    pub block_size: usize,
+    pub sample_rate: Option<usize>,
+    pub channels: ChannelAssignment,
+    pub bits_per_sample: Option<u32>,
+}
+
+/// Finds the end of the frame at the start of a buffer without decoding it. A frame ends where
+/// the crc of the bytes preceding a valid frame header is zero, or at the end of the stream.
+#[derive(Default)]
+pub(crate) struct FrameSplitter {
+    n_scanned: usize,
+    crc: u16,
+}
+
+impl ChannelAssignment {
+    pub fn n_channels(self) -> usize {
+        match self {
+            Self::Independent(n) => n,
+            _ => 2,
+        }
+    }
+
+    /// Returns the number of extra bits needed to store the given channel
+    fn extra_bits(self, channel: usize) -> u32 {
+        match (self, channel) {
+            (Self::LeftSide | Self::MidSide, 1) | (Self::SideRight, 0) => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl FrameHeader {
+    /// Reads a frame header from the start of a buffer, returning the header and its length in
+    /// bytes.
+    pub fn read(buf: &[u8]) -> PhonicResult<(Self, usize)> {
+        let mut reader = BitReader::new(buf);
+        if reader.read(14)? != SYNC_CODE || reader.read(1)? != 0 {
+            return Err(PhonicError::invalid_data());
+        }
+
+        let variable_block_size = reader.read(1)? == 1;
+        let block_size_code = reader.read(4)?;
+        let sample_rate_code = reader.read(4)?;
+        let channel_code = reader.read(4)?;
+        let sample_size_code = reader.read(3)?;
+        if reader.read(1)? != 0 {
+            return Err(PhonicError::invalid_data());
+        }
+
+        let number = reader.read_coded_number()?;
+        if !variable_block_size && number >= 1 << 31 {
+            return Err(PhonicError::invalid_data());
+        }
+
+        let block_size = match block_size_code {
+            0b0001 => 192,
+            0b0010..=0b0101 => 576 << (block_size_code - 2),
+            0b0110 => reader.read(8)? as usize + 1,
+            0b0111 => reader.read(16)? as usize + 1,
+            0b1000..=0b1111 => 256 << (block_size_code - 8),
+            _ => return Err(PhonicError::invalid_data()),
+        };
+
+        let sample_rate = match sample_rate_code {
+            0b0000 => None,
+            0b0001 => Some(88200),
+            0b0010 => Some(176400),
+            0b0011 => Some(192000),
+            0b0100 => Some(8000),
+            0b0101 => Some(16000),
+            0b0110 => Some(22050),
+            0b0111 => Some(24000),
+            0b1000 => Some(32000),
+            0b1001 => Some(44100),
+            0b1010 => Some(48000),
+            0b1011 => Some(96000),
+            0b1100 => Some(reader.read(8)? as usize * 1000),
+            0b1101 => Some(reader.read(16)? as usize),
+            0b1110 => Some(reader.read(16)? as usize * 10),
+            _ => return Err(PhonicError::invalid_data()),
+        };
+
+        let channels = match channel_code {
+            0b0000..=0b0111 => ChannelAssignment::Independent(channel_code as usize + 1),
+            0b1000 => ChannelAssignment::LeftSide,
+            0b1001 => ChannelAssignment::SideRight,
+            0b1010 => ChannelAssignment::MidSide,
+            _ => return Err(PhonicError::invalid_data()),
+        };
+
+        let bits_per_sample = match sample_size_code {
+            0b000 => None,
+            0b001 => Some(8),
+            0b010 => Some(12),
+            0b100 => Some(16),
+            0b101 => Some(20),
+            0b110 => Some(24),
+            0b111 => Some(32),
+            _ => return Err(PhonicError::invalid_data()),
+        };
+
+        let len = reader.byte_pos();
+        if reader.read(8)? as u8 != crc8(&buf[..len]) {
+            return Err(PhonicError::invalid_data());
+        }
+
+        let header = Self {
+            variable_block_size,
+            number,
+            block_size,
+            sample_rate,
+            channels,
+            bits_per_sample,
+        };
+
+        Ok((header, len + 1))
+    }
+
+    pub fn write(&self, buf: &mut Vec<u8>) {
+        let start = buf.len();
+        let mut writer = BitWriter::new(buf);
+
+        writer.write(SYNC_CODE, 14);
+        writer.write(0, 1);
+        writer.write(self.variable_block_size as u64, 1);
+
+        let (block_size_code, block_size_bits) = match self.block_size {
+            192 => (0b0001, 0),
+            576 | 1152 | 2304 | 4608 => (self.block_size.ilog2() as u64 - 7, 0),
+            256 | 512 | 1024 | 2048 | 4096 | 8192 | 16384 | 32768 => {
+                (self.block_size.ilog2() as u64, 0)
+            }
+            ..=256 => (0b0110, 8),
+            _ => (0b0111, 16),
+        };
+
+        let (sample_rate_code, sample_rate_bits, sample_rate_value) = match self.sample_rate {
+            None => (0b0000, 0, 0),
+            Some(88200) => (0b0001, 0, 0),
+            Some(176400) => (0b0010, 0, 0),
+            Some(192000) => (0b0011, 0, 0),
+            Some(8000) => (0b0100, 0, 0),
+            Some(16000) => (0b0101, 0, 0),
+            Some(22050) => (0b0110, 0, 0),
+            Some(24000) => (0b0111, 0, 0),
+            Some(32000) => (0b1000, 0, 0),
+            Some(44100) => (0b1001, 0, 0),
+            Some(48000) => (0b1010, 0, 0),
+            Some(96000) => (0b1011, 0, 0),
+            Some(rate) if rate % 1000 == 0 && rate / 1000 <= 0xFF => (0b1100, 8, rate / 1000),
+            Some(rate) if rate <= 0xFFFF => (0b1101, 16, rate),
+            Some(rate) if rate % 10 == 0 && rate / 10 <= 0xFFFF => (0b1110, 16, rate / 10),
+            Some(_) => (0b0000, 0, 0),
+        };
+
+        let channel_code = match self.channels {
+            ChannelAssignment::Independent(n) => n as u64 - 1,
+            ChannelAssignment::LeftSide => 0b1000,
+            ChannelAssignment::SideRight => 0b1001,
+            ChannelAssignment::MidSide => 0b1010,
+        };
+
+        let sample_size_code = match self.bits_per_sample {
+            Some(8) => 0b001,
+            Some(12) => 0b010,
+            Some(16) => 0b100,
+            Some(20) => 0b101,
+            Some(24) => 0b110,
+            Some(32) => 0b111,
+            _ => 0b000,
+        };
+
+        writer.write(block_size_code, 4);
+        writer.write(sample_rate_code, 4);
+        writer.write(channel_code, 4);
+        writer.write(sample_size_code, 3);
+        writer.write(0, 1);
+        writer.write_coded_number(self.number);
+        writer.write(self.block_size as u64 - 1, block_size_bits);
+        writer.write(sample_rate_value as u64, sample_rate_bits);
+        drop(writer);
+
+        let crc = crc8(&buf[start..]);
+        buf.push(crc);
+    }
+
+    pub fn n_channels(&self) -> usize {
+        self.channels.n_channels()
+    }
+
+    /// Returns the index of the first sample in the frame. Streams with a fixed block size
+    /// number their frames rather than their samples.
+    pub fn first_sample(&self, fixed_block_size: usize) -> u64 {
+        if self.variable_block_size {
+            self.number
+        } else {
+            self.number * fixed_block_size as u64
+        }
+    }
+}
+
+impl FrameSplitter {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Returns the length of the frame at the start of `buf`, or `None` if more data is needed
+    /// to find its end. Once a length is returned the splitter must be reset before it is used
+    /// on the next frame.
+    pub fn frame_len(&mut self, buf: &[u8], eof: bool) -> PhonicResult<Option<usize>> {
+        let header_len = match FrameHeader::read(buf) {
+            Ok((_, len)) => len,
+            Err(PhonicError::MissingData { .. }) if !eof => return Ok(None),
+            Err(_) => return Err(PhonicError::invalid_data()),
+        };
+
+        // the shortest possible frame is a header, a one byte subframe and a crc
+        let min_len = header_len + 3;
+        if self.n_scanned < min_len {
+            self.crc = crc16(&buf[..min_len.min(buf.len())]);
+            self.n_scanned = min_len.min(buf.len());
+        }
+
+        while self.n_scanned < buf.len() {
+            let i = self.n_scanned;
+            if self.crc == 0 && buf[i] == 0xFF {
+                match FrameHeader::read(&buf[i..]) {
+                    Ok(_) => return Ok(Some(i)),
+                    Err(PhonicError::MissingData { .. }) if !eof => return Ok(None),
+                    Err(_) => {}
+                }
+            }
+
+            self.crc = crc16_update(self.crc, buf[i]);
+            self.n_scanned += 1;
+        }
+
+        match eof {
+            true if self.crc == 0 && buf.len() >= min_len => Ok(Some(buf.len())),
+            true => Err(PhonicError::invalid_data()),
+            false => Ok(None),
+        }
+    }
+}
+
+/// Decodes the frame at the start of a buffer into one buffer per channel, returning the header
+/// and the length of the frame in bytes. `bits_per_sample` is used if the header doesn't specify
+/// a sample size. Returns `MissingData` if the buffer ends before the frame.
+pub(crate) fn decode_frame(
+    buf: &[u8],
+    bits_per_sample: u32,
+    channels: &mut Vec<Vec<i64>>,
+) -> PhonicResult<(FrameHeader, usize)> {
+    let (header, header_len) = FrameHeader::read(buf)?;
+    let bits_per_sample = header.bits_per_sample.unwrap_or(bits_per_sample);
+    let n_channels = header.n_channels();
+
+    channels.resize_with(n_channels, Vec::new);
+    let mut reader = BitReader::new(&buf[header_len..]);
+
+    for (i, channel) in channels.iter_mut().enumerate() {
+        channel.resize(header.block_size, 0);
+        let bits = bits_per_sample + header.channels.extra_bits(i);
+        decode_subframe(&mut reader, bits, channel)?;
+    }
+
+    reader.align();
+    reader.read(16)?;
+
+    let len = header_len + reader.byte_pos();
+    if crc16(&buf[..len]) != 0 {
+        return Err(PhonicError::invalid_data());
+    }
+
+    let (first, rest) = channels.split_at_mut(1);
+    let (a, b) = (&mut first[0], rest.first_mut());
+
+    match (header.channels, b) {
+        (ChannelAssignment::LeftSide, Some(side)) => {
+            for (left, side) in a.iter().zip(side.iter_mut()) {
+                *side = left - *side;
+            }
+        }
+        (ChannelAssignment::SideRight, Some(right)) => {
+            for (side, right) in a.iter_mut().zip(right.iter()) {
+                *side += right;
+            }
+        }
+        (ChannelAssignment::MidSide, Some(side)) => {
+            for (mid, side) in a.iter_mut().zip(side.iter_mut()) {
+                let m = *mid << 1 | (*side & 1);
+                *mid = (m + *side) >> 1;
+                *side = (m - *side) >> 1;
+            }
+        }
+        _ => {}
+    }
+
+    Ok((header, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc16, ChannelAssignment, FrameHeader, FrameSplitter};
+    use phonic_signal::PhonicError;
+
+    #[test]
+    fn headers_are_read_back() {
+        let block_sizes = [1, 192, 256, 576, 1000, 4096, 4608, 32768, 65535];
+        let sample_rates = [None, Some(44100), Some(22000), Some(11025), Some(100010)];
+        let channels = [
+            ChannelAssignment::Independent(1),
+            ChannelAssignment::Independent(8),
+            ChannelAssignment::LeftSide,
+            ChannelAssignment::SideRight,
+            ChannelAssignment::MidSide,
+        ];
+
+        for (i, block_size) in block_sizes.into_iter().enumerate() {
+            for (j, sample_rate) in sample_rates.into_iter().enumerate() {
+                let header = FrameHeader {
+                    variable_block_size: i % 2 == 0,
+                    number: (1 << (4 * i)) - 1,
+                    block_size,
+                    sample_rate,
+                    channels: channels[(i + j) % channels.len()],
+                    bits_per_sample: [None, Some(8), Some(16), Some(24)][j % 4],
+                };
+
+                let mut buf = Vec::new();
+                header.write(&mut buf);
+
+                let (read, len) = FrameHeader::read(&buf).unwrap();
+                assert_eq!(len, buf.len());
+                assert_eq!(read.variable_block_size, header.variable_block_size);
+                assert_eq!(read.number, header.number);
+                assert_eq!(read.block_size, header.block_size);
+                assert_eq!(read.sample_rate, header.sample_rate);
+                assert_eq!(read.channels, header.channels);
+                assert_eq!(read.bits_per_sample, header.bits_per_sample);
+
+                // a corrupted header fails the crc
+                *buf.last_mut().unwrap() ^= 1;
+                assert!(matches!(
+                    FrameHeader::read(&buf),
+                    Err(PhonicError::InvalidData { .. })
+                ));
+
+                // a truncated header needs more data
+                assert!(matches!(
+                    FrameHeader::read(&buf[..len - 1]),
+                    Err(PhonicError::MissingData { .. })
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn frames_are_split_at_the_next_header() {
+        let header = FrameHeader {
+            variable_block_size: true,
+            number: 0,
+            block_size: 16,
+            sample_rate: Some(48000),
+            channels: ChannelAssignment::Independent(1),
+            bits_per_sample: Some(16),
+        };
+
+        // a constant subframe of zero
+        let mut frame = Vec::new();
+        header.write(&mut frame);
+        frame.extend_from_slice(&[0, 0, 0]);
+        let crc = crc16(&frame);
+        frame.extend_from_slice(&crc.to_be_bytes());
+
+        let mut buf = frame.repeat(2);
+        let mut splitter = FrameSplitter::default();
+        assert_eq!(
+            splitter.frame_len(&buf[..frame.len()], false).unwrap(),
+            None
+        );
+        assert_eq!(splitter.frame_len(&buf, false).unwrap(), Some(frame.len()));
+
+        buf.drain(..frame.len());
+        splitter.reset();
+        assert_eq!(splitter.frame_len(&buf, false).unwrap(), None);
+        assert_eq!(splitter.frame_len(&buf, true).unwrap(), Some(frame.len()));
+    }
+}
@@ -0,0 +1,202 @@
+// https://www.rfc-editor.org/rfc/rfc1321
+
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, //
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, //
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, //
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+];
+
+const CONSTANTS: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+/// Computes the MD5 digest of the unencoded samples stored in the STREAMINFO block
+#[derive(Clone)]
+pub(crate) struct Md5 {
+    state: [u32; 4],
+    block: [u8; 64],
+    n_buffered: usize,
+    len: u64,
+}
+
+impl Md5 {
+    pub fn new() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            block: [0; 64],
+            n_buffered: 0,
+            len: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+
+        if self.n_buffered > 0 {
+            let n = bytes.len().min(64 - self.n_buffered);
+            self.block[self.n_buffered..self.n_buffered + n].copy_from_slice(&bytes[..n]);
+            self.n_buffered += n;
+            bytes = &bytes[n..];
+
+            if self.n_buffered < 64 {
+                return;
+            }
+
+            let block = self.block;
+            self.compress(&block);
+            self.n_buffered = 0;
+        }
+
+        let mut blocks = bytes.chunks_exact(64);
+        for block in &mut blocks {
+            self.compress(block);
+        }
+
+        let remainder = blocks.remainder();
+        self.block[..remainder.len()].copy_from_slice(remainder);
+        self.n_buffered = remainder.len();
+    }
+
+    /// Updates the digest with samples of `bits_per_sample` bits, each stored as a little endian
+    /// integer of the fewest bytes that can hold it
+    pub fn update_samples(&mut self, samples: impl IntoIterator<Item = i64>, bits_per_sample: u32) {
+        let n_bytes = bits_per_sample.div_ceil(8) as usize;
+        let mut buf = [0u8; 256];
+        let mut len = 0;
+
+        for sample in samples {
+            buf[len..len + n_bytes].copy_from_slice(&sample.to_le_bytes()[..n_bytes]);
+            len += n_bytes;
+
+            if len + n_bytes > buf.len() {
+                self.update(&buf[..len]);
+                len = 0;
+            }
+        }
+
+        self.update(&buf[..len]);
+    }
+
+    /// Returns the digest of the bytes written so far
+    pub fn digest(&self) -> [u8; 16] {
+        let mut md5 = self.clone();
+        let bit_len = self.len.wrapping_mul(8);
+
+        md5.update(&[0x80]);
+        while md5.n_buffered != 56 {
+            md5.update(&[0]);
+        }
+
+        md5.update(&bit_len.to_le_bytes());
+
+        let mut digest = [0u8; 16];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(md5.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut words = [0u32; 16];
+        for (word, bytes) in words.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_le_bytes(bytes.try_into().unwrap());
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => (b & c | !b & d, i),
+                1 => (d & b | !d & c, (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), 7 * i % 16),
+            };
+
+            let f = f
+                .wrapping_add(a)
+                .wrapping_add(CONSTANTS[i])
+                .wrapping_add(words[g]);
+
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(f.rotate_left(SHIFTS[i]));
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Md5;
+
+    fn hex(digest: [u8; 16]) -> String {
+        digest.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    #[test]
+    fn digests_match_the_reference_vectors() {
+        let vectors: [(&[u8], &str); 4] = [
+            (b"", "d41d8cd98f00b204e9800998ecf8427e"),
+            (b"abc", "900150983cd24fb0d6963f7d28e17f72"),
+            (
+                b"The quick brown fox jumps over the lazy dog",
+                "9e107d9d372bb6826bd81d3542a419d6",
+            ),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "57edf4a22be3c955ac49da2e2107b67a",
+            ),
+        ];
+
+        for (bytes, expected) in vectors {
+            let mut md5 = Md5::new();
+            md5.update(bytes);
+            assert_eq!(hex(md5.digest()), expected);
+
+            // the digest doesn't depend on how the input is split
+            let mut md5 = Md5::new();
+            for chunk in bytes.chunks(7) {
+                md5.update(chunk);
+            }
+
+            assert_eq!(hex(md5.digest()), expected);
+        }
+    }
+
+    #[test]
+    fn samples_are_hashed_as_little_endian_bytes() {
+        let samples = [0x123456, -2, 0x7FFFFF];
+
+        let mut expected = Md5::new();
+        expected.update(&[0x56, 0x34, 0x12, 0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]);
+
+        let mut md5 = Md5::new();
+        md5.update_samples(samples, 24);
+        assert_eq!(md5.digest(), expected.digest());
+
+        let mut expected = Md5::new();
+        expected.update(&[1, 0, 0xFF, 0xFF]);
+
+        let mut md5 = Md5::new();
+        md5.update_samples([1, -1], 12);
+        assert_eq!(md5.digest(), expected.digest());
+    }
+}
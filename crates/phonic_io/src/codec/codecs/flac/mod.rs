@@ -0,0 +1,20 @@
+mod bits;
+mod codec;
+mod encoder;
+mod frame;
+mod md5;
+mod sample;
+mod subframe;
+mod tag;
+
+pub use codec::*;
+pub use encoder::FlacCompression;
+pub use sample::*;
+pub use tag::*;
+
+pub(crate) use encoder::FrameEncoder;
+pub(crate) use frame::*;
+pub(crate) use md5::Md5;
+
+use bits::*;
+use subframe::*;
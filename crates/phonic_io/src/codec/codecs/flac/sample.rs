@@ -0,0 +1,95 @@
+use crate::codecs::pcm::ArbitrarySample;
+use phonic_signal::{I24, U24};
+
+/// Converts between samples and the signed integers stored in flac frames. Integer samples are
+/// aligned to their most significant bit and floating point samples are scaled to [-1, 1).
+pub trait FlacSample: ArbitrarySample {
+    fn from_flac(sample: i64, bits_per_sample: u32) -> Self;
+    fn into_flac(self, bits_per_sample: u32) -> i64;
+}
+
+#[inline]
+fn align(sample: i64, from_bits: u32, to_bits: u32) -> i64 {
+    if to_bits >= from_bits {
+        sample << (to_bits - from_bits)
+    } else {
+        sample >> (from_bits - to_bits)
+    }
+}
+
+macro_rules! impl_int_flac_sample {
+    ($signed:ty, $unsigned:ty) => {
+        impl FlacSample for $signed {
+            #[inline]
+            fn from_flac(sample: i64, bits_per_sample: u32) -> Self {
+                align(sample, bits_per_sample, <$signed>::BITS) as Self
+            }
+
+            #[inline]
+            fn into_flac(self, bits_per_sample: u32) -> i64 {
+                align(self as i64, <$signed>::BITS, bits_per_sample)
+            }
+        }
+
+        impl FlacSample for $unsigned {
+            #[inline]
+            fn from_flac(sample: i64, bits_per_sample: u32) -> Self {
+                <$signed>::from_flac(sample, bits_per_sample) as Self ^ (1 << (Self::BITS - 1))
+            }
+
+            #[inline]
+            fn into_flac(self, bits_per_sample: u32) -> i64 {
+                ((self ^ (1 << (Self::BITS - 1))) as $signed).into_flac(bits_per_sample)
+            }
+        }
+    };
+}
+
+macro_rules! impl_float_flac_sample {
+    ($float:ty) => {
+        impl FlacSample for $float {
+            #[inline]
+            fn from_flac(sample: i64, bits_per_sample: u32) -> Self {
+                sample as Self / (1u64 << (bits_per_sample - 1)) as Self
+            }
+
+            #[inline]
+            fn into_flac(self, bits_per_sample: u32) -> i64 {
+                let max = (1i64 << (bits_per_sample - 1)) as Self;
+                (self * max).round().clamp(-max, max - 1.0) as i64
+            }
+        }
+    };
+}
+
+impl_int_flac_sample!(i8, u8);
+impl_int_flac_sample!(i16, u16);
+impl_int_flac_sample!(i32, u32);
+impl_int_flac_sample!(i64, u64);
+
+impl FlacSample for I24 {
+    #[inline]
+    fn from_flac(sample: i64, bits_per_sample: u32) -> Self {
+        Self::new(align(sample, bits_per_sample, Self::BITS) as i32)
+    }
+
+    #[inline]
+    fn into_flac(self, bits_per_sample: u32) -> i64 {
+        align(self.get() as i64, Self::BITS, bits_per_sample)
+    }
+}
+
+impl FlacSample for U24 {
+    #[inline]
+    fn from_flac(sample: i64, bits_per_sample: u32) -> Self {
+        Self::new(I24::from_flac(sample, bits_per_sample).get() as u32 ^ (1 << 23))
+    }
+
+    #[inline]
+    fn into_flac(self, bits_per_sample: u32) -> i64 {
+        I24::new((self.get() ^ (1 << 23)) as i32).into_flac(bits_per_sample)
+    }
+}
+
+impl_float_flac_sample!(f32);
+impl_float_flac_sample!(f64);
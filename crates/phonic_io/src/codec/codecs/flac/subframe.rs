@@ -0,0 +1,144 @@
+// https://www.rfc-editor.org/rfc/rfc9639.html#name-subframes
+
+use crate::codecs::flac::BitReader;
+use phonic_signal::{PhonicError, PhonicResult};
+
+pub(super) const FIXED_COEFFICIENTS: [&[i64]; 5] =
+    [&[], &[1], &[2, -1], &[3, -3, 1], &[4, -6, 4, -1]];
+
+/// Decodes a subframe of `bits_per_sample` bits into `buf`, which holds one block of samples
+pub(super) fn decode_subframe(
+    reader: &mut BitReader,
+    bits_per_sample: u32,
+    buf: &mut [i64],
+) -> PhonicResult<()> {
+    if reader.read(1)? != 0 {
+        return Err(PhonicError::invalid_data());
+    }
+
+    let kind = reader.read(6)?;
+    let wasted_bits = match reader.read(1)? {
+        0 => 0,
+        _ => reader.read_unary()? as u32 + 1,
+    };
+
+    if wasted_bits >= bits_per_sample {
+        return Err(PhonicError::invalid_data());
+    }
+
+    let bits = bits_per_sample - wasted_bits;
+    match kind {
+        0b000000 => {
+            let sample = reader.read_signed(bits)?;
+            buf.fill(sample);
+        }
+        0b000001 => {
+            for sample in buf.iter_mut() {
+                *sample = reader.read_signed(bits)?;
+            }
+        }
+        0b001000..=0b001100 => {
+            let order = kind as usize - 0b001000;
+            decode_warmup(reader, bits, order, buf)?;
+            decode_residual(reader, order, buf)?;
+            predict(FIXED_COEFFICIENTS[order], 0, buf);
+        }
+        0b100000..=0b111111 => {
+            let order = kind as usize - 0b011111;
+            decode_warmup(reader, bits, order, buf)?;
+
+            let precision = match reader.read(4)? {
+                0b1111 => return Err(PhonicError::invalid_data()),
+                precision => precision as u32 + 1,
+            };
+
+            let shift = match reader.read_signed(5)? {
+                ..0 => return Err(PhonicError::invalid_data()),
+                shift => shift as u32,
+            };
+
+            let mut coefficients = [0i64; 32];
+            for coefficient in &mut coefficients[..order] {
+                *coefficient = reader.read_signed(precision)?;
+            }
+
+            decode_residual(reader, order, buf)?;
+            predict(&coefficients[..order], shift, buf);
+        }
+        _ => return Err(PhonicError::invalid_data()),
+    }
+
+    if wasted_bits > 0 {
+        buf.iter_mut().for_each(|sample| *sample <<= wasted_bits);
+    }
+
+    Ok(())
+}
+
+fn decode_warmup(
+    reader: &mut BitReader,
+    bits: u32,
+    order: usize,
+    buf: &mut [i64],
+) -> PhonicResult<()> {
+    if order > buf.len() {
+        return Err(PhonicError::invalid_data());
+    }
+
+    for sample in &mut buf[..order] {
+        *sample = reader.read_signed(bits)?;
+    }
+
+    Ok(())
+}
+
+/// Decodes the rice coded residual following `order` warmup samples
+fn decode_residual(reader: &mut BitReader, order: usize, buf: &mut [i64]) -> PhonicResult<()> {
+    let (param_bits, escape) = match reader.read(2)? {
+        0b00 => (4, 0b1111),
+        0b01 => (5, 0b11111),
+        _ => return Err(PhonicError::invalid_data()),
+    };
+
+    let partition_order = reader.read(4)? as u32;
+    let partition_len = buf.len() >> partition_order;
+    if partition_len << partition_order != buf.len() || partition_len < order {
+        return Err(PhonicError::invalid_data());
+    }
+
+    let mut i = order;
+    for partition in 0..1usize << partition_order {
+        let end = (partition + 1) * partition_len;
+        let param = reader.read(param_bits)? as u32;
+
+        if param == escape {
+            let bits = reader.read(5)? as u32;
+            for sample in &mut buf[i..end] {
+                *sample = reader.read_signed(bits)?;
+            }
+        } else {
+            for sample in &mut buf[i..end] {
+                let quotient = reader.read_unary()?;
+                let value = quotient << param | reader.read(param)?;
+                *sample = (value >> 1) as i64 ^ -((value & 1) as i64);
+            }
+        }
+
+        i = end;
+    }
+
+    Ok(())
+}
+
+/// Adds the prediction of each sample to the residual following the warmup samples
+fn predict(coefficients: &[i64], shift: u32, buf: &mut [i64]) {
+    let order = coefficients.len();
+    for i in order..buf.len() {
+        let prediction = coefficients
+            .iter()
+            .zip(buf[i - order..i].iter().rev())
+            .fold(0i64, |sum, (c, s)| sum.wrapping_add(c.wrapping_mul(*s)));
+
+        buf[i] = buf[i].wrapping_add(prediction >> shift);
+    }
+}
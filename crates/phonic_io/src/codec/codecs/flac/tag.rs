@@ -0,0 +1,225 @@
+use crate::{
+    codecs::flac::FlacCodec,
+    utils::{PollIo, UnWriteable},
+    CodecFromSignal, CodecFromStream, CodecTag, StreamSpec, StreamSpecBuilder, TypeLayout,
+};
+use phonic_signal::{utils::Poll, PhonicError, PhonicResult, SignalSpec, I24, U24};
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct FlacCodecTag;
+
+impl FlacCodecTag {
+    pub const MAX_CHANNELS: usize = 8;
+    pub const MAX_SAMPLE_RATE: usize = (1 << 20) - 1;
+
+    /// Returns the number of bits per sample used to encode samples of the given layout
+    pub fn bits_per_sample(layout: TypeLayout) -> Option<u32> {
+        match layout {
+            l if l.is::<i8>() || l.is::<u8>() => Some(8),
+            l if l.is::<i16>() || l.is::<u16>() => Some(16),
+            l if l.is::<I24>() || l.is::<U24>() => Some(24),
+            l if l.is::<i32>() || l.is::<u32>() => Some(32),
+            _ => None,
+        }
+    }
+
+    /// Returns the smallest sample layout that can hold samples of the given bit depth
+    pub fn sample_layout(bits_per_sample: u32) -> Option<TypeLayout> {
+        match bits_per_sample {
+            4..=8 => Some(TypeLayout::of::<i8>()),
+            9..=16 => Some(TypeLayout::of::<i16>()),
+            17..=32 => Some(TypeLayout::of::<i32>()),
+            _ => None,
+        }
+    }
+
+    pub fn infer_tagged_spec<C>(spec: StreamSpecBuilder<C>) -> PhonicResult<StreamSpec<C>>
+    where
+        C: CodecTag + TryInto<FlacCodecTag>,
+        FlacCodecTag: TryInto<C>,
+        PhonicError: From<<C as TryInto<FlacCodecTag>>::Error>,
+        PhonicError: From<<FlacCodecTag as TryInto<C>>::Error>,
+    {
+        let codec = spec
+            .codec
+            .map(TryInto::<FlacCodecTag>::try_into)
+            .transpose()?
+            .unwrap_or_default()
+            .try_into()?;
+
+        let sample = spec.sample.ok_or(PhonicError::missing_data())?;
+        if Self::bits_per_sample(sample).is_none() {
+            return Err(PhonicError::unsupported());
+        }
+
+        let decoded = spec.decoded.build()?;
+        let SignalSpec {
+            sample_rate,
+            n_channels,
+            ..
+        } = decoded;
+
+        if !(1..=Self::MAX_SAMPLE_RATE).contains(&sample_rate)
+            || !(1..=Self::MAX_CHANNELS).contains(&n_channels)
+        {
+            return Err(PhonicError::unsupported());
+        }
+
+        // flac streams are measured in decoded bytes since the size of encoded frames varies
+        let block_align = sample.size() * n_channels;
+        let byte_rate = block_align * sample_rate;
+
+        if spec.byte_rate.is_some_and(|rate| rate != byte_rate)
+            || spec.block_align.is_some_and(|align| align != block_align)
+        {
+            return Err(PhonicError::invalid_input());
+        }
+
+        Ok(StreamSpec {
+            codec,
+            byte_rate,
+            block_align,
+            sample,
+            valid_bits: None,
+            decoded,
+        })
+    }
+
+    #[cfg(feature = "dynamic")]
+    pub fn from_dyn_signal<C>(
+        tag: C,
+        signal: crate::dynamic::TaggedSignal,
+    ) -> PhonicResult<Box<dyn crate::dynamic::DynStream<Tag = C>>>
+    where
+        C: CodecTag + TryInto<FlacCodecTag> + 'static,
+        FlacCodecTag: TryInto<C>,
+        PhonicError: From<<C as TryInto<FlacCodecTag>>::Error>,
+        PhonicError: From<<FlacCodecTag as TryInto<C>>::Error>,
+    {
+        use crate::dynamic::TaggedSignal;
+
+        match signal {
+            TaggedSignal::I8(inner) => Ok(Box::new(PollIo(UnWriteable(FlacCodec::from_signal(
+                tag, inner,
+            )?)))),
+            TaggedSignal::I16(inner) => Ok(Box::new(PollIo(UnWriteable(FlacCodec::from_signal(
+                tag, inner,
+            )?)))),
+            TaggedSignal::I24(inner) => Ok(Box::new(PollIo(UnWriteable(FlacCodec::from_signal(
+                tag, inner,
+            )?)))),
+            TaggedSignal::I32(inner) => Ok(Box::new(PollIo(UnWriteable(FlacCodec::from_signal(
+                tag, inner,
+            )?)))),
+            TaggedSignal::U8(inner) => Ok(Box::new(PollIo(UnWriteable(FlacCodec::from_signal(
+                tag, inner,
+            )?)))),
+            TaggedSignal::U16(inner) => Ok(Box::new(PollIo(UnWriteable(FlacCodec::from_signal(
+                tag, inner,
+            )?)))),
+            TaggedSignal::U24(inner) => Ok(Box::new(PollIo(UnWriteable(FlacCodec::from_signal(
+                tag, inner,
+            )?)))),
+            TaggedSignal::U32(inner) => Ok(Box::new(PollIo(UnWriteable(FlacCodec::from_signal(
+                tag, inner,
+            )?)))),
+            _ => Err(PhonicError::unsupported()),
+        }
+    }
+
+    #[cfg(feature = "dynamic")]
+    pub fn from_dyn_stream<C>(
+        stream: Box<dyn crate::dynamic::DynStream<Tag = C>>,
+    ) -> PhonicResult<crate::dynamic::TaggedSignal>
+    where
+        C: CodecTag + TryInto<FlacCodecTag> + 'static,
+        FlacCodecTag: TryInto<C>,
+        PhonicError: From<<C as TryInto<FlacCodecTag>>::Error>,
+        PhonicError: From<<FlacCodecTag as TryInto<C>>::Error>,
+    {
+        use crate::dynamic::{KnownSampleType, TaggedSignal};
+
+        let sample_type = KnownSampleType::try_from(stream.stream_spec().sample.id())?;
+        let signal = match sample_type {
+            KnownSampleType::I8 => {
+                TaggedSignal::I8(Box::new(Poll(FlacCodec::from_stream(stream)?)))
+            }
+            KnownSampleType::I16 => {
+                TaggedSignal::I16(Box::new(Poll(FlacCodec::from_stream(stream)?)))
+            }
+            KnownSampleType::I24 => {
+                TaggedSignal::I24(Box::new(Poll(FlacCodec::from_stream(stream)?)))
+            }
+            KnownSampleType::I32 => {
+                TaggedSignal::I32(Box::new(Poll(FlacCodec::from_stream(stream)?)))
+            }
+            KnownSampleType::U8 => {
+                TaggedSignal::U8(Box::new(Poll(FlacCodec::from_stream(stream)?)))
+            }
+            KnownSampleType::U16 => {
+                TaggedSignal::U16(Box::new(Poll(FlacCodec::from_stream(stream)?)))
+            }
+            KnownSampleType::U24 => {
+                TaggedSignal::U24(Box::new(Poll(FlacCodec::from_stream(stream)?)))
+            }
+            KnownSampleType::U32 => {
+                TaggedSignal::U32(Box::new(Poll(FlacCodec::from_stream(stream)?)))
+            }
+            _ => return Err(PhonicError::unsupported()),
+        };
+
+        Ok(signal)
+    }
+}
+
+impl CodecTag for FlacCodecTag {
+    fn infer_spec(spec: StreamSpecBuilder<Self>) -> PhonicResult<StreamSpec<Self>> {
+        FlacCodecTag::infer_tagged_spec(spec)
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<FlacCodecTag> for crate::dynamic::KnownCodec {
+    fn from(tag: FlacCodecTag) -> Self {
+        match tag {
+            FlacCodecTag => Self::Flac,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<crate::dynamic::KnownCodec> for Option<FlacCodecTag> {
+    fn from(codec: crate::dynamic::KnownCodec) -> Self {
+        match codec {
+            crate::dynamic::KnownCodec::Flac => Some(FlacCodecTag),
+
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl TryFrom<crate::dynamic::KnownCodec> for FlacCodecTag {
+    type Error = PhonicError;
+
+    fn try_from(codec: crate::dynamic::KnownCodec) -> Result<Self, Self::Error> {
+        Option::<Self>::from(codec).ok_or(PhonicError::unsupported())
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<FlacCodecTag> for crate::dynamic::RegisteredCodec {
+    fn from(tag: FlacCodecTag) -> Self {
+        crate::dynamic::KnownCodec::from(tag).into()
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl TryFrom<crate::dynamic::RegisteredCodec> for FlacCodecTag {
+    type Error = PhonicError;
+
+    fn try_from(codec: crate::dynamic::RegisteredCodec) -> Result<Self, Self::Error> {
+        crate::dynamic::KnownCodec::try_from(codec)?.try_into()
+    }
+}
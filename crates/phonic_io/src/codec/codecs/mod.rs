@@ -1,2 +1,5 @@
+#[cfg(feature = "flac")]
+pub mod flac;
+
 #[cfg(feature = "pcm")]
 pub mod pcm;
@@ -1,14 +1,16 @@
-use phonic_signal::Sample;
+use phonic_signal::{Sample, I24, U24};
 
 pub unsafe trait ArbitrarySample: Sample {}
 
 unsafe impl ArbitrarySample for u8 {}
 unsafe impl ArbitrarySample for u16 {}
+unsafe impl ArbitrarySample for U24 {}
 unsafe impl ArbitrarySample for u32 {}
 unsafe impl ArbitrarySample for u64 {}
 
 unsafe impl ArbitrarySample for i8 {}
 unsafe impl ArbitrarySample for i16 {}
+unsafe impl ArbitrarySample for I24 {}
 unsafe impl ArbitrarySample for i32 {}
 unsafe impl ArbitrarySample for i64 {}
 
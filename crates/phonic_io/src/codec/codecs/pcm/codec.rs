@@ -1,21 +1,23 @@
 use crate::{
     codecs::pcm::{ArbitrarySample, Endianess, PcmCodecTag},
     CodecFromSignal, CodecFromStream, CodecTag, FiniteStream, IndexedStream, Stream, StreamReader,
-    StreamSeeker, StreamSpec, StreamSpecBuilder, StreamWriter,
+    StreamSeeker, StreamSpec, StreamSpecBuilder, StreamWriter, WakingStream,
 };
 use phonic_signal::{
     FiniteSignal, IndexedSignal, PhonicError, PhonicResult, Sample, Signal, SignalReader,
-    SignalSeeker, SignalSpec, SignalWriter,
+    SignalSeeker, SignalSpec, SignalWriter, WakingSignal,
 };
 use std::{
     marker::PhantomData,
     mem::{size_of, MaybeUninit},
+    task::Waker,
 };
 
 pub struct PcmCodec<T, S: Sample, C: CodecTag = PcmCodecTag> {
     inner: T,
     spec: StreamSpec<C>,
     endianess: Endianess,
+    swap_buf: Vec<u8>,
     _sample: PhantomData<S>,
 }
 
@@ -52,6 +54,7 @@ where
             inner,
             spec,
             endianess,
+            swap_buf: Vec::new(),
             _sample: PhantomData,
         })
     }
@@ -77,6 +80,7 @@ where
             inner,
             spec,
             endianess,
+            swap_buf: Vec::new(),
             _sample: PhantomData,
         })
     }
@@ -90,6 +94,12 @@ impl<T, S: Sample, C: CodecTag> Signal for PcmCodec<T, S, C> {
     }
 }
 
+impl<T: WakingStream, S: Sample, C: CodecTag> WakingSignal for PcmCodec<T, S, C> {
+    fn register_waker(&self, waker: &Waker) {
+        self.inner.register_waker(waker)
+    }
+}
+
 impl<T: IndexedStream, S: Sample, C: CodecTag> IndexedSignal for PcmCodec<T, S, C> {
     fn pos(&self) -> u64 {
         self.inner.pos() / size_of::<S>() as u64
@@ -133,7 +143,7 @@ where
 
         if !self.endianess.is_native() {
             for i in (0..n_bytes).step_by(size_of::<S>()) {
-                buf[i..i + size_of::<S>()].reverse()
+                aligned[i..i + size_of::<S>()].reverse()
             }
         }
 
@@ -149,11 +159,7 @@ where
     C: CodecTag,
 {
     fn write(&mut self, buf: &[Self::Sample]) -> PhonicResult<usize> {
-        if !self.endianess.is_native() {
-            return Err(PhonicError::unsupported());
-        }
-
-        let (prefix, aligned, suffix) = unsafe { buf.align_to::<u8>() };
+        let (prefix, mut aligned, suffix) = unsafe { buf.align_to::<u8>() };
         debug_assert!(prefix.is_empty() && suffix.is_empty());
 
         let aligned_len = aligned.len() - aligned.len() % self.spec.block_align;
@@ -161,6 +167,16 @@ where
             return Err(PhonicError::invalid_input());
         }
 
+        if !self.endianess.is_native() {
+            self.swap_buf.clear();
+            self.swap_buf.extend_from_slice(&aligned[..aligned_len]);
+            for sample in self.swap_buf.chunks_exact_mut(size_of::<S>()) {
+                sample.reverse();
+            }
+
+            aligned = &self.swap_buf;
+        }
+
         let mut n_bytes = 0;
         loop {
             match self.inner.write(&aligned[n_bytes..aligned_len]) {
@@ -198,6 +214,12 @@ impl<T, S: Sample, C: CodecTag> Stream for PcmCodec<T, S, C> {
     }
 }
 
+impl<T: WakingSignal, S: Sample, C: CodecTag> WakingStream for PcmCodec<T, S, C> {
+    fn register_waker(&self, waker: &Waker) {
+        self.inner.register_waker(waker)
+    }
+}
+
 impl<T, S, C> IndexedStream for PcmCodec<T, S, C>
 where
     T: IndexedSignal<Sample = S>,
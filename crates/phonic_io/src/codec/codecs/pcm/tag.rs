@@ -3,7 +3,7 @@ use crate::{
     utils::{PollIo, UnWriteable},
     CodecFromSignal, CodecFromStream, CodecTag, StreamSpec, StreamSpecBuilder,
 };
-use phonic_signal::{utils::Poll, PhonicError, PhonicResult, SignalSpec};
+use phonic_signal::{utils::Poll, PhonicError, PhonicResult};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum PcmCodecTag {
@@ -68,15 +68,26 @@ impl PcmCodecTag {
             n_channels * sample_layout.size()
         };
 
+        // a valid bit count equal to the size of the sample type is the same as none
+        let sample_bits = sample_layout.size() as u32 * 8;
+        let valid_bits = match spec.valid_bits {
+            Some(bits) if bits == 0 || bits > sample_bits => {
+                return Err(PhonicError::invalid_input())
+            }
+            bits => bits.filter(|bits| *bits < sample_bits),
+        };
+
         Ok(StreamSpec {
             codec,
             byte_rate: avg_byte_rate,
             block_align,
             sample: sample_layout,
-            decoded: SignalSpec {
-                sample_rate,
-                n_channels,
-            },
+            valid_bits,
+            decoded: spec
+                .decoded
+                .with_sample_rate(sample_rate)
+                .with_n_channels(n_channels)
+                .build()?,
         })
     }
 
@@ -112,6 +123,9 @@ impl PcmCodecTag {
             KnownSampleType::I16 => {
                 TaggedSignal::I16(Box::new(Poll(PcmCodec::from_stream(stream)?)))
             }
+            KnownSampleType::I24 => {
+                TaggedSignal::I24(Box::new(Poll(PcmCodec::from_stream(stream)?)))
+            }
             KnownSampleType::I32 => {
                 TaggedSignal::I32(Box::new(Poll(PcmCodec::from_stream(stream)?)))
             }
@@ -122,6 +136,9 @@ impl PcmCodecTag {
             KnownSampleType::U16 => {
                 TaggedSignal::U16(Box::new(Poll(PcmCodec::from_stream(stream)?)))
             }
+            KnownSampleType::U24 => {
+                TaggedSignal::U24(Box::new(Poll(PcmCodec::from_stream(stream)?)))
+            }
             KnownSampleType::U32 => {
                 TaggedSignal::U32(Box::new(Poll(PcmCodec::from_stream(stream)?)))
             }
@@ -188,6 +205,22 @@ impl TryFrom<crate::dynamic::KnownCodec> for PcmCodecTag {
     }
 }
 
+#[cfg(feature = "dynamic")]
+impl From<PcmCodecTag> for crate::dynamic::RegisteredCodec {
+    fn from(tag: PcmCodecTag) -> Self {
+        crate::dynamic::KnownCodec::from(tag).into()
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl TryFrom<crate::dynamic::RegisteredCodec> for PcmCodecTag {
+    type Error = PhonicError;
+
+    fn try_from(codec: crate::dynamic::RegisteredCodec) -> Result<Self, Self::Error> {
+        crate::dynamic::KnownCodec::try_from(codec)?.try_into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{codecs::pcm::PcmCodecTag, StreamSpec};
@@ -286,6 +319,7 @@ mod tests {
                     let spec = SignalSpec {
                         sample_rate: 48000,
                         n_channels: 2,
+                        layout: None,
                     };
 
                     let signal = Poll(Infinite(UnSeekable(Indexed::new(
@@ -1,9 +1,12 @@
 use crate::{
-    block_on_stream, BlockingStream, FiniteStream, IndexedStream, Stream, StreamReader,
-    StreamWriter,
+    block_on_stream, AsyncStreamReader, BlockingStream, FiniteStream, IndexedStream, Stream,
+    StreamReader, StreamWriter,
 };
 use phonic_signal::{utils::slice_as_init_mut, PhonicError, PhonicResult};
-use std::mem::MaybeUninit;
+use std::{
+    future::{poll_fn, Future},
+    mem::MaybeUninit,
+};
 
 pub trait StreamExt: Stream {
     fn rem(&self) -> u64
@@ -123,3 +126,58 @@ pub trait StreamExt: Stream {
 }
 
 impl<T: Stream> StreamExt for T {}
+
+pub trait AsyncStreamExt: Stream {
+    fn read_async<'a>(
+        &'a mut self,
+        buf: &'a mut [MaybeUninit<u8>],
+    ) -> impl Future<Output = PhonicResult<usize>> + 'a
+    where
+        Self: AsyncStreamReader,
+    {
+        poll_fn(move |cx| self.poll_read(cx, buf))
+    }
+
+    fn read_init_async<'a>(
+        &'a mut self,
+        buf: &'a mut [MaybeUninit<u8>],
+    ) -> impl Future<Output = PhonicResult<&'a mut [u8]>> + 'a
+    where
+        Self: AsyncStreamReader,
+    {
+        async move {
+            let n_bytes = self.read_async(buf).await?;
+            let uninit_slice = &mut buf[..n_bytes];
+            let init_slice = unsafe { slice_as_init_mut(uninit_slice) };
+
+            Ok(init_slice)
+        }
+    }
+
+    fn read_exact_async<'a>(
+        &'a mut self,
+        mut buf: &'a mut [MaybeUninit<u8>],
+    ) -> impl Future<Output = PhonicResult<()>> + 'a
+    where
+        Self: AsyncStreamReader,
+    {
+        async move {
+            if !buf.len().is_multiple_of(self.stream_spec().block_align) {
+                return Err(PhonicError::invalid_input());
+            }
+
+            while !buf.is_empty() {
+                match self.read_async(buf).await {
+                    Ok(0) => return Err(PhonicError::out_of_bounds()),
+                    Ok(n) => buf = &mut buf[n..],
+                    Err(PhonicError::Interrupted { .. }) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Stream> AsyncStreamExt for T {}
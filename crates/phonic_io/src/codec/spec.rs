@@ -1,5 +1,7 @@
 use crate::{CodecTag, TypeLayout};
-use phonic_signal::{PhonicError, PhonicResult, Sample, Signal, SignalSpec, SignalSpecBuilder};
+use phonic_signal::{
+    ChannelLayout, PhonicError, PhonicResult, Sample, Signal, SignalSpec, SignalSpecBuilder,
+};
 use std::fmt::Debug;
 
 #[derive(Debug, Clone, Copy)]
@@ -8,6 +10,11 @@ pub struct StreamSpec<C: CodecTag> {
     pub byte_rate: usize,
     pub block_align: usize,
     pub sample: TypeLayout,
+
+    /// The number of significant bits in each sample if it is fewer than the size of the sample
+    /// type, such as 24 bit audio in 32 bit containers. The significant bits are left justified,
+    /// so samples decode as the full sample type.
+    pub valid_bits: Option<u32>,
     pub decoded: SignalSpec,
 }
 
@@ -17,6 +24,7 @@ pub struct StreamSpecBuilder<C: CodecTag> {
     pub byte_rate: Option<usize>,
     pub block_align: Option<usize>,
     pub sample: Option<TypeLayout>,
+    pub valid_bits: Option<u32>,
     pub decoded: SignalSpecBuilder,
 }
 
@@ -39,6 +47,7 @@ impl<C: CodecTag> StreamSpec<C> {
             byte_rate: self.byte_rate,
             block_align: self.block_align,
             sample: self.sample,
+            valid_bits: self.valid_bits,
             decoded: self.decoded,
         }
     }
@@ -53,6 +62,7 @@ impl<C: CodecTag> StreamSpec<C> {
             byte_rate: self.byte_rate,
             block_align: self.block_align,
             sample: self.sample,
+            valid_bits: self.valid_bits,
             decoded: self.decoded,
         })
     }
@@ -64,12 +74,13 @@ impl<C: CodecTag> StreamSpec<C> {
         if self.codec != other.codec
             || self.byte_rate != other.byte_rate
             || self.sample != other.sample
+            || self.valid_bits != other.valid_bits
             || max_align % min_align != 0
-            || self.decoded != other.decoded
         {
             return Err(PhonicError::param_mismatch());
         }
 
+        self.decoded = self.decoded.merged(&other.decoded)?;
         self.block_align = max_align;
 
         Ok(())
@@ -96,6 +107,7 @@ impl<C: CodecTag> StreamSpecBuilder<C> {
             byte_rate: self.byte_rate,
             block_align: self.block_align,
             sample: self.sample,
+            valid_bits: self.valid_bits,
             decoded: self.decoded,
         }
     }
@@ -110,6 +122,7 @@ impl<C: CodecTag> StreamSpecBuilder<C> {
             byte_rate: self.byte_rate,
             block_align: self.block_align,
             sample: self.sample,
+            valid_bits: self.valid_bits,
             decoded: self.decoded,
         }
     }
@@ -124,6 +137,7 @@ impl<C: CodecTag> StreamSpecBuilder<C> {
             byte_rate: self.byte_rate,
             block_align: self.block_align,
             sample: self.sample,
+            valid_bits: self.valid_bits,
             decoded: self.decoded,
         })
     }
@@ -152,6 +166,11 @@ impl<C: CodecTag> StreamSpecBuilder<C> {
         self.with_sample_layout(TypeLayout::of::<T>())
     }
 
+    pub fn with_valid_bits(mut self, valid_bits: impl Into<Option<u32>>) -> Self {
+        self.valid_bits = valid_bits.into();
+        self
+    }
+
     pub fn with_decoded_spec(mut self, decoded_spec: impl Into<SignalSpecBuilder>) -> Self {
         self.decoded = decoded_spec.into();
         self
@@ -167,6 +186,11 @@ impl<C: CodecTag> StreamSpecBuilder<C> {
         self
     }
 
+    pub fn with_decoded_layout(mut self, layout: impl Into<Option<ChannelLayout>>) -> Self {
+        self.decoded.layout = layout.into();
+        self
+    }
+
     pub fn is_full(&self) -> bool {
         self.byte_rate.is_some()
             && self.block_align.is_some()
@@ -196,6 +220,13 @@ impl<C: CodecTag> StreamSpecBuilder<C> {
             return Err(PhonicError::param_mismatch());
         }
 
+        if other
+            .valid_bits
+            .is_some_and(|bits| *self.valid_bits.get_or_insert(bits) != bits)
+        {
+            return Err(PhonicError::param_mismatch());
+        }
+
         if let Some(align) = other.block_align {
             let self_align = self.block_align.unwrap_or(align);
             let min = align.min(self_align);
@@ -232,6 +263,7 @@ impl<C: CodecTag> Default for StreamSpecBuilder<C> {
             byte_rate: Default::default(),
             block_align: Default::default(),
             sample: Default::default(),
+            valid_bits: Default::default(),
             decoded: Default::default(),
         }
     }
@@ -246,6 +278,7 @@ impl<C: CodecTag> TryFrom<StreamSpecBuilder<C>> for StreamSpec<C> {
             byte_rate: spec.byte_rate.ok_or(PhonicError::missing_data())?,
             block_align: spec.block_align.ok_or(PhonicError::missing_data())?,
             sample: spec.sample.ok_or(PhonicError::missing_data())?,
+            valid_bits: spec.valid_bits,
             decoded: spec.decoded.build()?,
         })
     }
@@ -258,6 +291,7 @@ impl<C: CodecTag> From<StreamSpec<C>> for StreamSpecBuilder<C> {
             byte_rate: spec.byte_rate.into(),
             block_align: spec.block_align.into(),
             sample: spec.sample.into(),
+            valid_bits: spec.valid_bits,
             decoded: spec.decoded.into(),
         }
     }
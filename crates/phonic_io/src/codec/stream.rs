@@ -14,6 +14,12 @@ delegate_group! {
         fn block(&self);
     }
 
+    pub trait WakingStream: crate::Stream {
+        /// Registers a waker to be woken once an operation that returned
+        /// `Err(PhonicError::NotReady)` may succeed. See `phonic_signal::WakingSignal`.
+        fn register_waker(&self, waker: &std::task::Waker);
+    }
+
     pub trait IndexedStream: crate::Stream {
         /// retuns the number of bytes between the start and current position of the stream
         fn pos(&self) -> u64;
@@ -21,7 +27,8 @@ delegate_group! {
     }
 
     pub trait FiniteStream: crate::Stream {
-        /// returns the number of bytes between the start and end of the stream
+        /// returns the number of bytes between the start and end of the stream. for streams whose
+        /// end is not known in advance, such as streamed wave files, this is a lower bound
         fn len(&self) -> u64;
 
     }
@@ -34,6 +41,15 @@ delegate_group! {
         ) -> phonic_signal::PhonicResult<usize>;
     }
 
+    #[subgroup(Mut, Read, Async)]
+    pub trait AsyncStreamReader: crate::Stream {
+        fn poll_read(
+            &mut self,
+            cx: &mut std::task::Context<'_>,
+            buf: &mut [std::mem::MaybeUninit<u8>]
+        ) -> std::task::Poll<phonic_signal::PhonicResult<usize>>;
+    }
+
     #[subgroup(Mut, Read, Buffered)]
     pub trait BufferedStreamReader: crate::StreamReader {
         fn fill(&mut self) -> phonic_signal::PhonicResult<&[u8]>;
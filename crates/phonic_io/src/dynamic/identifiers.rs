@@ -1,4 +1,4 @@
-use crate::dynamic::KnownFormat;
+use crate::dynamic::{KnownFormat, Probe};
 use phonic_signal::PhonicError;
 use std::{collections::HashMap, ffi::OsStr, path::Path, sync::LazyLock};
 
@@ -6,6 +6,9 @@ use std::{collections::HashMap, ffi::OsStr, path::Path, sync::LazyLock};
 pub enum FormatIdentifier<'a> {
     FileExtension(&'a str),
     MimeType(&'a str),
+
+    /// The leading bytes of a source. See `Probe`.
+    Probe(&'a [u8]),
 }
 
 pub static KNOWN_FILE_EXT: LazyLock<HashMap<&'static str, KnownFormat>> = LazyLock::new(|| {
@@ -13,6 +16,20 @@ pub static KNOWN_FILE_EXT: LazyLock<HashMap<&'static str, KnownFormat>> = LazyLo
 
     let mut map = HashMap::new();
 
+    #[cfg(feature = "aiff")]
+    map.extend(
+        aiff::KNOWN_AIFF_FILE_EXTENSIONS
+            .into_iter()
+            .map(|ext| (ext, KnownFormat::Aiff)),
+    );
+
+    #[cfg(feature = "flac")]
+    map.extend(
+        flac::KNOWN_FLAC_FILE_EXTENSIONS
+            .into_iter()
+            .map(|ext| (ext, KnownFormat::Flac)),
+    );
+
     #[cfg(feature = "wave")]
     map.extend(
         wave::KNOWN_WAVE_FILE_EXTENSIONS
@@ -27,6 +44,20 @@ pub static KNOWN_MIME_TYPE: LazyLock<HashMap<&'static str, KnownFormat>> = LazyL
     use crate::formats::*;
     let mut map = HashMap::new();
 
+    #[cfg(feature = "aiff")]
+    map.extend(
+        aiff::KNOWN_AIFF_MIME_TYPES
+            .into_iter()
+            .map(|ext| (ext, KnownFormat::Aiff)),
+    );
+
+    #[cfg(feature = "flac")]
+    map.extend(
+        flac::KNOWN_FLAC_MIME_TYPES
+            .into_iter()
+            .map(|ext| (ext, KnownFormat::Flac)),
+    );
+
     #[cfg(feature = "wave")]
     map.extend(
         wave::KNOWN_WAVE_MIME_TYPES
@@ -42,6 +73,7 @@ impl FormatIdentifier<'_> {
         let entry = match self {
             Self::FileExtension(ext) => KNOWN_FILE_EXT.get(ext),
             Self::MimeType(mime) => KNOWN_MIME_TYPE.get(mime),
+            Self::Probe(bytes) => return Probe::from_bytes(bytes).best()?.known_format(),
         };
 
         entry.copied()
@@ -8,6 +8,9 @@ use std::hash::Hash;
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 #[non_exhaustive]
 pub enum KnownCodec {
+    #[cfg(feature = "flac")]
+    Flac,
+
     #[cfg(feature = "pcm")]
     PcmLE,
 
@@ -20,6 +23,9 @@ impl CodecTag for KnownCodec {
         use crate::codecs::*;
 
         match spec.codec {
+            #[cfg(feature = "flac")]
+            Some(Self::Flac) => flac::FlacCodecTag::infer_tagged_spec(spec),
+
             #[cfg(feature = "pcm")]
             Some(Self::PcmLE | Self::PcmBE) => pcm::PcmCodecTag::infer_tagged_spec(spec),
 
@@ -33,6 +39,9 @@ impl DynCodecConstructor for KnownCodec {
         use crate::codecs::*;
 
         match self {
+            #[cfg(feature = "flac")]
+            Self::Flac => flac::FlacCodecTag::from_dyn_signal(*self, signal),
+
             #[cfg(feature = "pcm")]
             Self::PcmLE | Self::PcmBE => pcm::PcmCodecTag::from_dyn_signal(*self, signal),
 
@@ -45,6 +54,9 @@ impl DynCodecConstructor for KnownCodec {
         use crate::codecs::*;
 
         match stream.stream_spec().codec {
+            #[cfg(feature = "flac")]
+            Self::Flac => flac::FlacCodecTag::from_dyn_stream(stream),
+
             #[cfg(feature = "pcm")]
             Self::PcmLE | Self::PcmBE => pcm::PcmCodecTag::from_dyn_stream(stream),
 
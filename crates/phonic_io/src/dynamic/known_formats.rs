@@ -8,6 +8,12 @@ use phonic_signal::PhonicResult;
 #[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
 #[non_exhaustive]
 pub enum KnownFormat {
+    #[cfg(feature = "aiff")]
+    Aiff,
+
+    #[cfg(feature = "flac")]
+    Flac,
+
     #[cfg(feature = "wave")]
     Wave,
 }
@@ -23,7 +29,13 @@ impl DynFormatConstructor for KnownFormat {
     {
         use crate::formats::*;
 
-        Ok(match self {
+        Ok(match *self {
+            #[cfg(feature = "aiff")]
+            Self::Aiff => Box::new(PollIo(aiff::AiffFormat::read_index(inner)?)),
+
+            #[cfg(feature = "flac")]
+            Self::Flac => Box::new(PollIo(flac::FlacFormat::read_index(inner)?)),
+
             #[cfg(feature = "wave")]
             Self::Wave => Box::new(PollIo(wave::WaveFormat::read_index(inner)?)),
         })
@@ -36,7 +48,13 @@ impl DynFormatConstructor for KnownFormat {
     {
         use crate::formats::*;
 
-        Ok(match self {
+        Ok(match *self {
+            #[cfg(feature = "aiff")]
+            Self::Aiff => Box::new(PollIo(aiff::AiffFormat::write_index(inner, index)?)),
+
+            #[cfg(feature = "flac")]
+            Self::Flac => Box::new(PollIo(flac::FlacFormat::write_index(inner, index)?)),
+
             #[cfg(feature = "wave")]
             Self::Wave => Box::new(PollIo(wave::WaveFormat::write_index(inner, index)?)),
         })
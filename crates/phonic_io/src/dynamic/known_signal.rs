@@ -1,16 +1,18 @@
 use crate::dynamic::DynSignal;
-use phonic_signal::{PhonicError, Sample, Signal, SignalSpec};
+use phonic_signal::{PhonicError, Sample, Signal, SignalSpec, I24, U24};
 use std::{any::TypeId, mem::size_of};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum KnownSampleType {
     I8,
     I16,
+    I24,
     I32,
     I64,
 
     U8,
     U16,
+    U24,
     U32,
     U64,
 
@@ -25,11 +27,13 @@ pub trait KnownSample: Sample {
 pub enum TaggedSignal {
     I8(Box<dyn DynSignal<Sample = i8>>),
     I16(Box<dyn DynSignal<Sample = i16>>),
+    I24(Box<dyn DynSignal<Sample = I24>>),
     I32(Box<dyn DynSignal<Sample = i32>>),
     I64(Box<dyn DynSignal<Sample = i64>>),
 
     U8(Box<dyn DynSignal<Sample = u8>>),
     U16(Box<dyn DynSignal<Sample = u16>>),
+    U24(Box<dyn DynSignal<Sample = U24>>),
     U32(Box<dyn DynSignal<Sample = u32>>),
     U64(Box<dyn DynSignal<Sample = u64>>),
 
@@ -42,10 +46,12 @@ impl KnownSampleType {
         match self {
             KnownSampleType::I8 => TypeId::of::<i8>(),
             KnownSampleType::I16 => TypeId::of::<i16>(),
+            KnownSampleType::I24 => TypeId::of::<I24>(),
             KnownSampleType::I32 => TypeId::of::<i32>(),
             KnownSampleType::I64 => TypeId::of::<i64>(),
             KnownSampleType::U8 => TypeId::of::<u8>(),
             KnownSampleType::U16 => TypeId::of::<u16>(),
+            KnownSampleType::U24 => TypeId::of::<U24>(),
             KnownSampleType::U32 => TypeId::of::<u32>(),
             KnownSampleType::U64 => TypeId::of::<u64>(),
             KnownSampleType::F32 => TypeId::of::<f32>(),
@@ -57,10 +63,12 @@ impl KnownSampleType {
         match self {
             Self::I8 => size_of::<i8>(),
             Self::I16 => size_of::<i16>(),
+            Self::I24 => size_of::<I24>(),
             Self::I32 => size_of::<i32>(),
             Self::I64 => size_of::<i64>(),
             Self::U8 => size_of::<u8>(),
             Self::U16 => size_of::<u16>(),
+            Self::U24 => size_of::<U24>(),
             Self::U32 => size_of::<u32>(),
             Self::U64 => size_of::<u64>(),
             Self::F32 => size_of::<f32>(),
@@ -72,10 +80,12 @@ impl KnownSampleType {
         match self {
             Self::I8 => align_of::<i8>(),
             Self::I16 => align_of::<i16>(),
+            Self::I24 => align_of::<I24>(),
             Self::I32 => align_of::<i32>(),
             Self::I64 => align_of::<i64>(),
             Self::U8 => align_of::<u8>(),
             Self::U16 => align_of::<u16>(),
+            Self::U24 => align_of::<U24>(),
             Self::U32 => align_of::<u32>(),
             Self::U64 => align_of::<u64>(),
             Self::F32 => align_of::<f32>(),
@@ -91,10 +101,12 @@ impl TryFrom<TypeId> for KnownSampleType {
         Ok(match id {
             id if id == TypeId::of::<i8>() => Self::I8,
             id if id == TypeId::of::<i16>() => Self::I16,
+            id if id == TypeId::of::<I24>() => Self::I24,
             id if id == TypeId::of::<i32>() => Self::I32,
             id if id == TypeId::of::<i64>() => Self::I64,
             id if id == TypeId::of::<u8>() => Self::U8,
             id if id == TypeId::of::<u16>() => Self::U16,
+            id if id == TypeId::of::<U24>() => Self::U24,
             id if id == TypeId::of::<u32>() => Self::U32,
             id if id == TypeId::of::<u64>() => Self::U64,
             id if id == TypeId::of::<f32>() => Self::F32,
@@ -120,10 +132,12 @@ macro_rules! impl_known_sample {
 
 impl_known_sample!(i8, I8);
 impl_known_sample!(i16, I16);
+impl_known_sample!(I24, I24);
 impl_known_sample!(i32, I32);
 impl_known_sample!(i64, I64);
 impl_known_sample!(u8, U8);
 impl_known_sample!(u16, U16);
+impl_known_sample!(U24, U24);
 impl_known_sample!(u32, U32);
 impl_known_sample!(u64, U64);
 impl_known_sample!(f32, F32);
@@ -135,10 +149,12 @@ macro_rules! match_tagged_signal {
         match $signal {
             $crate::dynamic::TaggedSignal::I8($inner) => $rhs,
             $crate::dynamic::TaggedSignal::I16($inner) => $rhs,
+            $crate::dynamic::TaggedSignal::I24($inner) => $rhs,
             $crate::dynamic::TaggedSignal::I32($inner) => $rhs,
             $crate::dynamic::TaggedSignal::I64($inner) => $rhs,
             $crate::dynamic::TaggedSignal::U8($inner) => $rhs,
             $crate::dynamic::TaggedSignal::U16($inner) => $rhs,
+            $crate::dynamic::TaggedSignal::U24($inner) => $rhs,
             $crate::dynamic::TaggedSignal::U32($inner) => $rhs,
             $crate::dynamic::TaggedSignal::U64($inner) => $rhs,
             $crate::dynamic::TaggedSignal::F32($inner) => $rhs,
@@ -171,11 +187,13 @@ macro_rules! impl_from_inner {
 impl TaggedSignal {
     impl_unwrap!(unwrap_i8, i8, I8);
     impl_unwrap!(unwrap_i16, i16, I16);
+    impl_unwrap!(unwrap_i24, I24, I24);
     impl_unwrap!(unwrap_i32, i32, I32);
     impl_unwrap!(unwrap_i64, i64, I64);
 
     impl_unwrap!(unwrap_u8, u8, U8);
     impl_unwrap!(unwrap_u16, u16, U16);
+    impl_unwrap!(unwrap_u24, U24, U24);
     impl_unwrap!(unwrap_u32, u32, U32);
     impl_unwrap!(unwrap_u64, u64, U64);
 
@@ -190,10 +208,12 @@ impl TaggedSignal {
         match self {
             TaggedSignal::I8(_) => KnownSampleType::I8,
             TaggedSignal::I16(_) => KnownSampleType::I16,
+            TaggedSignal::I24(_) => KnownSampleType::I24,
             TaggedSignal::I32(_) => KnownSampleType::I32,
             TaggedSignal::I64(_) => KnownSampleType::I64,
             TaggedSignal::U8(_) => KnownSampleType::U8,
             TaggedSignal::U16(_) => KnownSampleType::U16,
+            TaggedSignal::U24(_) => KnownSampleType::U24,
             TaggedSignal::U32(_) => KnownSampleType::U32,
             TaggedSignal::U64(_) => KnownSampleType::U64,
             TaggedSignal::F32(_) => KnownSampleType::F32,
@@ -204,11 +224,13 @@ impl TaggedSignal {
 
 impl_from_inner!(i8, I8);
 impl_from_inner!(i16, I16);
+impl_from_inner!(I24, I24);
 impl_from_inner!(i32, I32);
 impl_from_inner!(i64, I64);
 
 impl_from_inner!(u8, U8);
 impl_from_inner!(u16, U16);
+impl_from_inner!(U24, U24);
 impl_from_inner!(u32, U32);
 impl_from_inner!(u64, U64);
 
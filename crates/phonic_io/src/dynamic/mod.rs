@@ -4,6 +4,8 @@ mod identifiers;
 mod known_codecs;
 mod known_formats;
 mod known_signal;
+mod probe;
+mod registry;
 
 pub use construct::*;
 pub use dyn_traits::*;
@@ -11,3 +13,5 @@ pub use identifiers::*;
 pub use known_codecs::*;
 pub use known_formats::*;
 pub use known_signal::*;
+pub use probe::*;
+pub use registry::*;
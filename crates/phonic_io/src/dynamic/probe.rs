@@ -0,0 +1,424 @@
+use crate::dynamic::KnownFormat;
+use phonic_signal::{PhonicError, PhonicResult};
+use std::io::{Read, Seek, SeekFrom};
+
+/// The number of leading bytes read from a source when probing its format
+pub const PROBE_LEN: usize = 64;
+
+/// A container format that can be recognized from the leading bytes of a source. Formats that are
+/// not enabled or not supported by this crate are included so that rejected sources can be
+/// reported by what they look like.
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+#[non_exhaustive]
+pub enum ProbedFormat {
+    Aiff,
+    Caf,
+    Flac,
+    Mp4,
+    Mpeg,
+    Ogg,
+    Rf64,
+    Wave,
+}
+
+/// A format that matched the leading bytes of a source, along with a confidence in the range
+/// `0.0..=1.0`. A confidence of `1.0` means the magic bytes and the first header in the source
+/// were both recognized.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct ProbeMatch {
+    pub format: ProbedFormat,
+    pub confidence: f32,
+}
+
+/// Every format that matched the leading bytes of a source, ordered by descending confidence.
+/// Formats with equal confidence are kept in the order they are declared in `ProbedFormat`.
+#[derive(Clone, Debug, Default)]
+pub struct Probe {
+    matches: Vec<ProbeMatch>,
+}
+
+impl ProbedFormat {
+    const ALL: [Self; 8] = [
+        Self::Aiff,
+        Self::Caf,
+        Self::Flac,
+        Self::Mp4,
+        Self::Mpeg,
+        Self::Ogg,
+        Self::Rf64,
+        Self::Wave,
+    ];
+
+    /// The enabled format that can read sources of this format
+    pub fn known_format(self) -> Option<KnownFormat> {
+        match self {
+            #[cfg(feature = "aiff")]
+            Self::Aiff => Some(KnownFormat::Aiff),
+
+            #[cfg(feature = "flac")]
+            Self::Flac => Some(KnownFormat::Flac),
+
+            #[cfg(feature = "wave")]
+            Self::Rf64 | Self::Wave => Some(KnownFormat::Wave),
+
+            _ => None,
+        }
+    }
+
+    /// Scores how closely the leading bytes of a source match this format
+    pub fn score(self, bytes: &[u8]) -> f32 {
+        match self {
+            Self::Aiff => score_aiff(bytes),
+            Self::Caf => score_caf(bytes),
+            Self::Flac => score_flac(bytes),
+            Self::Mp4 => score_mp4(bytes),
+            Self::Mpeg => score_mpeg(bytes),
+            Self::Ogg => score_ogg(bytes),
+            Self::Rf64 => score_riff(bytes, [b"RF64", b"BW64"]),
+            Self::Wave => score_riff(bytes, [b"RIFF", b"RIFF"]),
+        }
+    }
+}
+
+impl ProbeMatch {
+    pub fn known_format(&self) -> Option<KnownFormat> {
+        self.format.known_format()
+    }
+}
+
+impl Probe {
+    /// Scores every format against the leading bytes of a source
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut matches = ProbedFormat::ALL
+            .into_iter()
+            .map(|format| ProbeMatch {
+                format,
+                confidence: format.score(bytes),
+            })
+            .filter(|m| m.confidence > 0.0)
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        Self { matches }
+    }
+
+    /// Reads up to `PROBE_LEN` bytes from the reader, scores every format against them, and seeks
+    /// the reader back to where it started.
+    pub fn read<R: Read + Seek>(reader: &mut R) -> PhonicResult<Self> {
+        let start = reader.stream_position()?;
+        let mut bytes = Vec::with_capacity(PROBE_LEN);
+        reader
+            .by_ref()
+            .take(PROBE_LEN as u64)
+            .read_to_end(&mut bytes)?;
+
+        reader.seek(SeekFrom::Start(start))?;
+        Ok(Self::from_bytes(&bytes))
+    }
+
+    pub fn matches(&self) -> &[ProbeMatch] {
+        &self.matches
+    }
+
+    /// The most likely format that is enabled
+    pub fn best(&self) -> Option<ProbeMatch> {
+        self.matches
+            .iter()
+            .find(|m| m.known_format().is_some())
+            .copied()
+    }
+
+    /// The most likely format, whether it is enabled or not. This can be used to report what a
+    /// source looked like when `best` returns `None`.
+    pub fn looks_like(&self) -> Option<ProbeMatch> {
+        self.matches.first().copied()
+    }
+}
+
+impl TryFrom<&Probe> for KnownFormat {
+    type Error = PhonicError;
+
+    fn try_from(probe: &Probe) -> Result<Self, Self::Error> {
+        match probe.best() {
+            Some(m) => Ok(m.known_format().unwrap()),
+            None if probe.looks_like().is_some() => Err(PhonicError::unsupported()),
+            None => Err(PhonicError::not_found()),
+        }
+    }
+}
+
+fn is_chunk_id(id: &[u8]) -> bool {
+    id.len() == 4 && id.iter().all(|b| (0x20..0x7F).contains(b))
+}
+
+fn score_riff(bytes: &[u8], riff_ids: [&[u8; 4]; 2]) -> f32 {
+    let Some(riff_id) = bytes.get(0..4) else {
+        return 0.0;
+    };
+
+    if !riff_ids.iter().any(|id| id[..] == *riff_id) {
+        return 0.0;
+    }
+
+    let mut score = 0.25;
+    if !matches!(bytes.get(8..12), Some(b"WAVE")) {
+        return score;
+    }
+
+    score += 0.5;
+    match bytes.get(12..16) {
+        Some(b"fmt " | b"ds64") => score += 0.25,
+        Some(id) if is_chunk_id(id) => score += 0.125,
+        _ => {}
+    }
+
+    score
+}
+
+fn score_aiff(bytes: &[u8]) -> f32 {
+    if !matches!(bytes.get(0..4), Some(b"FORM")) {
+        return 0.0;
+    }
+
+    let mut score = 0.25;
+    if !matches!(bytes.get(8..12), Some(b"AIFF" | b"AIFC")) {
+        return score;
+    }
+
+    score += 0.5;
+    match bytes.get(12..16) {
+        Some(b"COMM" | b"FVER") => score += 0.25,
+        Some(id) if is_chunk_id(id) => score += 0.125,
+        _ => {}
+    }
+
+    score
+}
+
+fn score_flac(bytes: &[u8]) -> f32 {
+    if !matches!(bytes.get(0..4), Some(b"fLaC")) {
+        return 0.0;
+    }
+
+    // the first metadata block must be a STREAMINFO block with a length of 34 bytes
+    match bytes.get(4..8) {
+        Some([block_type, 0, 0, 34]) if block_type & 0x7F == 0 => 1.0,
+        _ => 0.5,
+    }
+}
+
+fn score_caf(bytes: &[u8]) -> f32 {
+    if !matches!(bytes.get(0..4), Some(b"caff")) {
+        return 0.0;
+    }
+
+    match bytes.get(4..6) {
+        Some([0, 1]) => 1.0,
+        _ => 0.5,
+    }
+}
+
+fn score_mp4(bytes: &[u8]) -> f32 {
+    if !matches!(bytes.get(4..8), Some(b"ftyp")) {
+        return 0.0;
+    }
+
+    match bytes.get(8..12) {
+        Some(brand) if is_chunk_id(brand) => 0.75,
+        _ => 0.5,
+    }
+}
+
+fn score_ogg(bytes: &[u8]) -> f32 {
+    if !matches!(bytes.get(0..4), Some(b"OggS")) {
+        return 0.0;
+    }
+
+    // the first page must use version 0 and begin a logical bitstream
+    match bytes.get(4..6) {
+        Some([0, header_type]) if header_type & 0x02 != 0 => 1.0,
+        _ => 0.5,
+    }
+}
+
+fn score_mpeg(bytes: &[u8]) -> f32 {
+    if let Some([b'I', b'D', b'3', major, minor, _, size @ ..]) = bytes.get(0..10) {
+        let syncsafe = size.iter().all(|b| b & 0x80 == 0);
+        return if *major < 0xFF && *minor < 0xFF && syncsafe {
+            0.75
+        } else {
+            0.25
+        };
+    }
+
+    // an unframed stream must begin with a valid frame header
+    let Some(&[a, b, c, _]) = bytes.get(0..4) else {
+        return 0.0;
+    };
+
+    let sync = a == 0xFF && b & 0xE0 == 0xE0;
+    let version = (b >> 3) & 0b11;
+    let layer = (b >> 1) & 0b11;
+    let bitrate = c >> 4;
+    let sample_rate = (c >> 2) & 0b11;
+
+    if sync && version != 0b01 && layer != 0b00 && bitrate != 0xF && sample_rate != 0b11 {
+        0.25
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Probe, ProbeMatch, ProbedFormat};
+    use crate::dynamic::KnownFormat;
+    use phonic_signal::PhonicError;
+    use std::io::{Cursor, Read};
+
+    fn matches(bytes: &[u8]) -> Vec<(ProbedFormat, f32)> {
+        Probe::from_bytes(bytes)
+            .matches()
+            .iter()
+            .map(|ProbeMatch { format, confidence }| (*format, *confidence))
+            .collect()
+    }
+
+    #[test]
+    fn every_format_is_recognized_by_its_signature() {
+        let cases: [(&[u8], ProbedFormat, f32); 10] = [
+            (b"FORM\0\0\0\x2EAIFFCOMM", ProbedFormat::Aiff, 1.0),
+            (b"FORM\0\0\0\x2EAIFCFVER", ProbedFormat::Aiff, 1.0),
+            (b"caff\0\x01\0\0desc", ProbedFormat::Caf, 1.0),
+            (b"fLaC\x80\0\0\x22", ProbedFormat::Flac, 1.0),
+            (b"\0\0\0\x20ftypisom", ProbedFormat::Mp4, 0.75),
+            (b"ID3\x04\0\0\0\0\x01\x7F", ProbedFormat::Mpeg, 0.75),
+            (b"\xFF\xFB\x90\x64", ProbedFormat::Mpeg, 0.25),
+            (b"OggS\0\x02\0\0", ProbedFormat::Ogg, 1.0),
+            (b"BW64\xFF\xFF\xFF\xFFWAVEds64", ProbedFormat::Rf64, 1.0),
+            (b"RIFF\x24\0\0\0WAVEfmt ", ProbedFormat::Wave, 1.0),
+        ];
+
+        for (bytes, format, confidence) in cases {
+            assert_eq!(matches(bytes), [(format, confidence)], "{format:?}");
+        }
+    }
+
+    #[test]
+    fn equal_confidences_keep_the_declaration_order() {
+        // an id3 tag whose flags and size bytes happen to spell an mp4 file type box
+        let bytes = b"ID3\x04ftypisom";
+        assert_eq!(
+            matches(bytes),
+            [(ProbedFormat::Mp4, 0.75), (ProbedFormat::Mpeg, 0.75)]
+        );
+
+        let probe = Probe::from_bytes(bytes);
+        assert_eq!(probe.looks_like().unwrap().format, ProbedFormat::Mp4);
+    }
+
+    #[test]
+    fn ambiguous_sources_match_with_lower_confidence() {
+        // riff containers that aren't wave files
+        assert_eq!(
+            matches(b"RIFF\0\0\0\0AVI LIST"),
+            [(ProbedFormat::Wave, 0.25)]
+        );
+
+        // wave files whose first chunk isn't a format chunk
+        assert_eq!(
+            matches(b"RIFF\0\0\0\0WAVEJUNK"),
+            [(ProbedFormat::Wave, 0.875)]
+        );
+
+        assert_eq!(
+            matches(b"RIFF\0\0\0\0WAVE\0\0\0\0"),
+            [(ProbedFormat::Wave, 0.75)]
+        );
+
+        assert_eq!(matches(b"FORM\0\0\0\0SVX8"), [(ProbedFormat::Aiff, 0.25)]);
+
+        // the first metadata block isn't streaminfo
+        assert_eq!(matches(b"fLaC\x04\0\0\x22"), [(ProbedFormat::Flac, 0.5)]);
+
+        assert_eq!(matches(b"OggS\x01\x02"), [(ProbedFormat::Ogg, 0.5)]);
+        assert_eq!(
+            matches(b"ID3\x04\0\0\x80\0\0\0"),
+            [(ProbedFormat::Mpeg, 0.25)]
+        );
+
+        // frame syncs with a reserved version, layer, bitrate or sample rate
+        for header in [
+            b"\xFF\xEB\x90\x64",
+            b"\xFF\xF9\x90\x64",
+            b"\xFF\xFB\xF0\x64",
+            b"\xFF\xFB\x9C\x64",
+        ] {
+            assert!(matches(header).is_empty());
+        }
+    }
+
+    #[test]
+    fn sources_shorter_than_a_header_are_probed() {
+        assert!(matches(b"").is_empty());
+        assert!(matches(b"RIF").is_empty());
+        assert!(matches(b"\xFF\xFB").is_empty());
+        assert!(matches(b"ID3\x04").is_empty());
+
+        assert_eq!(matches(b"RIFF"), [(ProbedFormat::Wave, 0.25)]);
+        assert_eq!(matches(b"FORM\0\0\0\0AIFF"), [(ProbedFormat::Aiff, 0.75)]);
+        assert_eq!(matches(b"fLaC"), [(ProbedFormat::Flac, 0.5)]);
+        assert_eq!(matches(b"caff"), [(ProbedFormat::Caf, 0.5)]);
+        assert_eq!(matches(b"\0\0\0\x20ftyp"), [(ProbedFormat::Mp4, 0.5)]);
+
+        let mut cursor = Cursor::new(b"fLaC".to_vec());
+        let probe = Probe::read(&mut cursor).unwrap();
+        assert_eq!(probe.looks_like().unwrap().format, ProbedFormat::Flac);
+        assert_eq!(cursor.position(), 0);
+
+        assert!(matches!(
+            KnownFormat::try_from(&Probe::from_bytes(b"")),
+            Err(PhonicError::NotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn probe_rewinds_and_reports_unsupported_formats() {
+        let mut bytes = b"RIFF\x24\0\0\0WAVEfmt \x10\0\0\0".to_vec();
+        bytes.resize(128, 0);
+
+        let mut cursor = Cursor::new(bytes);
+
+        let probe = Probe::read(&mut cursor).unwrap();
+        let best = probe.looks_like().unwrap();
+        assert_eq!(best.format, ProbedFormat::Wave);
+        assert_eq!(best.confidence, 1.0);
+        assert_eq!(cursor.position(), 0);
+
+        let mut header = [0u8; 4];
+        cursor.read_exact(&mut header).unwrap();
+        assert_eq!(&header, b"RIFF");
+
+        let probe = Probe::from_bytes(b"OggS\0\x02\0\0\0\0\0\0\0\0");
+        assert!(probe.best().is_none());
+        assert_eq!(probe.looks_like().unwrap().format, ProbedFormat::Ogg);
+
+        let probe = Probe::from_bytes(b"not audio");
+        assert!(probe.looks_like().is_none());
+    }
+
+    #[cfg(feature = "wave")]
+    #[test]
+    fn rf64_is_read_as_wave() {
+        for riff_id in [b"RF64", b"BW64"] {
+            let mut bytes = riff_id.to_vec();
+            bytes.extend_from_slice(b"\xFF\xFF\xFF\xFFWAVEds64\x1C\0\0\0");
+
+            let probe = Probe::from_bytes(&bytes);
+            let best = probe.best().unwrap();
+            assert_eq!(best.format, ProbedFormat::Rf64);
+            assert_eq!(best.confidence, 1.0);
+            assert_eq!(KnownFormat::try_from(&probe).unwrap(), KnownFormat::Wave);
+        }
+    }
+}
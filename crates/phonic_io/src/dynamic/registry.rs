@@ -0,0 +1,358 @@
+use crate::{
+    dynamic::{
+        DynCodecConstructor, DynFormat, DynFormatConstructor, DynStream, FormatIdentifier,
+        KnownCodec, StdIoSource, TaggedSignal,
+    },
+    CodecTag, FormatTag, StreamSpec, StreamSpecBuilder,
+};
+use phonic_signal::{PhonicError, PhonicResult};
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+pub type ReadIndexFn =
+    fn(Box<dyn StdIoSource>) -> PhonicResult<Box<dyn DynFormat<Tag = RegisteredFormat>>>;
+
+pub type WriteIndexFn = fn(
+    Box<dyn StdIoSource>,
+    Vec<StreamSpec<RegisteredCodec>>,
+) -> PhonicResult<Box<dyn DynFormat<Tag = RegisteredFormat>>>;
+
+/// Scores how closely the leading bytes of a source match a format. See `ProbeMatch`.
+pub type ProbeFn = fn(&[u8]) -> f32;
+
+pub type InferSpecFn =
+    fn(StreamSpecBuilder<RegisteredCodec>) -> PhonicResult<StreamSpec<RegisteredCodec>>;
+
+pub type EncoderFn =
+    fn(RegisteredCodec, TaggedSignal) -> PhonicResult<Box<dyn DynStream<Tag = RegisteredCodec>>>;
+
+pub type DecoderFn = fn(Box<dyn DynStream<Tag = RegisteredCodec>>) -> PhonicResult<TaggedSignal>;
+
+/// A format registered at runtime with `register_format`. Tags are identified by the name the
+/// format was registered with, so tags obtained in different crates compare and hash equally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegisteredFormat {
+    name: &'static str,
+}
+
+/// A codec registered at runtime with `register_codec`. The codecs in `KnownCodec` are
+/// registered by default so they can be used in registered formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegisteredCodec {
+    name: &'static str,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FormatRegistration {
+    pub name: &'static str,
+    pub file_extensions: &'static [&'static str],
+    pub mime_types: &'static [&'static str],
+    pub probe: Option<ProbeFn>,
+    pub read_index: ReadIndexFn,
+    pub write_index: WriteIndexFn,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CodecRegistration {
+    pub name: &'static str,
+    pub infer_spec: InferSpecFn,
+    pub encoder: EncoderFn,
+    pub decoder: DecoderFn,
+}
+
+#[derive(Default)]
+struct Registry {
+    formats: HashMap<&'static str, FormatRegistration>,
+    codecs: HashMap<&'static str, CodecRegistration>,
+    file_ext: HashMap<&'static str, &'static str>,
+    mime_types: HashMap<&'static str, &'static str>,
+}
+
+static REGISTRY: LazyLock<RwLock<Registry>> = LazyLock::new(|| {
+    #[cfg_attr(not(any(feature = "flac", feature = "pcm")), allow(unused_mut))]
+    let mut registry = Registry::default();
+
+    #[cfg(feature = "flac")]
+    registry.codecs.insert(
+        KnownCodec::Flac.registered_name(),
+        CodecRegistration {
+            name: KnownCodec::Flac.registered_name(),
+            infer_spec: crate::codecs::flac::FlacCodecTag::infer_tagged_spec,
+            encoder: crate::codecs::flac::FlacCodecTag::from_dyn_signal,
+            decoder: crate::codecs::flac::FlacCodecTag::from_dyn_stream,
+        },
+    );
+
+    #[cfg(feature = "pcm")]
+    for codec in [KnownCodec::PcmLE, KnownCodec::PcmBE] {
+        registry.codecs.insert(
+            codec.registered_name(),
+            CodecRegistration {
+                name: codec.registered_name(),
+                infer_spec: crate::codecs::pcm::PcmCodecTag::infer_tagged_spec,
+                encoder: crate::codecs::pcm::PcmCodecTag::from_dyn_signal,
+                decoder: crate::codecs::pcm::PcmCodecTag::from_dyn_stream,
+            },
+        );
+    }
+
+    RwLock::new(registry)
+});
+
+fn registry() -> RwLockReadGuard<'static, Registry> {
+    REGISTRY.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn registry_mut() -> RwLockWriteGuard<'static, Registry> {
+    REGISTRY.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Registers a format so it can be constructed through `RegisteredFormat` and looked up with
+/// `FormatIdentifier::registered_format`. Fails if the name, or any of the file extensions or
+/// mime types, has already been registered.
+pub fn register_format(registration: FormatRegistration) -> PhonicResult<RegisteredFormat> {
+    let mut registry = registry_mut();
+    let FormatRegistration {
+        name,
+        file_extensions,
+        mime_types,
+        ..
+    } = registration;
+
+    let is_taken = registry.formats.contains_key(name)
+        || file_extensions
+            .iter()
+            .any(|ext| registry.file_ext.contains_key(ext))
+        || mime_types
+            .iter()
+            .any(|mime| registry.mime_types.contains_key(mime));
+
+    if is_taken {
+        return Err(PhonicError::invalid_input());
+    }
+
+    registry.formats.insert(name, registration);
+    registry
+        .file_ext
+        .extend(file_extensions.iter().map(|ext| (*ext, name)));
+    registry
+        .mime_types
+        .extend(mime_types.iter().map(|mime| (*mime, name)));
+
+    Ok(RegisteredFormat { name })
+}
+
+/// Registers a codec so it can be constructed through `RegisteredCodec`. Fails if the name has
+/// already been registered.
+pub fn register_codec(registration: CodecRegistration) -> PhonicResult<RegisteredCodec> {
+    let mut registry = registry_mut();
+    let name = registration.name;
+    if registry.codecs.contains_key(name) {
+        return Err(PhonicError::invalid_input());
+    }
+
+    registry.codecs.insert(name, registration);
+    Ok(RegisteredCodec { name })
+}
+
+impl RegisteredFormat {
+    /// Returns the tag of the format registered with the given name
+    pub fn get(name: &str) -> Option<Self> {
+        registry().formats.get(name).map(|registration| Self {
+            name: registration.name,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn registration(&self) -> FormatRegistration {
+        registry().formats[self.name]
+    }
+}
+
+impl RegisteredCodec {
+    /// Returns the tag of the codec registered with the given name
+    pub fn get(name: &str) -> Option<Self> {
+        registry().codecs.get(name).map(|registration| Self {
+            name: registration.name,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn registration(&self) -> CodecRegistration {
+        registry().codecs[self.name]
+    }
+}
+
+impl KnownCodec {
+    fn registered_name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "flac")]
+            Self::Flac => "flac",
+
+            #[cfg(feature = "pcm")]
+            Self::PcmLE => "pcm_le",
+
+            #[cfg(feature = "pcm")]
+            Self::PcmBE => "pcm_be",
+        }
+    }
+}
+
+impl FormatIdentifier<'_> {
+    /// Looks up a format that was registered with `register_format`. Probes are resolved to the
+    /// registered format with the highest score.
+    pub fn registered_format(self) -> Option<RegisteredFormat> {
+        let registry = registry();
+        let name = match self {
+            Self::FileExtension(ext) => registry.file_ext.get(ext).copied(),
+            Self::MimeType(mime) => registry.mime_types.get(mime).copied(),
+            Self::Probe(bytes) => registry
+                .formats
+                .values()
+                .filter_map(|format| Some((format.name, format.probe?(bytes))))
+                .filter(|(_, confidence)| *confidence > 0.0)
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(name, _)| name),
+        };
+
+        name.map(|name| RegisteredFormat { name })
+    }
+}
+
+impl FormatTag for RegisteredFormat {
+    type Codec = RegisteredCodec;
+}
+
+impl CodecTag for RegisteredCodec {
+    fn infer_spec(spec: StreamSpecBuilder<Self>) -> PhonicResult<StreamSpec<Self>> {
+        let codec = spec.codec.ok_or(PhonicError::missing_data())?;
+        (codec.registration().infer_spec)(spec)
+    }
+}
+
+impl DynFormatConstructor for RegisteredFormat {
+    fn read_index<T>(&self, inner: T) -> PhonicResult<Box<dyn DynFormat<Tag = Self>>>
+    where
+        T: StdIoSource + 'static,
+    {
+        (self.registration().read_index)(Box::new(inner))
+    }
+
+    fn write_index<T, I>(&self, inner: T, index: I) -> PhonicResult<Box<dyn DynFormat<Tag = Self>>>
+    where
+        T: StdIoSource + 'static,
+        I: IntoIterator<Item = StreamSpec<Self::Codec>>,
+    {
+        (self.registration().write_index)(Box::new(inner), index.into_iter().collect())
+    }
+}
+
+impl DynCodecConstructor for RegisteredCodec {
+    fn encoder(&self, signal: TaggedSignal) -> PhonicResult<Box<dyn DynStream<Tag = Self>>> {
+        (self.registration().encoder)(*self, signal)
+    }
+
+    fn decoder(stream: Box<dyn DynStream<Tag = Self>>) -> PhonicResult<TaggedSignal> {
+        let codec = stream.stream_spec().codec;
+        (codec.registration().decoder)(stream)
+    }
+}
+
+impl From<KnownCodec> for RegisteredCodec {
+    fn from(codec: KnownCodec) -> Self {
+        Self {
+            name: codec.registered_name(),
+        }
+    }
+}
+
+impl From<RegisteredCodec> for Option<KnownCodec> {
+    fn from(codec: RegisteredCodec) -> Self {
+        match codec.name {
+            #[cfg(feature = "flac")]
+            "flac" => Some(KnownCodec::Flac),
+
+            #[cfg(feature = "pcm")]
+            "pcm_le" => Some(KnownCodec::PcmLE),
+
+            #[cfg(feature = "pcm")]
+            "pcm_be" => Some(KnownCodec::PcmBE),
+
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<RegisteredCodec> for KnownCodec {
+    type Error = PhonicError;
+
+    fn try_from(codec: RegisteredCodec) -> Result<Self, Self::Error> {
+        Option::<Self>::from(codec).ok_or(PhonicError::unsupported())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{register_format, FormatRegistration, RegisteredFormat};
+    use crate::dynamic::FormatIdentifier;
+    use phonic_signal::PhonicError;
+
+    #[cfg(feature = "pcm")]
+    use super::RegisteredCodec;
+    #[cfg(feature = "pcm")]
+    use crate::{dynamic::KnownCodec, StreamSpec};
+    #[cfg(feature = "pcm")]
+    use phonic_signal::SignalSpec;
+
+    #[test]
+    fn registered_formats_are_identified() {
+        let registration = FormatRegistration {
+            name: "test.registry",
+            file_extensions: &["tstreg"],
+            mime_types: &["audio/x-test-registry"],
+            probe: Some(|bytes| if bytes.starts_with(b"TREG") { 1.0 } else { 0.0 }),
+            read_index: |_| Err(PhonicError::unsupported()),
+            write_index: |_, _| Err(PhonicError::unsupported()),
+        };
+
+        let format = register_format(registration).unwrap();
+        assert!(register_format(registration).is_err());
+        assert_eq!(RegisteredFormat::get("test.registry"), Some(format));
+
+        let ids = [
+            FormatIdentifier::FileExtension("tstreg"),
+            FormatIdentifier::MimeType("audio/x-test-registry"),
+            FormatIdentifier::Probe(b"TREG\0\0\0\0"),
+        ];
+
+        for id in ids {
+            assert_eq!(id.registered_format(), Some(format));
+            assert_eq!(id.known_format(), None);
+        }
+    }
+
+    #[cfg(feature = "pcm")]
+    #[test]
+    fn known_codecs_are_registered() {
+        let codec = RegisteredCodec::get("pcm_le").unwrap();
+        assert_eq!(codec, KnownCodec::PcmLE.into());
+
+        let spec = StreamSpec::<RegisteredCodec>::builder()
+            .with_codec(codec)
+            .with_sample_type::<i16>()
+            .with_decoded_spec(SignalSpec::stereo(48000))
+            .inferred()
+            .unwrap();
+
+        assert_eq!(spec.codec, codec);
+        assert_eq!(spec.byte_rate, 2 * 2 * 48000);
+    }
+}
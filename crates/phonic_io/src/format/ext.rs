@@ -1,9 +1,12 @@
 use crate::{
-    block_on_format, BlockingFormat, FiniteFormat, Format, FormatReader, FormatSeeker, FormatTag,
-    FormatWriter, IndexedFormat, StreamSpec,
+    block_on_format, AsyncFormatReader, BlockingFormat, FiniteFormat, Format, FormatReader,
+    FormatSeeker, FormatTag, FormatWriter, IndexedFormat, StreamSpec,
 };
 use phonic_signal::{utils::slice_as_init_mut, PhonicResult};
-use std::mem::MaybeUninit;
+use std::{
+    future::{poll_fn, Future},
+    mem::MaybeUninit,
+};
 
 pub trait FormatExt: Format {
     fn current_stream_spec(&self) -> &StreamSpec<<Self::Tag as FormatTag>::Codec> {
@@ -101,3 +104,33 @@ pub trait FormatExt: Format {
 }
 
 impl<T: Format> FormatExt for T {}
+
+pub trait AsyncFormatExt: Format {
+    fn read_async<'a>(
+        &'a mut self,
+        buf: &'a mut [MaybeUninit<u8>],
+    ) -> impl Future<Output = PhonicResult<(usize, usize)>> + 'a
+    where
+        Self: AsyncFormatReader,
+    {
+        poll_fn(move |cx| self.poll_read(cx, buf))
+    }
+
+    fn read_init_async<'a>(
+        &'a mut self,
+        buf: &'a mut [MaybeUninit<u8>],
+    ) -> impl Future<Output = PhonicResult<(usize, &'a mut [u8])>> + 'a
+    where
+        Self: AsyncFormatReader,
+    {
+        async move {
+            let (stream_i, n_bytes) = self.read_async(buf).await?;
+            let uninit_slice = &mut buf[..n_bytes];
+            let init_slice = unsafe { slice_as_init_mut(uninit_slice) };
+
+            Ok((stream_i, init_slice))
+        }
+    }
+}
+
+impl<T: Format> AsyncFormatExt for T {}
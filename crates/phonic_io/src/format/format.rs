@@ -24,6 +24,12 @@ delegate_group! {
         fn block(&self);
     }
 
+    pub trait WakingFormat: crate::Format {
+        /// Registers a waker to be woken once an operation that returned
+        /// `Err(PhonicError::NotReady)` may succeed. See `phonic_signal::WakingSignal`.
+        fn register_waker(&self, waker: &std::task::Waker);
+    }
+
     pub trait IndexedFormat: crate::Format {
         fn pos(&self) -> u64;
         fn stream_pos(&self, stream: usize) -> u64;
@@ -42,6 +48,15 @@ delegate_group! {
         ) -> phonic_signal::PhonicResult<(usize, usize)>;
     }
 
+    #[subgroup(Mut, Async)]
+    pub trait AsyncFormatReader: crate::Format {
+        fn poll_read(
+            &mut self,
+            cx: &mut std::task::Context<'_>,
+            buf: &mut [std::mem::MaybeUninit<u8>]
+        ) -> std::task::Poll<phonic_signal::PhonicResult<(usize, usize)>>;
+    }
+
     #[subgroup(Mut)]
     pub trait FormatWriter: crate::Format {
         fn write(&mut self, stream: usize, buf: &[u8]) -> phonic_signal::PhonicResult<usize>;
@@ -0,0 +1,349 @@
+// https://www.mmsp.ece.mcgill.ca/Documents/AudioFormats/AIFF/AIFF.html
+// https://www.mmsp.ece.mcgill.ca/Documents/AudioFormats/AIFF/Docs/AIFF-1.3.pdf
+// https://www.mmsp.ece.mcgill.ca/Documents/AudioFormats/AIFF/Docs/AIFF-C.9.26.91.pdf
+
+use crate::{
+    formats::aiff::{AiffSupportedCodec, ChunkEndianess, RiffChunk},
+    CodecTag, StreamSpec, StreamSpecBuilder, TypeLayout,
+};
+use phonic_signal::{PhonicError, PhonicResult, SignalSpec, I24};
+use std::io::{self, ErrorKind, Read, Seek, Write};
+
+pub(super) struct CommChunk {
+    n_channels: u16,
+    n_sample_frames: u32,
+    sample_size: u16,
+    sample_rate: usize,
+    compression: Option<Compression>,
+}
+
+struct Compression {
+    compression_type: [u8; 4],
+    compression_name: &'static str,
+}
+
+pub(super) struct FverChunk;
+
+pub(super) struct SsndHeader {
+    pub offset: u32,
+    pub block_size: u32,
+}
+
+impl CommChunk {
+    pub const CHUNK_ID: [u8; 4] = *b"COMM";
+
+    /// The offset of the frame count from the start of the chunk data
+    pub const N_SAMPLE_FRAMES_OFFSET: u32 = 2;
+
+    pub fn is_aifc(&self) -> bool {
+        self.compression.is_some()
+    }
+
+    pub fn apply_to_spec<C>(self, spec: &mut StreamSpecBuilder<C>) -> PhonicResult<()>
+    where
+        C: CodecTag,
+        AiffSupportedCodec: TryInto<C>,
+        PhonicError: From<<AiffSupportedCodec as TryInto<C>>::Error>,
+    {
+        let Self {
+            n_channels,
+            sample_size,
+            sample_rate,
+            compression,
+            ..
+        } = self;
+
+        // compression types are matched case insensitively since both cases are common
+        let compression_type = compression
+            .map_or(*b"NONE", |c| c.compression_type)
+            .map(|b| b.to_ascii_lowercase());
+
+        let (codec, sample) = match (&compression_type, sample_size) {
+            (b"none" | b"twos", 1..=8) => {
+                Some((AiffSupportedCodec::PcmBE, TypeLayout::of::<i8>())).unzip()
+            }
+            (b"none" | b"twos", 9..=16) => {
+                Some((AiffSupportedCodec::PcmBE, TypeLayout::of::<i16>())).unzip()
+            }
+            (b"none" | b"twos", 17..=24) => {
+                Some((AiffSupportedCodec::PcmBE, TypeLayout::of::<I24>())).unzip()
+            }
+            (b"none" | b"twos", 25..=32) => {
+                Some((AiffSupportedCodec::PcmBE, TypeLayout::of::<i32>())).unzip()
+            }
+            (b"sowt", 1..=8) => Some((AiffSupportedCodec::PcmLE, TypeLayout::of::<i8>())).unzip(),
+            (b"sowt", 9..=16) => Some((AiffSupportedCodec::PcmLE, TypeLayout::of::<i16>())).unzip(),
+            (b"sowt", 17..=24) => {
+                Some((AiffSupportedCodec::PcmLE, TypeLayout::of::<I24>())).unzip()
+            }
+            (b"sowt", 25..=32) => {
+                Some((AiffSupportedCodec::PcmLE, TypeLayout::of::<i32>())).unzip()
+            }
+            (b"fl32", _) => Some((AiffSupportedCodec::PcmBE, TypeLayout::of::<f32>())).unzip(),
+            (b"fl64", _) => Some((AiffSupportedCodec::PcmBE, TypeLayout::of::<f64>())).unzip(),
+            _ => (None, None),
+        };
+
+        spec.codec = codec.map(TryInto::try_into).transpose()?;
+        spec.sample = sample;
+
+        spec.decoded = SignalSpec::builder()
+            .with_n_channels(n_channels as usize)
+            .with_sample_rate(sample_rate);
+
+        if let Some(sample) = sample {
+            let block_align = sample.size() * n_channels as usize;
+            spec.byte_rate = Some(block_align * sample_rate);
+            spec.block_align = Some(block_align);
+        }
+
+        Ok(())
+    }
+
+    fn read_inner(reader: &mut impl Read, is_aifc: bool) -> io::Result<Self> {
+        let n_channels = read_u16(reader)?;
+        let n_sample_frames = read_u32(reader)?;
+        let sample_size = read_u16(reader)?;
+
+        let mut extended = [0u8; 10];
+        reader.read_exact(&mut extended)?;
+        let sample_rate = extended_to_usize(extended).ok_or(io::Error::new(
+            ErrorKind::InvalidData,
+            "invalid sample rate",
+        ))?;
+
+        let compression = if is_aifc {
+            let mut compression_type = [0u8; 4];
+            reader.read_exact(&mut compression_type)?;
+
+            Some(Compression {
+                compression_type,
+                compression_name: "",
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            n_channels,
+            n_sample_frames,
+            sample_size,
+            sample_rate,
+            compression,
+        })
+    }
+
+    pub fn read_riff_chunk(chunk: &mut RiffChunk<impl Read>, is_aifc: bool) -> io::Result<Self> {
+        debug_assert_eq!(chunk.id(), Self::CHUNK_ID);
+        debug_assert_eq!(chunk.pos(), 0);
+
+        // the compression name is skipped along with the rest of the chunk
+        Self::read_inner(chunk, is_aifc)
+    }
+
+    pub fn try_from_spec<C>(spec: StreamSpec<C>) -> PhonicResult<Self>
+    where
+        C: CodecTag + TryInto<AiffSupportedCodec>,
+        PhonicError: From<<C as TryInto<AiffSupportedCodec>>::Error>,
+    {
+        let StreamSpec {
+            codec,
+            block_align,
+            sample,
+            decoded:
+                SignalSpec {
+                    sample_rate,
+                    n_channels,
+                    ..
+                },
+            ..
+        } = spec;
+
+        if block_align != sample.size() * n_channels {
+            return Err(PhonicError::unsupported());
+        }
+
+        let native_codec = codec.try_into()?;
+        let compression = match native_codec {
+            AiffSupportedCodec::PcmBE if sample.is::<i8>() => None,
+            AiffSupportedCodec::PcmBE if sample.is::<i16>() => None,
+            AiffSupportedCodec::PcmBE if sample.is::<I24>() => None,
+            AiffSupportedCodec::PcmBE if sample.is::<i32>() => None,
+            AiffSupportedCodec::PcmBE if sample.is::<f32>() => {
+                Some((b"fl32", "32-bit floating point"))
+            }
+            AiffSupportedCodec::PcmBE if sample.is::<f64>() => {
+                Some((b"fl64", "64-bit floating point"))
+            }
+            AiffSupportedCodec::PcmLE if sample.is::<i8>() => Some((b"sowt", "little endian")),
+            AiffSupportedCodec::PcmLE if sample.is::<i16>() => Some((b"sowt", "little endian")),
+            AiffSupportedCodec::PcmLE if sample.is::<I24>() => Some((b"sowt", "little endian")),
+            AiffSupportedCodec::PcmLE if sample.is::<i32>() => Some((b"sowt", "little endian")),
+            _ => return Err(PhonicError::unsupported()),
+        };
+
+        Ok(Self {
+            n_channels: n_channels as u16,
+            n_sample_frames: 0,
+            sample_size: sample.size() as u16 * 8,
+            sample_rate,
+            compression: compression.map(|(compression_type, compression_name)| Compression {
+                compression_type: *compression_type,
+                compression_name,
+            }),
+        })
+    }
+
+    fn write_inner(self, writer: &mut impl Write) -> io::Result<()> {
+        let Self {
+            n_channels,
+            n_sample_frames,
+            sample_size,
+            sample_rate,
+            compression,
+        } = self;
+
+        writer.write_all(&n_channels.to_be_bytes())?;
+        writer.write_all(&n_sample_frames.to_be_bytes())?;
+        writer.write_all(&sample_size.to_be_bytes())?;
+        writer.write_all(&usize_to_extended(sample_rate))?;
+
+        if let Some(compression) = compression {
+            writer.write_all(&compression.compression_type)?;
+
+            // pascal style string padded to an even length
+            let name = compression.compression_name.as_bytes();
+            writer.write_all(&[name.len() as u8])?;
+            writer.write_all(name)?;
+
+            if name.len() % 2 == 0 {
+                writer.write_all(&[0])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_riff_chunk<W: Write + Seek>(self, writer: &mut W) -> io::Result<()> {
+        let mut chunk =
+            RiffChunk::write_new_with_endianess(writer, Self::CHUNK_ID, ChunkEndianess::Big)?;
+
+        self.write_inner(&mut chunk)?;
+        chunk.update_header()?;
+
+        debug_assert_eq!(chunk.len() % 2, 0);
+        debug_assert_eq!(chunk.pos(), chunk.len());
+
+        Ok(())
+    }
+}
+
+impl FverChunk {
+    pub const CHUNK_ID: [u8; 4] = *b"FVER";
+
+    /// The timestamp identifying version 1 of the AIFF-C specification
+    const AIFC_VERSION_1: u32 = 0xA2805140;
+
+    pub fn write_riff_chunk<W: Write + Seek>(self, writer: &mut W) -> io::Result<()> {
+        let mut chunk =
+            RiffChunk::write_new_with_endianess(writer, Self::CHUNK_ID, ChunkEndianess::Big)?;
+
+        chunk.write_all(&Self::AIFC_VERSION_1.to_be_bytes())?;
+        chunk.update_header()?;
+
+        debug_assert_eq!(chunk.len(), 4);
+        Ok(())
+    }
+}
+
+impl SsndHeader {
+    pub const CHUNK_ID: [u8; 4] = *b"SSND";
+    pub const LEN: u32 = 8;
+
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let offset = read_u32(reader)?;
+        let block_size = read_u32(reader)?;
+
+        Ok(Self { offset, block_size })
+    }
+
+    pub fn write(self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&self.offset.to_be_bytes())?;
+        writer.write_all(&self.block_size.to_be_bytes())
+    }
+}
+
+/// Converts an 80 bit IEEE 754 extended precision float to the nearest integer
+fn extended_to_usize(bytes: [u8; 10]) -> Option<usize> {
+    let sign_exponent = u16::from_be_bytes([bytes[0], bytes[1]]);
+    let mantissa = u64::from_be_bytes(bytes[2..].try_into().unwrap());
+
+    if sign_exponent & 0x8000 != 0 {
+        return None;
+    }
+
+    let shift = (sign_exponent & 0x7FFF) as i32 - 16383 - 63;
+    match shift {
+        _ if mantissa == 0 => Some(0),
+        ..=-65 => Some(0),
+        -64 => Some((mantissa >> 63) as usize),
+        -63..=-1 => {
+            let rounded = (mantissa >> (-shift - 1)).div_ceil(2);
+            Some(rounded as usize)
+        }
+        0..64 => (mantissa as usize).checked_mul(1 << shift),
+        _ => None,
+    }
+}
+
+/// Converts an integer to an 80 bit IEEE 754 extended precision float
+fn usize_to_extended(value: usize) -> [u8; 10] {
+    let mut bytes = [0u8; 10];
+    if value == 0 {
+        return bytes;
+    }
+
+    let value = value as u64;
+    let shift = value.leading_zeros();
+    let exponent = (16383 + 63 - shift) as u16;
+
+    bytes[..2].copy_from_slice(&exponent.to_be_bytes());
+    bytes[2..].copy_from_slice(&(value << shift).to_be_bytes());
+    bytes
+}
+
+#[inline]
+fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+    let mut bytes = [0u8; 2];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u16::from_be_bytes(bytes))
+}
+
+#[inline]
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_be_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extended_to_usize, usize_to_extended};
+
+    #[test]
+    fn extended_sample_rates_round_trip() {
+        let rate_44100 = [0x40, 0x0E, 0xAC, 0x44, 0, 0, 0, 0, 0, 0];
+        assert_eq!(usize_to_extended(44100), rate_44100);
+        assert_eq!(extended_to_usize(rate_44100), Some(44100));
+
+        for rate in [0, 1, 8000, 22050, 48000, 96000, 192000, 705600] {
+            assert_eq!(extended_to_usize(usize_to_extended(rate)), Some(rate));
+        }
+
+        // 44100.5 rounds to the nearest integer
+        let fractional = [0x40, 0x0E, 0xAC, 0x44, 0x80, 0, 0, 0, 0, 0];
+        assert_eq!(extended_to_usize(fractional), Some(44101));
+    }
+}
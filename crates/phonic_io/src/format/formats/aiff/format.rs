@@ -0,0 +1,579 @@
+use crate::{
+    formats::aiff::{
+        update_nested_chunk_header, AiffFormatTag, AiffSupportedCodec, ChunkEndianess, CommChunk,
+        FverChunk, RiffChunk, SsndHeader,
+    },
+    FiniteFormat, FiniteStream, Format, FormatFromReader, FormatFromWriter, FormatReader,
+    FormatSeeker, FormatTag, FormatWriter, IndexedFormat, IndexedStream, Stream, StreamReader,
+    StreamSeeker, StreamSpec, StreamSpecBuilder, StreamWriter, WakingFormat, WakingStream,
+};
+use phonic_signal::{utils::slice_as_init_mut, PhonicError, PhonicResult};
+use std::{
+    io::{self, Read, Seek, SeekFrom, Write},
+    mem::MaybeUninit,
+    task::Waker,
+};
+
+pub struct AiffFormat<T, F: FormatTag = AiffFormatTag> {
+    tag: F,
+    spec: StreamSpec<F::Codec>,
+    data: RiffChunk<RiffChunk<T>>,
+
+    /// The number of bytes between the start of the sound data chunk and the first sample
+    data_start: u32,
+
+    /// The position of the frame count within the form chunk when writing
+    n_frames_pos: Option<u32>,
+}
+
+impl<T, F: FormatTag> AiffFormat<T, F> {
+    const FORM_CHUNK_ID: [u8; 4] = *b"FORM";
+    const AIFF_ID: [u8; 4] = *b"AIFF";
+    const AIFC_ID: [u8; 4] = *b"AIFC";
+
+    pub fn into_inner(self) -> T {
+        self.data.into_inner().into_inner()
+    }
+
+    fn read_header(
+        reader: T,
+        spec: &mut StreamSpecBuilder<F::Codec>,
+    ) -> PhonicResult<(RiffChunk<RiffChunk<T>>, u32)>
+    where
+        T: Read + Seek,
+        AiffSupportedCodec: TryInto<F::Codec>,
+        PhonicError: From<<AiffSupportedCodec as TryInto<F::Codec>>::Error>,
+    {
+        let mut form_chunk = RiffChunk::read_new_with_endianess(reader, ChunkEndianess::Big)?;
+        if form_chunk.id() != Self::FORM_CHUNK_ID {
+            return Err(PhonicError::invalid_data());
+        }
+
+        let mut form_id = [0u8; 4];
+        form_chunk.read_exact(&mut form_id)?;
+        let is_aifc = match form_id {
+            Self::AIFF_ID => false,
+            Self::AIFC_ID => true,
+            _ => return Err(PhonicError::invalid_data()),
+        };
+
+        // the sound data chunk may come before the common chunk, in which case it is skipped and
+        // returned to once the common chunk has been read
+        let mut is_comm_read = false;
+        let mut ssnd_pos = None;
+
+        loop {
+            let chunk_pos = form_chunk.pos();
+            let mut chunk = RiffChunk::read_new_with_endianess(form_chunk, ChunkEndianess::Big)?;
+            match chunk.id() {
+                CommChunk::CHUNK_ID => {
+                    CommChunk::read_riff_chunk(&mut chunk, is_aifc)
+                        .map_err(Into::into)
+                        .and_then(|comm| comm.apply_to_spec(spec))?;
+
+                    is_comm_read = true;
+                    if let Some(pos) = ssnd_pos {
+                        form_chunk = chunk.into_inner();
+                        form_chunk.seek(SeekFrom::Start(pos))?;
+                        continue;
+                    }
+                }
+
+                SsndHeader::CHUNK_ID if is_comm_read => {
+                    let SsndHeader { offset, .. } = SsndHeader::read(&mut chunk)?;
+                    io::copy(&mut (&mut chunk).take(offset as u64), &mut io::sink())?;
+
+                    break Ok((chunk, SsndHeader::LEN + offset));
+                }
+
+                SsndHeader::CHUNK_ID => {
+                    ssnd_pos = Some(chunk_pos);
+                    if chunk.is_sized() {
+                        chunk.seek(SeekFrom::End(0))?;
+                    }
+                }
+
+                _ => {}
+            };
+
+            chunk.skip_remaining()?;
+            let pad = chunk.len() % 2;
+            form_chunk = chunk.into_inner();
+
+            // chunks are padded to an even length
+            if pad != 0 {
+                form_chunk.read_exact(&mut [0u8])?;
+            }
+        }
+    }
+
+    fn write_header(
+        writer: T,
+        spec: StreamSpec<F::Codec>,
+    ) -> PhonicResult<(RiffChunk<RiffChunk<T>>, u32)>
+    where
+        T: Write + Seek,
+        F::Codec: TryInto<AiffSupportedCodec>,
+        PhonicError: From<<F::Codec as TryInto<AiffSupportedCodec>>::Error>,
+    {
+        let comm = CommChunk::try_from_spec(spec)?;
+        let mut form_chunk =
+            RiffChunk::write_new_with_endianess(writer, Self::FORM_CHUNK_ID, ChunkEndianess::Big)?;
+
+        if comm.is_aifc() {
+            form_chunk.write_all(&Self::AIFC_ID)?;
+            FverChunk.write_riff_chunk(&mut form_chunk)?;
+        } else {
+            form_chunk.write_all(&Self::AIFF_ID)?;
+        }
+
+        let n_frames_pos = form_chunk.pos() as u32 + 8 + CommChunk::N_SAMPLE_FRAMES_OFFSET;
+        comm.write_riff_chunk(&mut form_chunk)?;
+
+        let mut data = RiffChunk::write_new_with_endianess(
+            form_chunk,
+            SsndHeader::CHUNK_ID,
+            ChunkEndianess::Big,
+        )?;
+
+        let header = SsndHeader {
+            offset: 0,
+            block_size: 0,
+        };
+
+        header.write(&mut data)?;
+        Ok((data, n_frames_pos))
+    }
+
+    fn update_header(&mut self, n_frames_pos: u32) -> io::Result<()>
+    where
+        T: Write + Seek,
+    {
+        let pos = self.data.pos();
+        let data_len = self.data.len() - self.data_start as u64;
+        let n_frames = (data_len / self.spec.block_align as u64) as u32;
+
+        // chunks are padded to an even length
+        if !self.data.len().is_multiple_of(2) {
+            self.data.seek(SeekFrom::End(0))?;
+
+            let form_chunk = self.data.inner_mut();
+            form_chunk.write_all(&[0])?;
+            form_chunk.seek_relative(-1)?;
+        }
+
+        let form_chunk = self.data.inner_mut();
+        let form_pos = form_chunk.pos();
+        form_chunk.seek(SeekFrom::Start(n_frames_pos as u64))?;
+        form_chunk.write_all(&n_frames.to_be_bytes())?;
+        form_chunk.seek(SeekFrom::Start(form_pos))?;
+
+        update_nested_chunk_header(&mut self.data)?;
+        self.data.seek(SeekFrom::Start(pos))?;
+
+        Ok(())
+    }
+}
+
+impl<T, F> FormatFromReader<T, F> for AiffFormat<T, F>
+where
+    T: Read + Seek,
+    F: FormatTag,
+    AiffFormatTag: TryInto<F>,
+    AiffSupportedCodec: TryInto<F::Codec>,
+    PhonicError: From<<AiffFormatTag as TryInto<F>>::Error>,
+    PhonicError: From<<AiffSupportedCodec as TryInto<F::Codec>>::Error>,
+{
+    fn read_index(reader: T) -> PhonicResult<Self> {
+        let tag = AiffFormatTag.try_into()?;
+
+        let mut spec_builder = StreamSpec::builder();
+        let (data, data_start) = Self::read_header(reader, &mut spec_builder)?;
+        let spec = spec_builder.build()?;
+
+        Ok(Self {
+            tag,
+            spec,
+            data,
+            data_start,
+            n_frames_pos: None,
+        })
+    }
+}
+
+impl<T, F> FormatFromWriter<T, F> for AiffFormat<T, F>
+where
+    T: Write + Seek,
+    F: FormatTag,
+    AiffFormatTag: TryInto<F>,
+    F::Codec: TryInto<AiffSupportedCodec>,
+    PhonicError: From<<AiffFormatTag as TryInto<F>>::Error>,
+    PhonicError: From<<F::Codec as TryInto<AiffSupportedCodec>>::Error>,
+{
+    fn write_index<I>(writer: T, index: I) -> PhonicResult<Self>
+    where
+        I: IntoIterator<Item = StreamSpec<F::Codec>>,
+    {
+        let tag = AiffFormatTag.try_into()?;
+
+        let mut index_iter = index.into_iter();
+        let spec = index_iter.next().ok_or(PhonicError::missing_data())?;
+        if index_iter.next().is_some() {
+            return Err(PhonicError::unsupported());
+        }
+
+        let (data, n_frames_pos) = Self::write_header(writer, spec)?;
+
+        Ok(Self {
+            tag,
+            spec,
+            data,
+            data_start: SsndHeader::LEN,
+            n_frames_pos: Some(n_frames_pos),
+        })
+    }
+}
+
+impl<T, F: FormatTag> Format for AiffFormat<T, F> {
+    type Tag = F;
+
+    fn format(&self) -> Self::Tag {
+        self.tag
+    }
+
+    fn streams(&self) -> &[StreamSpec<<Self::Tag as FormatTag>::Codec>] {
+        std::slice::from_ref(&self.spec)
+    }
+
+    fn current_stream(&self) -> usize {
+        0
+    }
+
+    fn primary_stream(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+/// The inner reader or writer has no way to signal when an operation that returned
+/// `Err(PhonicError::NotReady)` may succeed, so the waker is woken immediately
+impl<T, F: FormatTag> WakingFormat for AiffFormat<T, F> {
+    fn register_waker(&self, waker: &Waker) {
+        waker.wake_by_ref()
+    }
+}
+
+impl<T, F> IndexedFormat for AiffFormat<T, F>
+where
+    F: FormatTag,
+    Self: Format<Tag = F> + IndexedStream<Tag = F::Codec>,
+{
+    fn pos(&self) -> u64 {
+        IndexedStream::pos(self)
+    }
+
+    fn stream_pos(&self, stream: usize) -> u64 {
+        match stream {
+            0 => IndexedStream::pos(self),
+            _ => 0,
+        }
+    }
+}
+
+impl<T, F> FiniteFormat for AiffFormat<T, F>
+where
+    F: FormatTag,
+    Self: Format<Tag = F> + FiniteStream<Tag = F::Codec>,
+{
+    fn len(&self) -> u64 {
+        FiniteStream::len(self)
+    }
+
+    fn stream_len(&self, stream: usize) -> u64 {
+        match stream {
+            0 => FiniteStream::len(self),
+            _ => 0,
+        }
+    }
+}
+
+impl<T, F> FormatReader for AiffFormat<T, F>
+where
+    T: Read,
+    F: FormatTag,
+    Self: Format<Tag = F> + StreamReader<Tag = F::Codec>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<u8>]) -> PhonicResult<(usize, usize)> {
+        let n = StreamReader::read(self, buf)?;
+        Ok((0, n))
+    }
+}
+
+impl<T, F> FormatWriter for AiffFormat<T, F>
+where
+    T: Write + Seek,
+    F: FormatTag,
+    Self: Format<Tag = F> + StreamWriter<Tag = F::Codec>,
+{
+    fn write(&mut self, stream: usize, buf: &[u8]) -> PhonicResult<usize> {
+        match stream {
+            0 => StreamWriter::write(self, buf),
+            _ => Err(PhonicError::invalid_input()),
+        }
+    }
+
+    fn flush(&mut self) -> PhonicResult<()> {
+        StreamWriter::flush(self)
+    }
+
+    fn finalize(&mut self) -> PhonicResult<()> {
+        match self.n_frames_pos {
+            Some(n_frames_pos) => self.update_header(n_frames_pos).map_err(Into::into),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T, F> FormatSeeker for AiffFormat<T, F>
+where
+    T: Seek,
+    F: FormatTag,
+    Self: Format<Tag = F> + StreamSeeker<Tag = F::Codec>,
+{
+    fn seek(&mut self, stream: usize, offset: i64) -> PhonicResult<()> {
+        match stream {
+            0 => StreamSeeker::seek(self, offset),
+            _ => Err(PhonicError::invalid_input()),
+        }
+    }
+}
+
+impl<T, F: FormatTag> Stream for AiffFormat<T, F> {
+    type Tag = F::Codec;
+
+    fn stream_spec(&self) -> &StreamSpec<Self::Tag> {
+        &self.spec
+    }
+}
+
+/// See the [`WakingFormat`] implementation
+impl<T, F: FormatTag> WakingStream for AiffFormat<T, F> {
+    fn register_waker(&self, waker: &Waker) {
+        waker.wake_by_ref()
+    }
+}
+
+impl<T, F: FormatTag> IndexedStream for AiffFormat<T, F> {
+    fn pos(&self) -> u64 {
+        self.data.pos().saturating_sub(self.data_start as u64)
+    }
+}
+
+impl<T, F: FormatTag> FiniteStream for AiffFormat<T, F> {
+    fn len(&self) -> u64 {
+        self.data.len().saturating_sub(self.data_start as u64)
+    }
+}
+
+impl<T: Read, F: FormatTag> StreamReader for AiffFormat<T, F> {
+    fn read(&mut self, buf: &mut [MaybeUninit<u8>]) -> PhonicResult<usize> {
+        let mut len = buf.len();
+        len -= len % self.stream_spec().block_align;
+
+        let uninit_buf = &mut buf[..len];
+        let init_buf = unsafe { slice_as_init_mut(uninit_buf) };
+
+        let mut n_bytes = 0;
+        loop {
+            match self.data.read(&mut init_buf[n_bytes..])? {
+                0 if n_bytes == 0 => break,
+                0 => return Err(PhonicError::invalid_state()),
+                n_read => n_bytes += n_read,
+            }
+
+            if n_bytes % self.spec.block_align == 0 {
+                break;
+            }
+        }
+
+        Ok(n_bytes)
+    }
+}
+
+impl<T: Write + Seek, F: FormatTag> StreamWriter for AiffFormat<T, F> {
+    fn write(&mut self, buf: &[u8]) -> PhonicResult<usize> {
+        let mut len = buf.len();
+        len -= len % self.stream_spec().block_align;
+
+        let mut n_bytes = 0;
+        loop {
+            match self.data.write(&buf[n_bytes..len])? {
+                0 if n_bytes == 0 => break,
+                0 => return Err(PhonicError::invalid_state()),
+                n_written => n_bytes += n_written,
+            }
+
+            if n_bytes % self.spec.block_align == 0 {
+                break;
+            }
+        }
+
+        Ok(n_bytes)
+    }
+
+    fn flush(&mut self) -> PhonicResult<()> {
+        self.data.flush().map_err(Into::into)
+    }
+}
+
+impl<T: Seek, F: FormatTag> StreamSeeker for AiffFormat<T, F> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        if offset % self.spec.block_align as i64 != 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let pos = IndexedStream::pos(self);
+        if pos.checked_add_signed(offset).is_none() {
+            return Err(PhonicError::out_of_bounds());
+        }
+
+        self.data.seek_relative(offset).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        codecs::pcm::PcmCodec,
+        formats::aiff::{AiffFormat, AiffSupportedCodec},
+        CodecFromStream, FiniteStream, FormatFromReader, FormatFromWriter, FormatWriter, Stream,
+        StreamReader, StreamSeeker, StreamSpec, StreamWriter, TypeLayout,
+    };
+    use phonic_signal::{
+        utils::{slice_as_init_mut, Poll},
+        SignalExt, SignalSpec, I24,
+    };
+    use std::{io::Cursor, mem::MaybeUninit};
+
+    fn round_trip(codec: AiffSupportedCodec, sample: TypeLayout, n_channels: usize) {
+        let spec = StreamSpec::builder()
+            .with_codec(codec)
+            .with_sample_layout(sample)
+            .with_decoded_spec(SignalSpec::new(n_channels, 44100))
+            .inferred()
+            .unwrap();
+
+        let data = (0..spec.block_align * 101)
+            .map(|i| i as u8)
+            .collect::<Vec<_>>();
+
+        let mut format: AiffFormat<_> =
+            AiffFormat::write_index(Cursor::new(Vec::new()), [spec]).unwrap();
+
+        assert_eq!(StreamWriter::write(&mut format, &data).unwrap(), data.len());
+        format.finalize().unwrap();
+        format.finalize().unwrap();
+
+        let bytes = format.into_inner().into_inner();
+        assert_eq!(bytes.len() % 2, 0);
+
+        let form_len = u32::from_be_bytes(bytes[4..8].try_into().unwrap());
+        assert_eq!(form_len as usize, bytes.len() - 8);
+
+        let mut format: AiffFormat<_> = AiffFormat::read_index(Cursor::new(bytes)).unwrap();
+        let read_spec = format.stream_spec();
+        assert_eq!(read_spec.codec, codec);
+        assert_eq!(read_spec.sample, sample);
+        assert_eq!(read_spec.block_align, spec.block_align);
+        assert_eq!(read_spec.decoded, spec.decoded);
+        assert_eq!(FiniteStream::len(&format), data.len() as u64);
+
+        let mut buf = vec![MaybeUninit::uninit(); data.len()];
+        let n = StreamReader::read(&mut format, &mut buf).unwrap();
+        assert_eq!(unsafe { slice_as_init_mut(&mut buf[..n]) }, &data[..n]);
+
+        let offset = spec.block_align as i64 * 50 - n as i64;
+        StreamSeeker::seek(&mut format, offset).unwrap();
+
+        let n = StreamReader::read(&mut format, &mut buf).unwrap();
+        let expected = &data[spec.block_align * 50..];
+        assert_eq!(unsafe { slice_as_init_mut(&mut buf[..n]) }, expected);
+    }
+
+    #[test]
+    fn round_trip_aiff() {
+        round_trip(AiffSupportedCodec::PcmBE, TypeLayout::of::<i16>(), 2);
+        round_trip(AiffSupportedCodec::PcmBE, TypeLayout::of::<i8>(), 1);
+        round_trip(AiffSupportedCodec::PcmBE, TypeLayout::of::<i32>(), 3);
+        round_trip(AiffSupportedCodec::PcmBE, TypeLayout::of::<I24>(), 1);
+    }
+
+    #[test]
+    fn round_trip_aifc() {
+        round_trip(AiffSupportedCodec::PcmLE, TypeLayout::of::<i16>(), 2);
+        round_trip(AiffSupportedCodec::PcmLE, TypeLayout::of::<I24>(), 2);
+        round_trip(AiffSupportedCodec::PcmBE, TypeLayout::of::<f32>(), 2);
+        round_trip(AiffSupportedCodec::PcmBE, TypeLayout::of::<f64>(), 1);
+    }
+
+    #[test]
+    fn pcm_samples_are_stored_big_endian() {
+        let spec = StreamSpec::builder()
+            .with_codec(AiffSupportedCodec::PcmBE)
+            .with_sample_type::<i16>()
+            .with_decoded_spec(SignalSpec::new(2, 8000))
+            .inferred()
+            .unwrap();
+
+        let samples = [0x0102i16, -2, 0x7F00, i16::MIN];
+        let format: AiffFormat<_> =
+            AiffFormat::write_index(Cursor::new(Vec::new()), [spec]).unwrap();
+
+        let mut codec = PcmCodec::<_, i16, _>::from_stream(format).unwrap();
+        Poll(&mut codec).write_exact(&samples).unwrap();
+
+        let mut format = codec.into_inner();
+        format.finalize().unwrap();
+
+        let bytes = format.into_inner().into_inner();
+        let data = &bytes[bytes.len() - 8..];
+        assert_eq!(data, [0x01, 0x02, 0xFF, 0xFE, 0x7F, 0x00, 0x80, 0x00]);
+
+        let format: AiffFormat<_> = AiffFormat::read_index(Cursor::new(bytes)).unwrap();
+        let mut codec = PcmCodec::<_, i16, _>::from_stream(format).unwrap();
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        let decoded = Poll(&mut codec).read_exact_init(&mut buf).unwrap();
+        assert_eq!(decoded, samples);
+    }
+
+    #[test]
+    fn sound_data_may_come_before_the_common_chunk() {
+        let spec = StreamSpec::builder()
+            .with_codec(AiffSupportedCodec::PcmBE)
+            .with_sample_type::<i16>()
+            .with_decoded_spec(SignalSpec::new(2, 8000))
+            .inferred()
+            .unwrap();
+
+        let data = [1, 2, 3, 4, 5, 6, 7, 8];
+        let mut format: AiffFormat<_> =
+            AiffFormat::write_index(Cursor::new(Vec::new()), [spec]).unwrap();
+
+        StreamWriter::write(&mut format, &data).unwrap();
+        format.finalize().unwrap();
+
+        // moves the common chunk after the sound data chunk
+        let bytes = format.into_inner().into_inner();
+        let comm_len = u32::from_be_bytes(bytes[16..20].try_into().unwrap()) as usize;
+        let comm_end = 20 + comm_len + comm_len % 2;
+        let reordered = [&bytes[..12], &bytes[comm_end..], &bytes[12..comm_end]].concat();
+
+        let mut format: AiffFormat<_> = AiffFormat::read_index(Cursor::new(reordered)).unwrap();
+        assert_eq!(format.stream_spec().decoded, spec.decoded);
+        assert_eq!(FiniteStream::len(&format), data.len() as u64);
+
+        let mut buf = [MaybeUninit::uninit(); 8];
+        let n = StreamReader::read(&mut format, &mut buf).unwrap();
+        assert_eq!(unsafe { slice_as_init_mut(&mut buf[..n]) }, data);
+    }
+}
@@ -0,0 +1,2 @@
+pub const KNOWN_AIFF_FILE_EXTENSIONS: [&str; 3] = ["aiff", "aif", "aifc"];
+pub const KNOWN_AIFF_MIME_TYPES: [&str; 3] = ["audio/aiff", "audio/x-aiff", "audio/x-aifc"];
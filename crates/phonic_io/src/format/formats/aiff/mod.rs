@@ -0,0 +1,11 @@
+mod chunks;
+mod format;
+mod identifiers;
+mod tag;
+
+use super::riff::*;
+use chunks::*;
+
+pub use format::*;
+pub use identifiers::*;
+pub use tag::*;
@@ -0,0 +1,108 @@
+use crate::{codecs::pcm::PcmCodecTag, CodecTag, FormatTag, StreamSpec, StreamSpecBuilder};
+use phonic_signal::{PhonicError, PhonicResult};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct AiffFormatTag;
+
+#[non_exhaustive]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum AiffSupportedCodec {
+    #[default]
+    PcmBE,
+
+    /// Little endian samples, stored as an AIFF-C `sowt` stream
+    PcmLE,
+}
+
+impl FormatTag for AiffFormatTag {
+    type Codec = AiffSupportedCodec;
+}
+
+impl CodecTag for AiffSupportedCodec {
+    fn infer_spec(spec: StreamSpecBuilder<Self>) -> PhonicResult<StreamSpec<Self>> {
+        match spec.codec {
+            Some(Self::PcmBE | Self::PcmLE) => PcmCodecTag::infer_tagged_spec(spec),
+            None => Err(PhonicError::missing_data()),
+        }
+    }
+}
+
+impl From<PcmCodecTag> for AiffSupportedCodec {
+    fn from(codec: PcmCodecTag) -> Self {
+        match codec {
+            PcmCodecTag::BE => Self::PcmBE,
+            PcmCodecTag::LE => Self::PcmLE,
+        }
+    }
+}
+
+impl From<AiffSupportedCodec> for PcmCodecTag {
+    fn from(codec: AiffSupportedCodec) -> Self {
+        match codec {
+            AiffSupportedCodec::PcmBE => Self::BE,
+            AiffSupportedCodec::PcmLE => Self::LE,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<AiffFormatTag> for crate::dynamic::KnownFormat {
+    fn from(tag: AiffFormatTag) -> Self {
+        match tag {
+            AiffFormatTag => Self::Aiff,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<crate::dynamic::KnownFormat> for Option<AiffFormatTag> {
+    fn from(format: crate::dynamic::KnownFormat) -> Self {
+        match format {
+            crate::dynamic::KnownFormat::Aiff => Some(AiffFormatTag),
+
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl TryFrom<crate::dynamic::KnownFormat> for AiffFormatTag {
+    type Error = PhonicError;
+
+    fn try_from(format: crate::dynamic::KnownFormat) -> Result<Self, Self::Error> {
+        Option::<Self>::from(format).ok_or(PhonicError::unsupported())
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<AiffSupportedCodec> for crate::dynamic::KnownCodec {
+    fn from(codec: AiffSupportedCodec) -> Self {
+        match codec {
+            AiffSupportedCodec::PcmBE => Self::PcmBE,
+            AiffSupportedCodec::PcmLE => Self::PcmLE,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<crate::dynamic::KnownCodec> for Option<AiffSupportedCodec> {
+    fn from(codec: crate::dynamic::KnownCodec) -> Self {
+        match codec {
+            crate::dynamic::KnownCodec::PcmBE => Some(AiffSupportedCodec::PcmBE),
+            crate::dynamic::KnownCodec::PcmLE => Some(AiffSupportedCodec::PcmLE),
+
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl TryFrom<crate::dynamic::KnownCodec> for AiffSupportedCodec {
+    type Error = PhonicError;
+
+    fn try_from(codec: crate::dynamic::KnownCodec) -> Result<Self, Self::Error> {
+        Option::<Self>::from(codec).ok_or(PhonicError::unsupported())
+    }
+}
@@ -0,0 +1,886 @@
+use crate::{
+    codecs::flac::{decode_frame, FlacCodecTag, FrameHeader, FrameSplitter, Md5},
+    formats::flac::{BlockHeader, BlockType, FlacFormatTag, SeekPoint, StreamInfo, VorbisComment},
+    FiniteFormat, FiniteStream, Format, FormatFromReader, FormatFromWriter, FormatReader,
+    FormatSeeker, FormatTag, FormatWriter, IndexedFormat, IndexedStream, Stream, StreamReader,
+    StreamSeeker, StreamSpec, StreamWriter, WakingFormat, WakingStream,
+};
+use phonic_signal::{PhonicError, PhonicResult, SignalSpec};
+use std::{
+    io::{self, Read, Seek, SeekFrom, Write},
+    mem::MaybeUninit,
+    task::Waker,
+};
+
+/// The number of bytes requested from the inner reader at a time
+const READ_LEN: usize = 1 << 14;
+
+/// The number of seek points reserved when writing a stream
+const SEEK_POINTS: usize = 100;
+
+/// The minimum number of seconds between the seek points of a written stream
+const SEEK_INTERVAL: u64 = 10;
+
+const VENDOR: &str = concat!("phonic ", env!("CARGO_PKG_VERSION"));
+
+/// A native flac stream. The encoded frames are exposed as a stream measured in decoded bytes:
+/// the position of the stream is the first sample of the frame being read and seeking moves to
+/// the start of the frame containing the target sample.
+pub struct FlacFormat<T, F: FormatTag = FlacFormatTag> {
+    tag: F,
+    spec: StreamSpec<F::Codec>,
+    info: StreamInfo,
+    seek_table: Vec<SeekPoint>,
+    comment: Option<VorbisComment>,
+
+    inner: T,
+    data_start: u64,
+
+    /// The current frame followed by any bytes read or written after it
+    buf: Vec<u8>,
+    splitter: FrameSplitter,
+    frame_len: Option<usize>,
+    n_served: usize,
+    eof: bool,
+
+    /// The number of bytes between the first and current frame, and the first sample of the
+    /// current frame
+    offset: u64,
+    frame_pos: u64,
+
+    stats: Option<WriteStats>,
+}
+
+struct WriteStats {
+    n_frames: u64,
+    min_block_size: Option<u16>,
+    last_block_size: Option<u16>,
+    seek_points: Vec<SeekPoint>,
+    next_seek_sample: u64,
+
+    /// The digest of the samples written so far and the channels of the last decoded frame
+    md5: Md5,
+    channels: Vec<Vec<i64>>,
+}
+
+impl<T, F: FormatTag> FlacFormat<T, F> {
+    const MARKER: [u8; 4] = *b"fLaC";
+
+    pub fn stream_info(&self) -> &StreamInfo {
+        &self.info
+    }
+
+    pub fn seek_table(&self) -> &[SeekPoint] {
+        &self.seek_table
+    }
+
+    pub fn vorbis_comment(&self) -> Option<&VorbisComment> {
+        self.comment.as_ref()
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn new(tag: F, spec: StreamSpec<F::Codec>, info: StreamInfo, inner: T) -> Self {
+        Self {
+            tag,
+            spec,
+            info,
+            seek_table: Vec::new(),
+            comment: None,
+
+            inner,
+            data_start: 0,
+
+            buf: Vec::new(),
+            splitter: FrameSplitter::default(),
+            frame_len: None,
+            n_served: 0,
+            eof: false,
+
+            offset: 0,
+            frame_pos: 0,
+
+            stats: None,
+        }
+    }
+
+    /// Returns the length of the current frame, reading until its end is found, or `None` at
+    /// the end of the stream
+    fn load_frame(&mut self) -> PhonicResult<Option<usize>>
+    where
+        T: Read,
+    {
+        loop {
+            if self.frame_len.is_some() {
+                return Ok(self.frame_len);
+            }
+
+            if self.eof && self.buf.is_empty() {
+                return Ok(None);
+            }
+
+            self.frame_len = self.splitter.frame_len(&self.buf, self.eof)?;
+            if self.frame_len.is_none() {
+                let len = self.buf.len();
+                self.buf.resize(len + READ_LEN, 0);
+
+                let n = self.inner.read(&mut self.buf[len..]);
+                self.buf.truncate(len + *n.as_ref().unwrap_or(&0));
+                self.eof = n? == 0;
+            }
+        }
+    }
+
+    fn current_header(&self) -> PhonicResult<FrameHeader> {
+        FrameHeader::read(&self.buf).map(|(header, _)| header)
+    }
+
+    /// Moves past the current frame
+    fn next_frame(&mut self) -> PhonicResult<()> {
+        let len = self.frame_len.take().ok_or(PhonicError::invalid_state())?;
+        let header = self.current_header()?;
+
+        self.frame_pos = header.first_sample(self.info.max_block_size as usize);
+        self.frame_pos += header.block_size as u64;
+        self.offset += len as u64;
+
+        self.buf.drain(..len);
+        self.splitter.reset();
+        self.n_served = 0;
+
+        Ok(())
+    }
+
+    /// Writes the complete frame at the start of the buffer to the inner writer
+    fn commit_frame(&mut self, len: usize) -> PhonicResult<()>
+    where
+        T: Write,
+    {
+        let stats = self.stats.as_mut().ok_or(PhonicError::unsupported())?;
+        let bits_per_sample = self.info.bits_per_sample as u32;
+        let (header, _) = decode_frame(&self.buf[..len], bits_per_sample, &mut stats.channels)?;
+        self.inner.write_all(&self.buf[..len])?;
+
+        // the digest covers the interleaved samples rather than the encoded frames
+        let bits_per_sample = header.bits_per_sample.unwrap_or(bits_per_sample);
+        let samples = (0..header.block_size)
+            .flat_map(|i| stats.channels.iter().map(move |channel| channel[i]));
+        stats.md5.update_samples(samples, bits_per_sample);
+
+        let frame_size = len as u32;
+        let block_size = header.block_size as u16;
+        if stats.n_frames == 0 {
+            self.info.min_frame_size = frame_size;
+            self.info.max_frame_size = frame_size;
+        } else {
+            self.info.min_frame_size = self.info.min_frame_size.min(frame_size);
+            self.info.max_frame_size = self.info.max_frame_size.max(frame_size);
+        }
+
+        // the last block is excluded from the minimum block size
+        if let Some(last) = stats.last_block_size.replace(block_size) {
+            stats.min_block_size = Some(stats.min_block_size.unwrap_or(last).min(last));
+        }
+
+        self.info.max_block_size = self.info.max_block_size.max(block_size);
+
+        if self.frame_pos >= stats.next_seek_sample {
+            stats.seek_points.push(SeekPoint {
+                sample: self.frame_pos,
+                offset: self.offset,
+                n_samples: block_size,
+            });
+
+            stats.next_seek_sample = self.frame_pos + SEEK_INTERVAL * self.info.sample_rate as u64;
+        }
+
+        stats.n_frames += 1;
+        self.frame_pos += header.block_size as u64;
+        self.info.total_samples = self.frame_pos;
+        self.offset += len as u64;
+
+        self.buf.drain(..len);
+        self.splitter.reset();
+
+        Ok(())
+    }
+
+    /// Rewrites the stream info and seek table to describe the frames written so far
+    fn write_metadata(&mut self) -> io::Result<()>
+    where
+        T: Write + Seek,
+    {
+        let Some(stats) = &self.stats else {
+            return Ok(());
+        };
+
+        self.info.min_block_size = stats
+            .min_block_size
+            .or(stats.last_block_size)
+            .unwrap_or_default();
+
+        self.info.md5 = stats.md5.digest();
+
+        let points = &stats.seek_points;
+        for (i, point) in self.seek_table.iter_mut().enumerate() {
+            *point = match points.len() {
+                n if n <= SEEK_POINTS => points.get(i).copied(),
+                n => points.get(i * n / SEEK_POINTS).copied(),
+            }
+            .unwrap_or(SeekPoint::PLACEHOLDER);
+        }
+
+        self.inner.seek(SeekFrom::Start(
+            (Self::MARKER.len() as u32 + BlockHeader::LEN) as u64,
+        ))?;
+
+        self.info.write(&mut self.inner)?;
+        self.inner
+            .seek(SeekFrom::Current(BlockHeader::LEN as i64))?;
+        for point in &self.seek_table {
+            point.write(&mut self.inner)?;
+        }
+
+        self.inner
+            .seek(SeekFrom::Start(self.data_start + self.offset))?;
+
+        Ok(())
+    }
+}
+
+impl<T, F> FormatFromReader<T, F> for FlacFormat<T, F>
+where
+    T: Read,
+    F: FormatTag,
+    FlacFormatTag: TryInto<F>,
+    FlacCodecTag: TryInto<F::Codec>,
+    PhonicError: From<<FlacFormatTag as TryInto<F>>::Error>,
+    PhonicError: From<<FlacCodecTag as TryInto<F::Codec>>::Error>,
+{
+    fn read_index(mut reader: T) -> PhonicResult<Self> {
+        let tag = FlacFormatTag.try_into()?;
+
+        let mut marker = [0u8; 4];
+        reader.read_exact(&mut marker)?;
+        if marker != Self::MARKER {
+            return Err(PhonicError::invalid_data());
+        }
+
+        let mut data_start = marker.len() as u64;
+        let mut info = None;
+        let mut seek_table = Vec::new();
+        let mut comment = None;
+
+        loop {
+            let header = BlockHeader::read(&mut reader)?;
+            data_start += (BlockHeader::LEN + header.len) as u64;
+
+            let mut block = (&mut reader).take(header.len as u64);
+            match header.block_type {
+                BlockType::StreamInfo if info.is_none() && header.len == StreamInfo::LEN => {
+                    info = Some(StreamInfo::read(&mut block)?)
+                }
+                BlockType::StreamInfo => return Err(PhonicError::invalid_data()),
+                BlockType::SeekTable => seek_table = SeekPoint::read_table(&mut block, header.len)?,
+                BlockType::VorbisComment => comment = Some(VorbisComment::read(&mut block)?),
+                BlockType::Other(_) => {}
+            }
+
+            io::copy(&mut block, &mut io::sink())?;
+            if header.is_last {
+                break;
+            }
+        }
+
+        let info = info.ok_or(PhonicError::missing_data())?;
+        let sample = FlacCodecTag::sample_layout(info.bits_per_sample as u32)
+            .ok_or(PhonicError::unsupported())?;
+
+        let n_channels = info.n_channels as usize;
+        let sample_rate = info.sample_rate as usize;
+        let block_align = sample.size() * n_channels;
+
+        let spec = StreamSpec {
+            codec: FlacCodecTag.try_into()?,
+            byte_rate: block_align * sample_rate,
+            block_align,
+            sample,
+            valid_bits: None,
+            decoded: SignalSpec::new(n_channels, sample_rate),
+        };
+
+        let mut format = Self::new(tag, spec, info, reader);
+        format.seek_table = seek_table;
+        format.comment = comment;
+        format.data_start = data_start;
+
+        Ok(format)
+    }
+}
+
+impl<T, F> FormatFromWriter<T, F> for FlacFormat<T, F>
+where
+    T: Write + Seek,
+    F: FormatTag,
+    FlacFormatTag: TryInto<F>,
+    F::Codec: TryInto<FlacCodecTag>,
+    PhonicError: From<<FlacFormatTag as TryInto<F>>::Error>,
+    PhonicError: From<<F::Codec as TryInto<FlacCodecTag>>::Error>,
+{
+    fn write_index<I>(mut writer: T, index: I) -> PhonicResult<Self>
+    where
+        I: IntoIterator<Item = StreamSpec<F::Codec>>,
+    {
+        let tag = FlacFormatTag.try_into()?;
+
+        let mut index_iter = index.into_iter();
+        let spec = index_iter.next().ok_or(PhonicError::missing_data())?;
+        if index_iter.next().is_some() {
+            return Err(PhonicError::unsupported());
+        }
+
+        let _: FlacCodecTag = spec.codec.try_into()?;
+        let bits_per_sample =
+            FlacCodecTag::bits_per_sample(spec.sample).ok_or(PhonicError::unsupported())?;
+
+        let SignalSpec {
+            sample_rate,
+            n_channels,
+            ..
+        } = spec.decoded;
+
+        if !(1..=FlacCodecTag::MAX_SAMPLE_RATE).contains(&sample_rate)
+            || !(1..=FlacCodecTag::MAX_CHANNELS).contains(&n_channels)
+        {
+            return Err(PhonicError::unsupported());
+        }
+
+        let info = StreamInfo {
+            min_block_size: 0,
+            max_block_size: 0,
+            min_frame_size: 0,
+            max_frame_size: 0,
+            sample_rate: sample_rate as u32,
+            n_channels: n_channels as u8,
+            bits_per_sample: bits_per_sample as u8,
+            total_samples: 0,
+            md5: [0; 16],
+        };
+
+        let seek_table = vec![SeekPoint::PLACEHOLDER; SEEK_POINTS];
+        let comment = VorbisComment {
+            vendor: VENDOR.to_owned(),
+            comments: Vec::new(),
+        };
+
+        writer.write_all(&Self::MARKER)?;
+
+        let blocks = [
+            (BlockType::StreamInfo, StreamInfo::LEN),
+            (BlockType::SeekTable, SeekPoint::LEN * SEEK_POINTS as u32),
+            (BlockType::VorbisComment, comment.len()),
+        ];
+
+        let mut data_start = Self::MARKER.len() as u64;
+        for (i, (block_type, len)) in blocks.into_iter().enumerate() {
+            let header = BlockHeader {
+                is_last: i == blocks.len() - 1,
+                block_type,
+                len,
+            };
+
+            header.write(&mut writer)?;
+            match block_type {
+                BlockType::StreamInfo => info.write(&mut writer)?,
+                BlockType::SeekTable => {
+                    for point in &seek_table {
+                        point.write(&mut writer)?;
+                    }
+                }
+                _ => comment.write(&mut writer)?,
+            }
+
+            data_start += (BlockHeader::LEN + len) as u64;
+        }
+
+        let mut format = Self::new(tag, spec, info, writer);
+        format.seek_table = seek_table;
+        format.comment = Some(comment);
+        format.data_start = data_start;
+        format.stats = Some(WriteStats {
+            n_frames: 0,
+            min_block_size: None,
+            last_block_size: None,
+            seek_points: Vec::new(),
+            next_seek_sample: 0,
+            md5: Md5::new(),
+            channels: Vec::new(),
+        });
+
+        Ok(format)
+    }
+}
+
+impl<T, F: FormatTag> Format for FlacFormat<T, F> {
+    type Tag = F;
+
+    fn format(&self) -> Self::Tag {
+        self.tag
+    }
+
+    fn streams(&self) -> &[StreamSpec<<Self::Tag as FormatTag>::Codec>] {
+        std::slice::from_ref(&self.spec)
+    }
+
+    fn current_stream(&self) -> usize {
+        0
+    }
+
+    fn primary_stream(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+/// The inner reader or writer has no way to signal when an operation that returned
+/// `Err(PhonicError::NotReady)` may succeed, so the waker is woken immediately
+impl<T, F: FormatTag> WakingFormat for FlacFormat<T, F> {
+    fn register_waker(&self, waker: &Waker) {
+        waker.wake_by_ref()
+    }
+}
+
+impl<T, F> IndexedFormat for FlacFormat<T, F>
+where
+    F: FormatTag,
+    Self: Format<Tag = F> + IndexedStream<Tag = F::Codec>,
+{
+    fn pos(&self) -> u64 {
+        IndexedStream::pos(self)
+    }
+
+    fn stream_pos(&self, stream: usize) -> u64 {
+        match stream {
+            0 => IndexedStream::pos(self),
+            _ => 0,
+        }
+    }
+}
+
+impl<T, F> FiniteFormat for FlacFormat<T, F>
+where
+    F: FormatTag,
+    Self: Format<Tag = F> + FiniteStream<Tag = F::Codec>,
+{
+    fn len(&self) -> u64 {
+        FiniteStream::len(self)
+    }
+
+    fn stream_len(&self, stream: usize) -> u64 {
+        match stream {
+            0 => FiniteStream::len(self),
+            _ => 0,
+        }
+    }
+}
+
+impl<T, F> FormatReader for FlacFormat<T, F>
+where
+    T: Read,
+    F: FormatTag,
+    Self: Format<Tag = F> + StreamReader<Tag = F::Codec>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<u8>]) -> PhonicResult<(usize, usize)> {
+        let n = StreamReader::read(self, buf)?;
+        Ok((0, n))
+    }
+}
+
+impl<T, F> FormatWriter for FlacFormat<T, F>
+where
+    T: Write + Seek,
+    F: FormatTag,
+    Self: Format<Tag = F> + StreamWriter<Tag = F::Codec>,
+{
+    fn write(&mut self, stream: usize, buf: &[u8]) -> PhonicResult<usize> {
+        match stream {
+            0 => StreamWriter::write(self, buf),
+            _ => Err(PhonicError::invalid_input()),
+        }
+    }
+
+    fn flush(&mut self) -> PhonicResult<()> {
+        StreamWriter::flush(self)
+    }
+
+    fn finalize(&mut self) -> PhonicResult<()> {
+        if self.stats.is_none() {
+            return Ok(());
+        }
+
+        if let Some(len) = self.splitter.frame_len(&self.buf, true)? {
+            self.commit_frame(len)?;
+        }
+
+        self.write_metadata()?;
+        self.inner.flush().map_err(Into::into)
+    }
+}
+
+impl<T, F> FormatSeeker for FlacFormat<T, F>
+where
+    T: Read + Seek,
+    F: FormatTag,
+    Self: Format<Tag = F> + StreamSeeker<Tag = F::Codec>,
+{
+    fn seek(&mut self, stream: usize, offset: i64) -> PhonicResult<()> {
+        match stream {
+            0 => StreamSeeker::seek(self, offset),
+            _ => Err(PhonicError::invalid_input()),
+        }
+    }
+}
+
+impl<T, F: FormatTag> Stream for FlacFormat<T, F> {
+    type Tag = F::Codec;
+
+    fn stream_spec(&self) -> &StreamSpec<Self::Tag> {
+        &self.spec
+    }
+}
+
+/// See the [`WakingFormat`] implementation
+impl<T, F: FormatTag> WakingStream for FlacFormat<T, F> {
+    fn register_waker(&self, waker: &Waker) {
+        waker.wake_by_ref()
+    }
+}
+
+impl<T, F: FormatTag> IndexedStream for FlacFormat<T, F> {
+    fn pos(&self) -> u64 {
+        self.frame_pos * self.spec.block_align as u64
+    }
+}
+
+impl<T, F: FormatTag> FiniteStream for FlacFormat<T, F> {
+    fn len(&self) -> u64 {
+        self.info.total_samples * self.spec.block_align as u64
+    }
+}
+
+impl<T: Read, F: FormatTag> StreamReader for FlacFormat<T, F> {
+    fn read(&mut self, buf: &mut [MaybeUninit<u8>]) -> PhonicResult<usize> {
+        if self.stats.is_some() {
+            return Err(PhonicError::unsupported());
+        }
+
+        let Some(frame_len) = self.load_frame()? else {
+            return Ok(0);
+        };
+
+        let frame = &self.buf[self.n_served..frame_len];
+        let n = buf.len().min(frame.len());
+        for (byte, value) in buf.iter_mut().zip(&frame[..n]) {
+            byte.write(*value);
+        }
+
+        self.n_served += n;
+        if self.n_served == frame_len {
+            self.next_frame()?;
+        }
+
+        Ok(n)
+    }
+}
+
+impl<T: Write, F: FormatTag> StreamWriter for FlacFormat<T, F> {
+    fn write(&mut self, buf: &[u8]) -> PhonicResult<usize> {
+        if self.stats.is_none() {
+            return Err(PhonicError::unsupported());
+        }
+
+        self.buf.extend_from_slice(buf);
+        while let Some(len) = self.splitter.frame_len(&self.buf, false)? {
+            self.commit_frame(len)?;
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> PhonicResult<()> {
+        self.inner.flush().map_err(Into::into)
+    }
+}
+
+/// Seeks to the frame containing the target sample, starting from the closest seek point
+/// preceding it.
+impl<T: Read + Seek, F: FormatTag> StreamSeeker for FlacFormat<T, F> {
+    fn seek(&mut self, offset: i64) -> PhonicResult<()> {
+        if self.stats.is_some() {
+            return Err(PhonicError::unsupported());
+        }
+
+        let block_align = self.spec.block_align as i64;
+        if offset % block_align != 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let target = self
+            .frame_pos
+            .checked_add_signed(offset / block_align)
+            .ok_or(PhonicError::out_of_bounds())?;
+
+        if self.info.total_samples > 0 && target > self.info.total_samples {
+            return Err(PhonicError::out_of_bounds());
+        }
+
+        let (start, start_offset) = self
+            .seek_table
+            .iter()
+            .filter(|point| !point.is_placeholder() && point.sample <= target)
+            .map(|point| (point.sample, point.offset))
+            .max()
+            .unwrap_or_default();
+
+        if !(start..=target).contains(&self.frame_pos) {
+            self.inner
+                .seek(SeekFrom::Start(self.data_start + start_offset))?;
+
+            self.buf.clear();
+            self.splitter.reset();
+            self.frame_len = None;
+            self.eof = false;
+            self.offset = start_offset;
+            self.frame_pos = start;
+        }
+
+        self.n_served = 0;
+        while self.load_frame()?.is_some() {
+            let header = self.current_header()?;
+            self.frame_pos = header.first_sample(self.info.max_block_size as usize);
+
+            if self.frame_pos + header.block_size as u64 > target {
+                break;
+            }
+
+            self.next_frame()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        codecs::flac::{FlacCodec, FlacCodecTag, FlacCompression, Md5},
+        formats::flac::FlacFormat,
+        CodecFromStream, FormatFromReader, FormatFromWriter, FormatWriter, StreamSpec,
+    };
+    use phonic_signal::{
+        utils::Poll, IndexedSignal, SignalExt, SignalReader, SignalSeeker, SignalSpec,
+        SignalWriter, I24,
+    };
+    use std::{io::Cursor, mem::MaybeUninit};
+
+    const SPEC: SignalSpec = SignalSpec {
+        sample_rate: 4000,
+        n_channels: 2,
+        layout: None,
+    };
+
+    fn samples(n_frames: usize) -> Vec<i16> {
+        let mut state = 0x1234_5678u32;
+        (0..n_frames * SPEC.n_channels)
+            .map(|i| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+
+                let t = (i / SPEC.n_channels) as f32 / SPEC.sample_rate as f32;
+                let tone = (t * 440.0 * std::f32::consts::TAU).sin() * 8000.0;
+                tone as i16 + (state % 64) as i16
+            })
+            .collect()
+    }
+
+    fn encode(samples: &[i16], compression: FlacCompression) -> Vec<u8> {
+        let spec = StreamSpec::<FlacCodecTag>::builder()
+            .with_sample_type::<i16>()
+            .with_decoded_spec(SPEC)
+            .inferred()
+            .unwrap();
+
+        let format: FlacFormat<_> =
+            FlacFormat::write_index(Cursor::new(Vec::new()), [spec]).unwrap();
+        let mut codec = FlacCodec::<_, i16>::from_stream(format)
+            .unwrap()
+            .with_compression(compression)
+            .unwrap();
+
+        Poll(&mut codec).write_exact(samples).unwrap();
+        codec.flush().unwrap();
+
+        let mut format = codec.into_inner();
+        format.finalize().unwrap();
+        format.into_inner().into_inner()
+    }
+
+    fn decode(bytes: Vec<u8>) -> FlacCodec<FlacFormat<Cursor<Vec<u8>>>, i16> {
+        let format: FlacFormat<_> = FlacFormat::read_index(Cursor::new(bytes)).unwrap();
+        FlacCodec::from_stream(format).unwrap()
+    }
+
+    fn md5(samples: impl IntoIterator<Item = i64>, bits_per_sample: u32) -> [u8; 16] {
+        let mut md5 = Md5::new();
+        md5.update_samples(samples, bits_per_sample);
+        md5.digest()
+    }
+
+    #[test]
+    fn round_trip_is_lossless() {
+        let expected = samples(20000);
+
+        for level in [0, 3, 5, 8] {
+            let compression = FlacCompression::level(level).unwrap();
+            let bytes = encode(&expected, compression);
+            assert!(bytes.len() < expected.len() * 2);
+
+            let mut codec = decode(bytes);
+            let info = codec.as_inner().stream_info();
+            assert_eq!(info.total_samples, 20000);
+            assert_eq!(info.bits_per_sample, 16);
+            assert_eq!(info.md5, md5(expected.iter().map(|s| *s as i64), 16));
+
+            let mut buf = vec![MaybeUninit::uninit(); expected.len()];
+            let decoded = Poll(&mut codec).read_exact_init(&mut buf).unwrap();
+            assert_eq!(decoded, expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn seek_uses_seek_table() {
+        let n_frames = SPEC.sample_rate * 30;
+        let expected = samples(n_frames);
+        let bytes = encode(&expected, FlacCompression::default());
+
+        let mut codec = decode(bytes);
+        let seek_table = codec.as_inner().seek_table();
+        assert_eq!(seek_table.iter().filter(|p| !p.is_placeholder()).count(), 3);
+
+        let mut buf = [MaybeUninit::uninit(); 32];
+        for frame in [25_001, 3, 11_111, n_frames - 16] {
+            let offset = (frame * SPEC.n_channels) as i64 - codec.pos() as i64;
+            codec.seek(offset).unwrap();
+
+            let i = frame * SPEC.n_channels;
+            let decoded = Poll(&mut codec).read_exact_init(&mut buf).unwrap();
+            assert_eq!(decoded, &expected[i..i + 32]);
+        }
+    }
+
+    #[test]
+    fn small_writes_are_buffered_into_whole_blocks() {
+        let expected = samples(10000);
+        let compression = FlacCompression::default();
+
+        let spec = StreamSpec::<FlacCodecTag>::builder()
+            .with_sample_type::<i16>()
+            .with_decoded_spec(SPEC)
+            .inferred()
+            .unwrap();
+
+        let format: FlacFormat<_> =
+            FlacFormat::write_index(Cursor::new(Vec::new()), [spec]).unwrap();
+        let mut codec = FlacCodec::<_, i16>::from_stream(format).unwrap();
+
+        for chunk in expected.chunks(10 * SPEC.n_channels) {
+            Poll(&mut codec).write_exact(chunk).unwrap();
+        }
+
+        assert_eq!(codec.pos(), expected.len() as u64);
+        codec.flush().unwrap();
+
+        let mut format = codec.into_inner();
+        format.finalize().unwrap();
+
+        let mut codec = decode(format.into_inner().into_inner());
+        let info = codec.as_inner().stream_info();
+        assert_eq!(info.min_block_size as usize, compression.block_size);
+        assert_eq!(info.max_block_size as usize, compression.block_size);
+        assert_eq!(info.total_samples, 10000);
+
+        let mut buf = vec![MaybeUninit::uninit(); expected.len()];
+        let decoded = Poll(&mut codec).read_exact_init(&mut buf).unwrap();
+        assert_eq!(decoded, expected.as_slice());
+    }
+
+    #[test]
+    fn packed_24_bit_samples_are_encoded() {
+        let expected = samples(5000)
+            .into_iter()
+            .map(|s| I24::new((s as i32) << 8 | 0x5A))
+            .collect::<Vec<_>>();
+
+        let spec = StreamSpec::<FlacCodecTag>::builder()
+            .with_sample_type::<I24>()
+            .with_decoded_spec(SPEC)
+            .inferred()
+            .unwrap();
+
+        let format: FlacFormat<_> =
+            FlacFormat::write_index(Cursor::new(Vec::new()), [spec]).unwrap();
+        let mut codec = FlacCodec::<_, I24>::from_stream(format).unwrap();
+        Poll(&mut codec).write_exact(&expected).unwrap();
+        codec.flush().unwrap();
+
+        let mut format = codec.into_inner();
+        format.finalize().unwrap();
+
+        let bytes = format.into_inner().into_inner();
+        let format: FlacFormat<_> = FlacFormat::read_index(Cursor::new(bytes)).unwrap();
+        let info = *format.stream_info();
+        assert_eq!(info.bits_per_sample, 24);
+        assert_eq!(info.md5, md5(expected.iter().map(|s| s.get() as i64), 24));
+
+        let mut codec = FlacCodec::<_, I24>::from_stream(format).unwrap();
+        let mut buf = vec![MaybeUninit::uninit(); expected.len()];
+        let decoded = Poll(&mut codec).read_exact_init(&mut buf).unwrap();
+        assert_eq!(decoded, expected.as_slice());
+    }
+
+    #[test]
+    fn reference_encoder_streams_are_decoded() {
+        // encoded by libFLAC 1.3.2 with wasted bits in some subframes, from the claxon test
+        // samples
+        let bytes = include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/testdata/wasted_bits.flac"
+        ));
+
+        let mut codec = decode(bytes.to_vec());
+        let info = *codec.as_inner().stream_info();
+        assert_eq!(info.n_channels, 1);
+        assert_eq!(info.bits_per_sample, 16);
+
+        let mut buf = vec![MaybeUninit::uninit(); info.total_samples as usize];
+        let decoded = Poll(&mut codec).read_exact_init(&mut buf).unwrap();
+        assert_eq!(info.md5, md5(decoded.iter().map(|s| *s as i64), 16));
+
+        let mut buf = [MaybeUninit::uninit(); 1];
+        assert_eq!(codec.read(&mut buf).unwrap(), 0);
+    }
+
+    #[cfg(feature = "dynamic")]
+    #[test]
+    fn flac_extension_is_known() {
+        use crate::dynamic::{FormatIdentifier, KnownFormat};
+
+        let id = FormatIdentifier::FileExtension("flac");
+        assert_eq!(id.known_format(), Some(KnownFormat::Flac));
+
+        let id = FormatIdentifier::MimeType("audio/flac");
+        assert_eq!(id.known_format(), Some(KnownFormat::Flac));
+    }
+}
@@ -0,0 +1,2 @@
+pub const KNOWN_FLAC_FILE_EXTENSIONS: [&str; 1] = ["flac"];
+pub const KNOWN_FLAC_MIME_TYPES: [&str; 2] = ["audio/flac", "audio/x-flac"];
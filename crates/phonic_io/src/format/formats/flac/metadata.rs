@@ -0,0 +1,240 @@
+// https://www.rfc-editor.org/rfc/rfc9639.html#name-metadata-blocks
+// https://www.xiph.org/vorbis/doc/v-comment.html
+
+use std::io::{self, ErrorKind, Read, Write};
+
+/// The properties of a flac stream stored in its first metadata block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamInfo {
+    pub min_block_size: u16,
+    pub max_block_size: u16,
+    pub min_frame_size: u32,
+    pub max_frame_size: u32,
+    pub sample_rate: u32,
+    pub n_channels: u8,
+    pub bits_per_sample: u8,
+
+    /// The number of frames in the stream or zero if unknown
+    pub total_samples: u64,
+    pub md5: [u8; 16],
+}
+
+/// A frame that can be seeked to directly. `offset` is the number of bytes between the first
+/// frame and the target frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeekPoint {
+    pub sample: u64,
+    pub offset: u64,
+    pub n_samples: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VorbisComment {
+    pub vendor: String,
+    pub comments: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BlockType {
+    StreamInfo,
+    SeekTable,
+    VorbisComment,
+    Other(u8),
+}
+
+pub(super) struct BlockHeader {
+    pub is_last: bool,
+    pub block_type: BlockType,
+    pub len: u32,
+}
+
+impl StreamInfo {
+    pub const LEN: u32 = 34;
+
+    pub(super) fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut bytes = [0u8; Self::LEN as usize];
+        reader.read_exact(&mut bytes)?;
+
+        let packed = u64::from_be_bytes(bytes[10..18].try_into().unwrap());
+        let mut md5 = [0u8; 16];
+        md5.copy_from_slice(&bytes[18..]);
+
+        Ok(Self {
+            min_block_size: u16::from_be_bytes([bytes[0], bytes[1]]),
+            max_block_size: u16::from_be_bytes([bytes[2], bytes[3]]),
+            min_frame_size: u32::from_be_bytes([0, bytes[4], bytes[5], bytes[6]]),
+            max_frame_size: u32::from_be_bytes([0, bytes[7], bytes[8], bytes[9]]),
+            sample_rate: (packed >> 44) as u32,
+            n_channels: ((packed >> 41) & 0x7) as u8 + 1,
+            bits_per_sample: ((packed >> 36) & 0x1F) as u8 + 1,
+            total_samples: packed & 0xF_FFFF_FFFF,
+            md5,
+        })
+    }
+
+    pub(super) fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let packed = (self.sample_rate as u64) << 44
+            | ((self.n_channels - 1) as u64) << 41
+            | ((self.bits_per_sample - 1) as u64) << 36
+            | self.total_samples & 0xF_FFFF_FFFF;
+
+        writer.write_all(&self.min_block_size.to_be_bytes())?;
+        writer.write_all(&self.max_block_size.to_be_bytes())?;
+        writer.write_all(&self.min_frame_size.to_be_bytes()[1..])?;
+        writer.write_all(&self.max_frame_size.to_be_bytes()[1..])?;
+        writer.write_all(&packed.to_be_bytes())?;
+        writer.write_all(&self.md5)
+    }
+}
+
+impl SeekPoint {
+    pub const LEN: u32 = 18;
+    pub const PLACEHOLDER: Self = Self {
+        sample: u64::MAX,
+        offset: 0,
+        n_samples: 0,
+    };
+
+    pub fn is_placeholder(&self) -> bool {
+        self.sample == u64::MAX
+    }
+
+    pub(super) fn read_table(reader: &mut impl Read, len: u32) -> io::Result<Vec<Self>> {
+        if !len.is_multiple_of(Self::LEN) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "invalid seek table length",
+            ));
+        }
+
+        (0..len / Self::LEN)
+            .map(|_| {
+                let mut bytes = [0u8; Self::LEN as usize];
+                reader.read_exact(&mut bytes)?;
+
+                Ok(Self {
+                    sample: u64::from_be_bytes(bytes[..8].try_into().unwrap()),
+                    offset: u64::from_be_bytes(bytes[8..16].try_into().unwrap()),
+                    n_samples: u16::from_be_bytes([bytes[16], bytes[17]]),
+                })
+            })
+            .collect()
+    }
+
+    pub(super) fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&self.sample.to_be_bytes())?;
+        writer.write_all(&self.offset.to_be_bytes())?;
+        writer.write_all(&self.n_samples.to_be_bytes())
+    }
+}
+
+impl VorbisComment {
+    /// Returns the value of the first comment with the given field name, ignoring case
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.comments
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub(super) fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let vendor = read_string(reader)?;
+        let n_comments = read_u32_le(reader)?;
+
+        let mut comments = Vec::new();
+        for _ in 0..n_comments {
+            let comment = read_string(reader)?;
+            if let Some((key, value)) = comment.split_once('=') {
+                comments.push((key.to_owned(), value.to_owned()));
+            }
+        }
+
+        Ok(Self { vendor, comments })
+    }
+
+    pub(super) fn len(&self) -> u32 {
+        let comments = self
+            .comments
+            .iter()
+            .map(|(k, v)| 4 + k.len() + 1 + v.len())
+            .sum::<usize>();
+
+        (4 + self.vendor.len() + 4 + comments) as u32
+    }
+
+    pub(super) fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&(self.vendor.len() as u32).to_le_bytes())?;
+        writer.write_all(self.vendor.as_bytes())?;
+        writer.write_all(&(self.comments.len() as u32).to_le_bytes())?;
+
+        for (key, value) in &self.comments {
+            writer.write_all(&((key.len() + 1 + value.len()) as u32).to_le_bytes())?;
+            writer.write_all(key.as_bytes())?;
+            writer.write_all(b"=")?;
+            writer.write_all(value.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BlockHeader {
+    pub const LEN: u32 = 4;
+
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut bytes = [0u8; Self::LEN as usize];
+        reader.read_exact(&mut bytes)?;
+
+        let block_type = match bytes[0] & 0x7F {
+            0 => BlockType::StreamInfo,
+            3 => BlockType::SeekTable,
+            4 => BlockType::VorbisComment,
+            127 => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "invalid metadata block type",
+                ))
+            }
+            n => BlockType::Other(n),
+        };
+
+        Ok(Self {
+            is_last: bytes[0] & 0x80 != 0,
+            block_type,
+            len: u32::from_be_bytes([0, bytes[1], bytes[2], bytes[3]]),
+        })
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        let block_type = match self.block_type {
+            BlockType::StreamInfo => 0,
+            BlockType::SeekTable => 3,
+            BlockType::VorbisComment => 4,
+            BlockType::Other(n) => n,
+        };
+
+        let mut bytes = self.len.to_be_bytes();
+        bytes[0] = (self.is_last as u8) << 7 | block_type;
+
+        writer.write_all(&bytes)
+    }
+}
+
+fn read_u32_le(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let len = read_u32_le(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+
+    if bytes.len() != len as usize {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+
+    String::from_utf8(bytes).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
@@ -0,0 +1,9 @@
+mod format;
+mod identifiers;
+mod metadata;
+mod tag;
+
+pub use format::*;
+pub use identifiers::*;
+pub use metadata::*;
+pub use tag::*;
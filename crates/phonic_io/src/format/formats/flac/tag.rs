@@ -0,0 +1,40 @@
+use crate::{codecs::flac::FlacCodecTag, FormatTag};
+#[cfg(feature = "dynamic")]
+use phonic_signal::PhonicError;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct FlacFormatTag;
+
+impl FormatTag for FlacFormatTag {
+    type Codec = FlacCodecTag;
+}
+
+#[cfg(feature = "dynamic")]
+impl From<FlacFormatTag> for crate::dynamic::KnownFormat {
+    fn from(tag: FlacFormatTag) -> Self {
+        match tag {
+            FlacFormatTag => Self::Flac,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<crate::dynamic::KnownFormat> for Option<FlacFormatTag> {
+    fn from(format: crate::dynamic::KnownFormat) -> Self {
+        match format {
+            crate::dynamic::KnownFormat::Flac => Some(FlacFormatTag),
+
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl TryFrom<crate::dynamic::KnownFormat> for FlacFormatTag {
+    type Error = PhonicError;
+
+    fn try_from(format: crate::dynamic::KnownFormat) -> Result<Self, Self::Error> {
+        Option::<Self>::from(format).ok_or(PhonicError::unsupported())
+    }
+}
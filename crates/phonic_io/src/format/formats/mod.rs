@@ -1,2 +1,11 @@
+#[cfg(feature = "aiff")]
+pub mod aiff;
+
+#[cfg(feature = "flac")]
+pub mod flac;
+
 #[cfg(feature = "wave")]
 pub mod wave;
+
+#[cfg(any(feature = "aiff", feature = "wave"))]
+mod riff;
@@ -0,0 +1,317 @@
+use phonic_signal::utils::{DefaultSizedBuf, SizedBuf};
+use std::io::{self, ErrorKind, Read, Seek, SeekFrom, Write};
+
+/// The length written to the headers of chunks whose length is not known when they are written,
+/// following the convention used by ffmpeg and sox. RF64 files also use it for the chunks whose
+/// lengths are stored in the ds64 chunk.
+pub(super) const UNSIZED_CHUNK_LEN: u32 = u32::MAX;
+
+/// The byte order of chunk sizes. RIFF files use little endian sizes while IFF derived files
+/// such as AIFF use big endian sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ChunkEndianess {
+    #[cfg_attr(not(feature = "wave"), allow(dead_code))]
+    Little,
+    #[cfg_attr(not(feature = "aiff"), allow(dead_code))]
+    Big,
+}
+
+/// A chunk of a RIFF or IFF file. Chunks whose length is unknown, either because the header
+/// holds the `UNSIZED_CHUNK_LEN` placeholder or because the file ended before the chunk boundary, run
+/// until the end of the inner reader and report the number of bytes seen so far as their length.
+pub(super) struct RiffChunk<T> {
+    inner: T,
+    id: [u8; 4],
+    len: u64,
+    pos: u64,
+    is_sized: bool,
+    endianess: ChunkEndianess,
+}
+
+impl ChunkEndianess {
+    fn decode(self, bytes: [u8; 4]) -> u32 {
+        match self {
+            Self::Little => u32::from_le_bytes(bytes),
+            Self::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    fn encode(self, len: u32) -> [u8; 4] {
+        match self {
+            Self::Little => len.to_le_bytes(),
+            Self::Big => len.to_be_bytes(),
+        }
+    }
+}
+
+impl<T> RiffChunk<T> {
+    #[cfg(feature = "wave")]
+    pub fn read_new(inner: T) -> io::Result<Self>
+    where
+        T: Read,
+    {
+        Self::read_new_with_endianess(inner, ChunkEndianess::Little)
+    }
+
+    pub fn read_new_with_endianess(mut inner: T, endianess: ChunkEndianess) -> io::Result<Self>
+    where
+        T: Read,
+    {
+        let mut buf = [0u8; 8];
+        inner.read_exact(&mut buf)?;
+
+        let [id, len_bytes]: [[u8; 4]; 2] = unsafe { std::mem::transmute(buf) };
+        let len = endianess.decode(len_bytes);
+        let is_sized = len != UNSIZED_CHUNK_LEN;
+
+        Ok(Self {
+            inner,
+            id,
+            len: if is_sized { len as u64 } else { 0 },
+            pos: 0,
+            is_sized,
+            endianess,
+        })
+    }
+
+    #[cfg(feature = "wave")]
+    pub fn write_new(inner: T, id: [u8; 4]) -> io::Result<Self>
+    where
+        T: Write,
+    {
+        Self::write_new_with_endianess(inner, id, ChunkEndianess::Little)
+    }
+
+    pub fn write_new_with_endianess(
+        inner: T,
+        id: [u8; 4],
+        endianess: ChunkEndianess,
+    ) -> io::Result<Self>
+    where
+        T: Write,
+    {
+        Self::write_new_with_len(inner, id, 0, endianess)
+    }
+
+    #[cfg(feature = "wave")]
+    /// Writes the header of a chunk whose length is not known ahead of time to a writer that can
+    /// not seek back to update it
+    pub fn write_new_unsized(inner: T, id: [u8; 4]) -> io::Result<Self>
+    where
+        T: Write,
+    {
+        let mut chunk =
+            Self::write_new_with_len(inner, id, UNSIZED_CHUNK_LEN, ChunkEndianess::Little)?;
+        chunk.len = 0;
+        chunk.is_sized = false;
+
+        Ok(chunk)
+    }
+
+    pub fn write_new_with_len(
+        mut inner: T,
+        id: [u8; 4],
+        len: u32,
+        endianess: ChunkEndianess,
+    ) -> io::Result<Self>
+    where
+        T: Write,
+    {
+        let len_bytes = endianess.encode(len);
+
+        inner.write_all(&id)?;
+        inner.write_all(&len_bytes)?;
+
+        Ok(Self {
+            inner,
+            id,
+            len: len as u64,
+            pos: 0,
+            is_sized: true,
+            endianess,
+        })
+    }
+
+    pub fn id(&self) -> [u8; 4] {
+        self.id
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn pos(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns false if the chunk runs until the end of the inner reader
+    pub fn is_sized(&self) -> bool {
+        self.is_sized
+    }
+
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn skip_remaining(&mut self) -> io::Result<()>
+    where
+        T: Read,
+    {
+        let mut buf = <DefaultSizedBuf<_>>::filled(0u8);
+
+        while self.pos < self.len || !self.is_sized {
+            let len = if self.is_sized {
+                buf.len().min((self.len - self.pos) as usize)
+            } else {
+                buf.len()
+            };
+
+            match self.inner.read(&mut buf[..len]) {
+                Ok(0) if !self.is_sized => break,
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "reached end of file before expected chunk boundary",
+                    ))
+                }
+                Ok(n) => {
+                    self.pos += n as u64;
+                    self.len = self.len.max(self.pos);
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the length of the chunk to its header. Unsized chunks are left untouched.
+    pub fn update_header(&mut self) -> io::Result<()>
+    where
+        T: Write + Seek,
+    {
+        if !self.is_sized {
+            return Ok(());
+        }
+
+        let Ok(len) = u32::try_from(self.len) else {
+            return Err(io::Error::new(
+                ErrorKind::FileTooLarge,
+                "chunk length does not fit in the chunk header",
+            ));
+        };
+
+        self.rewrite_header(self.id, len)
+    }
+
+    /// Replaces the id and length in the header of the chunk, such as when a placeholder chunk is
+    /// replaced or a RIFF file is upgraded to RF64
+    pub fn rewrite_header(&mut self, id: [u8; 4], len: u32) -> io::Result<()>
+    where
+        T: Write + Seek,
+    {
+        let pos = self.pos as i64;
+        let len_bytes = self.endianess.encode(len);
+        let header_offset = -pos - (id.len() + len_bytes.len()) as i64;
+
+        self.inner.seek_relative(header_offset)?;
+        self.inner.write_all(&id)?;
+        self.inner.write_all(&len_bytes)?;
+        self.inner.seek_relative(pos)?;
+
+        self.id = id;
+        Ok(())
+    }
+
+    #[cfg(feature = "wave")]
+    /// Sets the length of a chunk whose header holds the `UNSIZED_CHUNK_LEN` placeholder, such as the
+    /// chunks of an RF64 file whose lengths are stored in the ds64 chunk
+    pub fn set_len(&mut self, len: u64) {
+        self.len = len;
+        self.is_sized = true;
+    }
+}
+
+impl<T: Read> Read for RiffChunk<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let buf_len = if self.is_sized {
+            buf.len().min((self.len - self.pos) as usize)
+        } else {
+            buf.len()
+        };
+
+        if buf_len == 0 {
+            return Ok(0);
+        }
+
+        let n = self.inner.read(&mut buf[..buf_len])?;
+        if n == 0 && self.is_sized {
+            // the file was truncated, so the chunk runs until the end of the file instead
+            self.is_sized = false;
+            self.len = self.pos;
+        }
+
+        self.pos += n as u64;
+        self.len = self.len.max(self.pos);
+
+        Ok(n)
+    }
+}
+
+impl<T: Write> Write for RiffChunk<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+
+        self.pos += n as u64;
+        self.len = self.len.max(self.pos);
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: Seek> Seek for RiffChunk<T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos_result = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(offset) => self.pos.checked_add_signed(offset),
+            SeekFrom::End(offset) if self.is_sized => self.len.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        };
+
+        let out_of_bounds_err = io::Error::new(
+            ErrorKind::InvalidInput,
+            "attempted to seek outside of the defined bounds of the chunk",
+        );
+
+        let Some(new_pos) = new_pos_result else {
+            return Err(out_of_bounds_err);
+        };
+
+        if self.is_sized && new_pos > self.len {
+            return Err(out_of_bounds_err);
+        }
+
+        self.inner
+            .seek(SeekFrom::Current(new_pos as i64 - self.pos as i64))?;
+
+        self.pos = new_pos;
+        self.len = self.len.max(self.pos);
+        Ok(new_pos)
+    }
+}
+
+pub(super) fn update_nested_chunk_header<W: Write + Seek>(
+    chunk: &mut RiffChunk<RiffChunk<W>>,
+) -> io::Result<()> {
+    chunk.inner.update_header()?;
+    chunk.update_header()
+}
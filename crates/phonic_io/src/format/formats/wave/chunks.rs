@@ -1,15 +1,16 @@
 // https://www.mmsp.ece.mcgill.ca/Documents/AudioFormats/WAVE/WAVE.html
 // https://ccrma.stanford.edu/courses/422-winter-2014/projects/WaveFormat/
 // https://tech.ebu.ch/docs/tech/tech3285.pdf
+// https://tech.ebu.ch/docs/tech/tech3306v1_1.pdf
 // https://github.com/tpn/winsdk-10/blob/master/Include/10.0.14393.0/shared/mmreg.h
 // https://datatracker.ietf.org/doc/html/rfc2361
 
 use crate::{
-    formats::wave::{RiffChunk, WaveSupportedCodec},
+    formats::wave::{ChunkEndianess, RiffChunk, WaveSupportedCodec},
     CodecTag, StreamSpec, StreamSpecBuilder, TypeLayout,
 };
-use phonic_signal::{PhonicError, PhonicResult, SignalSpec};
-use std::io::{self, ErrorKind, Read, Seek, Write};
+use phonic_signal::{ChannelLayout, PhonicError, PhonicResult, SignalSpec, I24};
+use std::io::{self, ErrorKind, Read, Write};
 
 pub(super) struct FmtChunk {
     w_format_tag: u16,
@@ -27,13 +28,23 @@ struct FmtExt {
     sub_format: [u8; 16],
 }
 
-pub(super) struct FactChunk {
-    dw_sample_length: u32,
+/// The chunk holding the 64 bit sizes of an RF64 or BW64 file
+pub(super) struct Ds64Chunk {
+    pub riff_size: u64,
+    pub data_size: u64,
+    pub sample_count: u64,
 }
 
 impl FmtChunk {
     pub const CHUNK_ID: [u8; 4] = *b"fmt ";
 
+    const WAVE_FORMAT_PCM: u16 = 0x0001;
+    const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+    const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+    /// The length of the extension following the `cb_size` field
+    const EXT_LEN: u16 = 22;
+
     pub fn apply_to_spec<C>(self, spec: &mut StreamSpecBuilder<C>) -> PhonicResult<()>
     where
         C: CodecTag,
@@ -51,21 +62,58 @@ impl FmtChunk {
             ..
         } = self;
 
-        let (codec, sample) = match (w_format_tag, w_bits_per_sample) {
-            (0x0001, 8) => Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<u8>())).unzip(),
-            (0x0001, 16) => Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<i16>())).unzip(),
-            (0x0001, 32) => Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<i32>())).unzip(),
-            (0x0003, 32) => Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<f32>())).unzip(),
-            (0x0003, 64) => Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<f64>())).unzip(),
+        let (format_tag, valid_bits, channel_mask) = match extension {
+            Some(ext) if w_format_tag == Self::WAVE_FORMAT_EXTENSIBLE => (
+                ext.sub_format_tag(),
+                ext.w_valid_bits_per_sample,
+                ext.dw_channel_mask,
+            ),
+            _ => (Some(w_format_tag), w_bits_per_sample, 0),
+        };
+
+        // a valid bit count of 0 is used by some encoders to indicate a full container
+        let valid_bits = match valid_bits {
+            0 => w_bits_per_sample,
+            n if n > w_bits_per_sample => return Err(PhonicError::invalid_data()),
+            n => n,
+        };
+
+        // samples are left justified within their containers so padded samples such as 24 bit
+        // samples in 32 bit containers decode as the container type
+        let (codec, sample) = match (format_tag, w_bits_per_sample, valid_bits) {
+            (Some(Self::WAVE_FORMAT_PCM), 8, _) => {
+                Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<u8>())).unzip()
+            }
+            (Some(Self::WAVE_FORMAT_PCM), 16, _) => {
+                Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<i16>())).unzip()
+            }
+            (Some(Self::WAVE_FORMAT_PCM), 24, _) => {
+                Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<I24>())).unzip()
+            }
+            (Some(Self::WAVE_FORMAT_PCM), 32, _) => {
+                Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<i32>())).unzip()
+            }
+            (Some(Self::WAVE_FORMAT_IEEE_FLOAT), 32, 32) => {
+                Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<f32>())).unzip()
+            }
+            (Some(Self::WAVE_FORMAT_IEEE_FLOAT), 64, 64) => {
+                Some((WaveSupportedCodec::PcmLE, TypeLayout::of::<f64>())).unzip()
+            }
             _ => (None, None),
         };
 
+        // masks that don't assign every channel are ignored rather than guessing the remainder
+        let layout = Some(ChannelLayout::from_mask(channel_mask))
+            .filter(|layout| channel_mask != 0 && layout.n_channels() == n_channels as usize);
+
         spec.codec = codec.map(TryInto::try_into).transpose()?;
         spec.sample = sample;
+        spec.valid_bits = (valid_bits < w_bits_per_sample).then_some(valid_bits as u32);
 
         spec.decoded = SignalSpec::builder()
             .with_n_channels(n_channels as usize)
-            .with_sample_rate(n_samples_per_sec as usize);
+            .with_sample_rate(n_samples_per_sec as usize)
+            .with_layout(layout);
 
         spec.byte_rate = Some(n_avg_bytes_per_sec as usize);
         spec.block_align = Some(n_block_align as usize);
@@ -87,18 +135,15 @@ impl FmtChunk {
             Err(e) => return Err(e),
         };
 
-        let extension = if let Some(cb_size) = cb_size {
-            const EXPECTED_EXT_LEN: u16 = 22;
-            if cb_size != EXPECTED_EXT_LEN {
+        let extension = match cb_size {
+            None | Some(0) => None,
+            Some(Self::EXT_LEN) => Some(FmtExt::read(reader)?),
+            Some(_) => {
                 return Err(io::Error::new(
                     ErrorKind::InvalidData,
                     "invalid format chunk extension length",
-                ));
+                ))
             }
-
-            Some(FmtExt::read(reader)?)
-        } else {
-            None
         };
 
         Ok(Self {
@@ -134,31 +179,67 @@ impl FmtChunk {
             byte_rate,
             block_align,
             sample,
+            valid_bits,
             decoded:
                 SignalSpec {
                     sample_rate,
                     n_channels,
+                    layout,
                 },
         } = spec;
 
         let native_codec = codec.try_into()?;
-        let w_format_tag = match native_codec {
-            WaveSupportedCodec::PcmLE if sample.is::<u8>() => 0x0001,
-            WaveSupportedCodec::PcmLE if sample.is::<i16>() => 0x0001,
-            WaveSupportedCodec::PcmLE if sample.is::<i32>() => 0x0001,
-            WaveSupportedCodec::PcmLE if sample.is::<f32>() => 0x0003,
-            WaveSupportedCodec::PcmLE if sample.is::<f64>() => 0x0003,
+        let format_tag = match native_codec {
+            WaveSupportedCodec::PcmLE if sample.is::<u8>() => Self::WAVE_FORMAT_PCM,
+            WaveSupportedCodec::PcmLE if sample.is::<i16>() => Self::WAVE_FORMAT_PCM,
+            WaveSupportedCodec::PcmLE if sample.is::<I24>() => Self::WAVE_FORMAT_PCM,
+            WaveSupportedCodec::PcmLE if sample.is::<i32>() => Self::WAVE_FORMAT_PCM,
+            WaveSupportedCodec::PcmLE if sample.is::<f32>() => Self::WAVE_FORMAT_IEEE_FLOAT,
+            WaveSupportedCodec::PcmLE if sample.is::<f64>() => Self::WAVE_FORMAT_IEEE_FLOAT,
             _ => return Err(PhonicError::unsupported()),
         };
 
+        let w_bits_per_sample = sample.size() as u16 * 8;
+        let w_valid_bits_per_sample = match valid_bits {
+            None => w_bits_per_sample,
+            Some(bits)
+                if format_tag == Self::WAVE_FORMAT_PCM && bits <= w_bits_per_sample as u32 =>
+            {
+                bits as u16
+            }
+            Some(_) => return Err(PhonicError::unsupported()),
+        };
+
+        // the extensible format is required to describe more than 2 channels or 16 bits, or
+        // samples that don't fill their containers
+        let is_extensible = n_channels > 2
+            || w_bits_per_sample > 16
+            || w_valid_bits_per_sample != w_bits_per_sample
+            || layout.is_some();
+
+        // layouts that can't be described by a channel mask are rejected rather than written as
+        // unassigned channels
+        let dw_channel_mask = match layout {
+            Some(layout) => layout.mask().ok_or(PhonicError::unsupported())?,
+            None => 0,
+        };
+
+        let extension = is_extensible
+            .then(|| FmtExt::new(format_tag, w_valid_bits_per_sample, dw_channel_mask));
+
+        let w_format_tag = match extension {
+            Some(_) => Self::WAVE_FORMAT_EXTENSIBLE,
+            None => format_tag,
+        };
+
         Ok(Self {
             w_format_tag,
             n_channels: n_channels as u16,
             n_samples_per_sec: sample_rate as u32,
             n_avg_bytes_per_sec: byte_rate as u32,
             n_block_align: block_align as u16,
-            w_bits_per_sample: sample.size() as u16 * 8,
-            extension: None,
+            w_bits_per_sample,
+            extension,
         })
     }
 
@@ -181,17 +262,25 @@ impl FmtChunk {
         writer.write_all(&w_bits_per_sample.to_le_bytes())?;
 
         if let Some(extension) = extension {
+            writer.write_all(&Self::EXT_LEN.to_le_bytes())?;
             extension.write(writer)?
         }
 
         Ok(())
     }
 
-    pub fn write_riff_chunk<W: Write + Seek>(self, writer: &mut W) -> io::Result<()> {
-        let mut chunk = RiffChunk::write_new(writer, Self::CHUNK_ID)?;
+    pub fn write_riff_chunk<W: Write>(self, writer: &mut W) -> io::Result<()> {
+        // the chunk is buffered so its length is known before the header is written
+        let mut buf = Vec::with_capacity(40);
+        self.write_inner(&mut buf)?;
 
-        self.write_inner(&mut chunk)?;
-        chunk.update_header()?;
+        let mut chunk = RiffChunk::write_new_with_len(
+            writer,
+            Self::CHUNK_ID,
+            buf.len() as u32,
+            ChunkEndianess::Little,
+        )?;
+        chunk.write_all(&buf)?;
 
         debug_assert!(matches!(chunk.len(), 16 | 18 | 40));
         debug_assert_eq!(chunk.pos(), chunk.len());
@@ -201,6 +290,31 @@ impl FmtChunk {
 }
 
 impl FmtExt {
+    /// The bytes following the format tag in every `KSDATAFORMAT_SUBTYPE` guid derived from a
+    /// wave format tag
+    const SUB_FORMAT_SUFFIX: [u8; 14] = [
+        0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+    ];
+
+    fn new(format_tag: u16, w_valid_bits_per_sample: u16, dw_channel_mask: u32) -> Self {
+        let mut sub_format = [0u8; 16];
+        sub_format[..2].copy_from_slice(&format_tag.to_le_bytes());
+        sub_format[2..].copy_from_slice(&Self::SUB_FORMAT_SUFFIX);
+
+        Self {
+            w_valid_bits_per_sample,
+            dw_channel_mask,
+            sub_format,
+        }
+    }
+
+    /// The wave format tag of the sub format, or `None` if the sub format guid isn't derived from
+    /// a wave format tag
+    fn sub_format_tag(&self) -> Option<u16> {
+        let (tag, suffix) = self.sub_format.split_at(2);
+        (suffix == Self::SUB_FORMAT_SUFFIX).then(|| u16::from_le_bytes([tag[0], tag[1]]))
+    }
+
     fn read(reader: &mut impl Read) -> io::Result<Self> {
         let w_valid_bits_per_sample = read_u16(reader)?;
         let dw_channel_mask = read_u32(reader)?;
@@ -230,37 +344,61 @@ impl FmtExt {
     }
 }
 
-impl FactChunk {
-    pub const CHUNK_ID: [u8; 4] = *b"fact";
+impl Ds64Chunk {
+    pub const CHUNK_ID: [u8; 4] = *b"ds64";
+
+    /// The length of a ds64 chunk without a table of chunk sizes
+    pub const LEN: u32 = 28;
 
     fn read_inner(reader: &mut impl Read) -> io::Result<Self> {
-        let dw_sample_length = read_u32(reader)?;
-        Ok(Self { dw_sample_length })
+        let riff_size = read_u64(reader)?;
+        let data_size = read_u64(reader)?;
+        let sample_count = read_u64(reader)?;
+
+        Ok(Self {
+            riff_size,
+            data_size,
+            sample_count,
+        })
     }
 
     pub fn read_riff_chunk(chunk: &mut RiffChunk<impl Read>) -> io::Result<Self> {
-        // TODO: should these be enforced
         debug_assert_eq!(chunk.id(), Self::CHUNK_ID);
-        debug_assert_eq!(chunk.len(), 4);
         debug_assert_eq!(chunk.pos(), 0);
 
-        let fact = Self::read_inner(chunk)?;
-        Ok(fact)
+        let ds64 = Self::read_inner(chunk)?;
+
+        // the table holds the sizes of chunks other than the riff and data chunks, which are
+        // never larger than 4 GiB in practice
+        chunk.skip_remaining()?;
+
+        Ok(ds64)
     }
 
     fn write_inner(self, writer: &mut impl Write) -> io::Result<()> {
-        let Self { dw_sample_length } = self;
+        let Self {
+            riff_size,
+            data_size,
+            sample_count,
+        } = self;
+
+        writer.write_all(&riff_size.to_le_bytes())?;
+        writer.write_all(&data_size.to_le_bytes())?;
+        writer.write_all(&sample_count.to_le_bytes())?;
 
-        writer.write_all(&dw_sample_length.to_le_bytes())
+        // table length
+        writer.write_all(&0u32.to_le_bytes())
     }
 
-    pub fn write_riff_chunk<W: Write + Seek>(self, writer: W) -> io::Result<()> {
-        let mut chunk = RiffChunk::write_new(writer, Self::CHUNK_ID)?;
+    pub fn write_riff_chunk<W: Write>(self, writer: W) -> io::Result<()> {
+        let mut chunk = RiffChunk::write_new_with_len(
+            writer,
+            Self::CHUNK_ID,
+            Self::LEN,
+            ChunkEndianess::Little,
+        )?;
 
         self.write_inner(&mut chunk)?;
-        chunk.update_header()?;
-
-        debug_assert_eq!(chunk.len(), 4);
         debug_assert_eq!(chunk.pos(), chunk.len());
 
         Ok(())
@@ -268,7 +406,7 @@ impl FactChunk {
 }
 
 #[inline]
-fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
+pub(super) fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
     let mut bytes = [0u8; 2];
     reader.read_exact(&mut bytes)?;
 
@@ -276,9 +414,17 @@ fn read_u16(reader: &mut impl Read) -> io::Result<u16> {
 }
 
 #[inline]
-fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+pub(super) fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
     let mut bytes = [0u8; 4];
     reader.read_exact(&mut bytes)?;
 
     Ok(u32::from_le_bytes(bytes))
 }
+
+#[inline]
+pub(super) fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u64::from_le_bytes(bytes))
+}
@@ -0,0 +1,615 @@
+// https://www.mmsp.ece.mcgill.ca/Documents/AudioFormats/WAVE/Docs/riffmci.pdf
+// https://tech.ebu.ch/docs/tech/tech3285.pdf
+// https://www.recordingblogs.com/wiki/sample-chunk-of-a-wave-file
+// http://www.gallery.co.uk/ixml/
+
+use crate::formats::wave::{read_u16, read_u32, read_u64, ChunkEndianess, RiffChunk};
+use std::io::{self, ErrorKind, Read, Write};
+
+/// The metadata chunks of a wave file. Chunks that are not part of this model, or that could not
+/// be parsed without losing information, are kept in `unknown` so that reading a file and writing
+/// its metadata to a new file is lossless.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WaveMetadata {
+    /// The text tags of LIST/INFO chunks, such as `INAM` (title) and `IART` (artist)
+    pub info: Vec<InfoTag>,
+    pub bext: Option<BroadcastExtension>,
+    pub cues: Vec<CuePoint>,
+
+    /// The `labl` chunks of LIST/adtl chunks
+    pub labels: Vec<CueLabel>,
+
+    /// The `note` chunks of LIST/adtl chunks
+    pub notes: Vec<CueLabel>,
+
+    /// The other chunks of LIST/adtl chunks, such as `ltxt`
+    pub associated_data: Vec<RawChunk>,
+    pub sampler: Option<SamplerInfo>,
+
+    /// The raw xml document of an iXML chunk
+    pub ixml: Option<String>,
+    pub unknown: Vec<RawChunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfoTag {
+    pub id: [u8; 4],
+    pub value: String,
+}
+
+/// The broadcast audio extension chunk defined by EBU Tech 3285
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BroadcastExtension {
+    pub description: String,
+    pub originator: String,
+    pub originator_reference: String,
+
+    /// The date the audio was created in the form `yyyy-mm-dd`
+    pub origination_date: String,
+
+    /// The time the audio was created in the form `hh-mm-ss`
+    pub origination_time: String,
+
+    /// The number of samples since midnight at the first sample of the data chunk
+    pub time_reference: u64,
+    pub version: u16,
+    pub umid: [u8; 64],
+
+    /// The loudness fields are in hundredths of a LU or dB and are only defined by version 2
+    pub loudness_value: i16,
+    pub loudness_range: i16,
+    pub max_true_peak_level: i16,
+    pub max_momentary_loudness: i16,
+    pub max_short_term_loudness: i16,
+    pub coding_history: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CuePoint {
+    pub id: u32,
+    pub position: u32,
+    pub chunk_id: [u8; 4],
+    pub chunk_start: u32,
+    pub block_start: u32,
+    pub sample_offset: u32,
+}
+
+/// A text label or note attached to the cue point with the same id
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CueLabel {
+    pub cue_id: u32,
+    pub text: String,
+}
+
+/// The loop points and tuning information of a `smpl` chunk
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SamplerInfo {
+    pub manufacturer: u32,
+    pub product: u32,
+    pub sample_period: u32,
+    pub midi_unity_note: u32,
+    pub midi_pitch_fraction: u32,
+    pub smpte_format: u32,
+    pub smpte_offset: u32,
+    pub loops: Vec<SampleLoop>,
+    pub sampler_data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SampleLoop {
+    pub cue_id: u32,
+    pub loop_type: u32,
+    pub start: u32,
+    pub end: u32,
+    pub fraction: u32,
+    pub play_count: u32,
+}
+
+/// A chunk that is written back exactly as it was read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawChunk {
+    pub id: [u8; 4],
+    pub data: Vec<u8>,
+}
+
+impl WaveMetadata {
+    const LIST_CHUNK_ID: [u8; 4] = *b"LIST";
+    const INFO_LIST_ID: [u8; 4] = *b"INFO";
+    const ADTL_LIST_ID: [u8; 4] = *b"adtl";
+    const LABL_CHUNK_ID: [u8; 4] = *b"labl";
+    const NOTE_CHUNK_ID: [u8; 4] = *b"note";
+    const IXML_CHUNK_ID: [u8; 4] = *b"iXML";
+
+    /// Chunks that only reserve space and are dropped when read
+    const PADDING_CHUNK_IDS: [[u8; 4]; 3] = [*b"JUNK", *b"PAD ", *b"FLLR"];
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns the value of the first INFO tag with the given id
+    pub fn info(&self, id: [u8; 4]) -> Option<&str> {
+        self.info
+            .iter()
+            .find(|tag| tag.id == id)
+            .map(|tag| tag.value.as_str())
+    }
+
+    /// Returns the label of the cue point with the given id
+    pub fn label(&self, cue_id: u32) -> Option<&str> {
+        self.labels
+            .iter()
+            .find(|label| label.cue_id == cue_id)
+            .map(|label| label.text.as_str())
+    }
+
+    pub(super) fn read_riff_chunk(&mut self, chunk: &mut RiffChunk<impl Read>) -> io::Result<()> {
+        let id = chunk.id();
+        if Self::PADDING_CHUNK_IDS.contains(&id) {
+            return chunk.skip_remaining();
+        }
+
+        let mut data = Vec::new();
+        chunk.read_to_end(&mut data)?;
+
+        if self.parse_chunk(id, &data).is_none() {
+            self.unknown.push(RawChunk { id, data });
+        }
+
+        Ok(())
+    }
+
+    /// Adds a chunk to the model. Nothing is changed if the chunk could not be parsed or if its
+    /// fields are already set by another chunk.
+    fn parse_chunk(&mut self, id: [u8; 4], data: &[u8]) -> Option<()> {
+        match id {
+            Self::LIST_CHUNK_ID => {
+                let (list_id, data) = data.split_first_chunk::<4>()?;
+                let chunks = parse_sub_chunks(data)?;
+
+                match *list_id {
+                    Self::INFO_LIST_ID => self.parse_info(chunks),
+                    Self::ADTL_LIST_ID => self.parse_adtl(chunks),
+                    _ => None,
+                }
+            }
+
+            BroadcastExtension::CHUNK_ID if self.bext.is_none() => {
+                self.bext = Some(BroadcastExtension::parse(data)?);
+                Some(())
+            }
+
+            CuePoint::CHUNK_ID if self.cues.is_empty() => {
+                self.cues = CuePoint::parse_chunk(data)?;
+                Some(())
+            }
+
+            SamplerInfo::CHUNK_ID if self.sampler.is_none() => {
+                self.sampler = Some(SamplerInfo::parse(data)?);
+                Some(())
+            }
+
+            Self::IXML_CHUNK_ID if self.ixml.is_none() => {
+                self.ixml = Some(parse_text(data)?);
+                Some(())
+            }
+
+            _ => None,
+        }
+    }
+
+    fn parse_info(&mut self, chunks: Vec<([u8; 4], &[u8])>) -> Option<()> {
+        let tags = chunks
+            .into_iter()
+            .map(|(id, data)| parse_text(data).map(|value| InfoTag { id, value }))
+            .collect::<Option<Vec<_>>>()?;
+
+        self.info.extend(tags);
+        Some(())
+    }
+
+    fn parse_adtl(&mut self, chunks: Vec<([u8; 4], &[u8])>) -> Option<()> {
+        let mut labels = Vec::new();
+        let mut notes = Vec::new();
+        let mut associated_data = Vec::new();
+
+        for (id, data) in chunks {
+            match id {
+                Self::LABL_CHUNK_ID => labels.push(CueLabel::parse(data)?),
+                Self::NOTE_CHUNK_ID => notes.push(CueLabel::parse(data)?),
+                _ => associated_data.push(RawChunk {
+                    id,
+                    data: data.to_vec(),
+                }),
+            }
+        }
+
+        self.labels.extend(labels);
+        self.notes.extend(notes);
+        self.associated_data.extend(associated_data);
+        Some(())
+    }
+
+    pub(super) fn write_riff_chunks(&self, writer: &mut impl Write) -> io::Result<()> {
+        if !self.info.is_empty() {
+            let mut list = Self::INFO_LIST_ID.to_vec();
+            for tag in &self.info {
+                let mut value = tag.value.as_bytes().to_vec();
+                value.push(0);
+
+                write_chunk(&mut list, tag.id, &value)?;
+            }
+
+            write_chunk(writer, Self::LIST_CHUNK_ID, &list)?;
+        }
+
+        if let Some(bext) = &self.bext {
+            write_chunk(writer, BroadcastExtension::CHUNK_ID, &bext.to_bytes()?)?;
+        }
+
+        if let Some(ixml) = &self.ixml {
+            write_chunk(writer, Self::IXML_CHUNK_ID, ixml.as_bytes())?;
+        }
+
+        if !self.cues.is_empty() {
+            write_chunk(
+                writer,
+                CuePoint::CHUNK_ID,
+                &CuePoint::chunk_bytes(&self.cues)?,
+            )?;
+        }
+
+        if !self.labels.is_empty() || !self.notes.is_empty() || !self.associated_data.is_empty() {
+            let mut list = Self::ADTL_LIST_ID.to_vec();
+            for label in &self.labels {
+                write_chunk(&mut list, Self::LABL_CHUNK_ID, &label.to_bytes())?;
+            }
+
+            for note in &self.notes {
+                write_chunk(&mut list, Self::NOTE_CHUNK_ID, &note.to_bytes())?;
+            }
+
+            for chunk in &self.associated_data {
+                write_chunk(&mut list, chunk.id, &chunk.data)?;
+            }
+
+            write_chunk(writer, Self::LIST_CHUNK_ID, &list)?;
+        }
+
+        if let Some(sampler) = &self.sampler {
+            write_chunk(writer, SamplerInfo::CHUNK_ID, &sampler.to_bytes()?)?;
+        }
+
+        for chunk in &self.unknown {
+            write_chunk(writer, chunk.id, &chunk.data)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl BroadcastExtension {
+    const CHUNK_ID: [u8; 4] = *b"bext";
+    const RESERVED_LEN: usize = 180;
+
+    fn parse(mut data: &[u8]) -> Option<Self> {
+        let reader = &mut data;
+        let description = parse_text(take(reader, 256)?)?;
+        let originator = parse_text(take(reader, 32)?)?;
+        let originator_reference = parse_text(take(reader, 32)?)?;
+        let origination_date = parse_text(take(reader, 10)?)?;
+        let origination_time = parse_text(take(reader, 8)?)?;
+        let time_reference = read_u64(reader).ok()?;
+        let version = read_u16(reader).ok()?;
+        let umid = take(reader, 64)?.try_into().ok()?;
+
+        let loudness_value = read_u16(reader).ok()? as i16;
+        let loudness_range = read_u16(reader).ok()? as i16;
+        let max_true_peak_level = read_u16(reader).ok()? as i16;
+        let max_momentary_loudness = read_u16(reader).ok()? as i16;
+        let max_short_term_loudness = read_u16(reader).ok()? as i16;
+
+        // reserved bytes that are not zero belong to a newer version of the chunk
+        if take(reader, Self::RESERVED_LEN)?.iter().any(|b| *b != 0) {
+            return None;
+        }
+
+        let coding_history = parse_text(reader)?;
+
+        Some(Self {
+            description,
+            originator,
+            originator_reference,
+            origination_date,
+            origination_time,
+            time_reference,
+            version,
+            umid,
+            loudness_value,
+            loudness_range,
+            max_true_peak_level,
+            max_momentary_loudness,
+            max_short_term_loudness,
+            coding_history,
+        })
+    }
+
+    fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        write_fixed_text(&mut bytes, &self.description, 256)?;
+        write_fixed_text(&mut bytes, &self.originator, 32)?;
+        write_fixed_text(&mut bytes, &self.originator_reference, 32)?;
+        write_fixed_text(&mut bytes, &self.origination_date, 10)?;
+        write_fixed_text(&mut bytes, &self.origination_time, 8)?;
+        bytes.extend_from_slice(&self.time_reference.to_le_bytes());
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.extend_from_slice(&self.umid);
+
+        for loudness in [
+            self.loudness_value,
+            self.loudness_range,
+            self.max_true_peak_level,
+            self.max_momentary_loudness,
+            self.max_short_term_loudness,
+        ] {
+            bytes.extend_from_slice(&loudness.to_le_bytes());
+        }
+
+        bytes.resize(bytes.len() + Self::RESERVED_LEN, 0);
+        bytes.extend_from_slice(self.coding_history.as_bytes());
+
+        Ok(bytes)
+    }
+}
+
+impl Default for BroadcastExtension {
+    fn default() -> Self {
+        Self {
+            description: String::new(),
+            originator: String::new(),
+            originator_reference: String::new(),
+            origination_date: String::new(),
+            origination_time: String::new(),
+            time_reference: 0,
+            version: 2,
+            umid: [0; 64],
+            loudness_value: 0x7FFF,
+            loudness_range: 0x7FFF,
+            max_true_peak_level: 0x7FFF,
+            max_momentary_loudness: 0x7FFF,
+            max_short_term_loudness: 0x7FFF,
+            coding_history: String::new(),
+        }
+    }
+}
+
+impl CuePoint {
+    const CHUNK_ID: [u8; 4] = *b"cue ";
+    const LEN: usize = 24;
+    const DATA_CHUNK_ID: [u8; 4] = *b"data";
+
+    /// Creates a cue point at a sample offset in the data chunk
+    pub fn new(id: u32, sample_offset: u32) -> Self {
+        Self {
+            id,
+            position: sample_offset,
+            chunk_id: Self::DATA_CHUNK_ID,
+            chunk_start: 0,
+            block_start: 0,
+            sample_offset,
+        }
+    }
+
+    fn parse_chunk(mut data: &[u8]) -> Option<Vec<Self>> {
+        let reader = &mut data;
+        let n_cues = read_u32(reader).ok()? as usize;
+        if reader.len() != n_cues.checked_mul(Self::LEN)? {
+            return None;
+        }
+
+        let mut cues = Vec::with_capacity(n_cues);
+        for _ in 0..n_cues {
+            cues.push(Self {
+                id: read_u32(reader).ok()?,
+                position: read_u32(reader).ok()?,
+                chunk_id: take(reader, 4)?.try_into().ok()?,
+                chunk_start: read_u32(reader).ok()?,
+                block_start: read_u32(reader).ok()?,
+                sample_offset: read_u32(reader).ok()?,
+            });
+        }
+
+        Some(cues)
+    }
+
+    fn chunk_bytes(cues: &[Self]) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(4 + cues.len() * Self::LEN);
+        bytes.extend_from_slice(&len_as_u32(cues.len())?.to_le_bytes());
+
+        for cue in cues {
+            bytes.extend_from_slice(&cue.id.to_le_bytes());
+            bytes.extend_from_slice(&cue.position.to_le_bytes());
+            bytes.extend_from_slice(&cue.chunk_id);
+            bytes.extend_from_slice(&cue.chunk_start.to_le_bytes());
+            bytes.extend_from_slice(&cue.block_start.to_le_bytes());
+            bytes.extend_from_slice(&cue.sample_offset.to_le_bytes());
+        }
+
+        Ok(bytes)
+    }
+}
+
+impl CueLabel {
+    fn parse(mut data: &[u8]) -> Option<Self> {
+        let cue_id = read_u32(&mut data).ok()?;
+        let text = parse_text(data)?;
+
+        Some(Self { cue_id, text })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.cue_id.to_le_bytes().to_vec();
+        bytes.extend_from_slice(self.text.as_bytes());
+        bytes.push(0);
+
+        bytes
+    }
+}
+
+impl SamplerInfo {
+    const CHUNK_ID: [u8; 4] = *b"smpl";
+
+    fn parse(mut data: &[u8]) -> Option<Self> {
+        let reader = &mut data;
+        let mut fields = [0u32; 9];
+        for field in fields.iter_mut() {
+            *field = read_u32(reader).ok()?;
+        }
+
+        let [manufacturer, product, sample_period, midi_unity_note, midi_pitch_fraction, smpte_format, smpte_offset, n_loops, sampler_data_len] =
+            fields;
+
+        let loops_len = (n_loops as usize).checked_mul(SampleLoop::LEN)?;
+        if reader.len() != loops_len.checked_add(sampler_data_len as usize)? {
+            return None;
+        }
+
+        let mut loops = Vec::with_capacity(n_loops as usize);
+        for _ in 0..n_loops {
+            loops.push(SampleLoop {
+                cue_id: read_u32(reader).ok()?,
+                loop_type: read_u32(reader).ok()?,
+                start: read_u32(reader).ok()?,
+                end: read_u32(reader).ok()?,
+                fraction: read_u32(reader).ok()?,
+                play_count: read_u32(reader).ok()?,
+            });
+        }
+
+        Some(Self {
+            manufacturer,
+            product,
+            sample_period,
+            midi_unity_note,
+            midi_pitch_fraction,
+            smpte_format,
+            smpte_offset,
+            loops,
+            sampler_data: reader.to_vec(),
+        })
+    }
+
+    fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let fields = [
+            self.manufacturer,
+            self.product,
+            self.sample_period,
+            self.midi_unity_note,
+            self.midi_pitch_fraction,
+            self.smpte_format,
+            self.smpte_offset,
+            len_as_u32(self.loops.len())?,
+            len_as_u32(self.sampler_data.len())?,
+        ];
+
+        let mut bytes = Vec::new();
+        for field in fields {
+            bytes.extend_from_slice(&field.to_le_bytes());
+        }
+
+        for sample_loop in &self.loops {
+            let SampleLoop {
+                cue_id,
+                loop_type,
+                start,
+                end,
+                fraction,
+                play_count,
+            } = *sample_loop;
+
+            for field in [cue_id, loop_type, start, end, fraction, play_count] {
+                bytes.extend_from_slice(&field.to_le_bytes());
+            }
+        }
+
+        bytes.extend_from_slice(&self.sampler_data);
+        Ok(bytes)
+    }
+}
+
+impl SampleLoop {
+    const LEN: usize = 24;
+}
+
+/// Splits the body of a LIST chunk into the ids and bodies of its chunks
+fn parse_sub_chunks(mut data: &[u8]) -> Option<Vec<([u8; 4], &[u8])>> {
+    let mut chunks = Vec::new();
+    while !data.is_empty() {
+        let (id, rest) = data.split_first_chunk::<4>()?;
+        let (len_bytes, rest) = rest.split_first_chunk::<4>()?;
+        let len = u32::from_le_bytes(*len_bytes) as usize;
+
+        chunks.push((*id, rest.get(..len)?));
+
+        // the pad byte of the last chunk is sometimes left out
+        data = rest.get(len + len % 2..).unwrap_or_default();
+    }
+
+    Some(chunks)
+}
+
+/// Parses a string that is terminated or padded with zeros. Strings that are not valid utf-8 or
+/// that have data after the terminator are rejected so the chunk is kept as is.
+fn parse_text(data: &[u8]) -> Option<String> {
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    if data[end..].iter().any(|b| *b != 0) {
+        return None;
+    }
+
+    String::from_utf8(data[..end].to_vec()).ok()
+}
+
+fn take<'a>(reader: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if reader.len() < len {
+        return None;
+    }
+
+    let (bytes, rest) = reader.split_at(len);
+    *reader = rest;
+    Some(bytes)
+}
+
+fn write_fixed_text(bytes: &mut Vec<u8>, text: &str, len: usize) -> io::Result<()> {
+    if text.len() > len {
+        return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "text does not fit in the chunk field",
+        ));
+    }
+
+    bytes.extend_from_slice(text.as_bytes());
+    bytes.resize(bytes.len() + len - text.len(), 0);
+    Ok(())
+}
+
+fn len_as_u32(len: usize) -> io::Result<u32> {
+    u32::try_from(len).map_err(|_| {
+        io::Error::new(
+            ErrorKind::InvalidInput,
+            "chunk length does not fit in the chunk header",
+        )
+    })
+}
+
+/// Writes a chunk followed by a pad byte if its length is odd
+fn write_chunk(writer: &mut impl Write, id: [u8; 4], data: &[u8]) -> io::Result<()> {
+    let len = len_as_u32(data.len())?;
+    let mut chunk = RiffChunk::write_new_with_len(&mut *writer, id, len, ChunkEndianess::Little)?;
+    chunk.write_all(data)?;
+
+    if !data.len().is_multiple_of(2) {
+        writer.write_all(&[0])?;
+    }
+
+    Ok(())
+}
@@ -1,12 +1,13 @@
 mod chunks;
 mod format;
 mod identifiers;
-mod riff;
+mod metadata;
 mod tag;
 
+use super::riff::*;
 use chunks::*;
-use riff::*;
 
 pub use format::*;
 pub use identifiers::*;
+pub use metadata::*;
 pub use tag::*;
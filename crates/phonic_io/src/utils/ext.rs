@@ -1,7 +1,7 @@
 use crate::{
     utils::{
-        copy_stream_all, copy_stream_exact, DropFinalize, IntoStreamDuration, NBytes, PollIo,
-        StreamSelector,
+        copy_stream_all, copy_stream_exact, AsyncPollIo, DropFinalize, IntoStreamDuration, NBytes,
+        PollIo, StreamSelector,
     },
     BlockingStream, FiniteFormat, FiniteStream, Format, FormatWriter, IndexedFormat, IndexedStream,
     Stream, StreamExt, StreamReader, StreamWriter,
@@ -61,6 +61,10 @@ pub trait FormatUtilsExt: Sized + Format {
     fn polled(self) -> PollIo<Self> {
         PollIo(self)
     }
+
+    fn async_polled(self) -> AsyncPollIo<Self> {
+        AsyncPollIo(self)
+    }
 }
 
 pub trait StreamUtilsExt: Sized + Stream {
@@ -143,6 +147,10 @@ pub trait StreamUtilsExt: Sized + Stream {
     fn polled(self) -> PollIo<Self> {
         PollIo(self)
     }
+
+    fn async_polled(self) -> AsyncPollIo<Self> {
+        AsyncPollIo(self)
+    }
 }
 
 impl<T> FormatUtilsExt for T where T: Sized + Format {}
@@ -1,9 +1,22 @@
-use crate::{delegate_format, delegate_stream, BlockingFormat, BlockingStream, Format, Stream};
-use phonic_signal::utils::Poll;
+use crate::{
+    delegate_format, delegate_stream, AsyncFormatReader, AsyncStreamReader, BlockingFormat,
+    BlockingStream, Format, FormatReader, Stream, StreamReader, WakingFormat, WakingStream,
+};
+use phonic_signal::{poll_on_signal, utils::Poll, PhonicResult};
+use std::{
+    mem::MaybeUninit,
+    task::{self, Context},
+};
 
 #[repr(transparent)]
 pub struct PollIo<T>(pub T);
 
+/// Exposes a stream or format that returns `Err(PhonicError::NotReady)` as an async reader. The
+/// task is registered with `WakingStream::register_waker` or `WakingFormat::register_waker`. See
+/// `phonic_signal::utils::AsyncPoll`.
+#[repr(transparent)]
+pub struct AsyncPollIo<T>(pub T);
+
 delegate_stream! {
     impl<T> * + !BlockingStream for PollIo<T> {
         Self as T;
@@ -22,6 +35,24 @@ delegate_format! {
     }
 }
 
+delegate_stream! {
+    impl<T> * + !Async for AsyncPollIo<T> {
+        Self as T;
+
+        &self => &self.0;
+        &mut self => &mut self.0;
+    }
+}
+
+delegate_format! {
+    impl<T> * + !Async for AsyncPollIo<T> {
+        Self as T;
+
+        &self => &self.0;
+        &mut self => &mut self.0;
+    }
+}
+
 #[macro_export]
 macro_rules! block_on_format {
     ($self:expr, $func:expr, $result:pat => $return:expr) => {
@@ -71,3 +102,154 @@ impl<T: Stream> BlockingStream for PollIo<T> {
         Poll::<()>::interval()
     }
 }
+
+impl<T: FormatReader + WakingFormat> AsyncFormatReader for AsyncPollIo<T> {
+    fn poll_read(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut [MaybeUninit<u8>],
+    ) -> task::Poll<PhonicResult<(usize, usize)>> {
+        poll_on_signal!(cx, WakingFormat::register_waker, &self.0, self.0.read(buf))
+    }
+}
+
+impl<T: StreamReader + WakingStream> AsyncStreamReader for AsyncPollIo<T> {
+    fn poll_read(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut [MaybeUninit<u8>],
+    ) -> task::Poll<PhonicResult<usize>> {
+        poll_on_signal!(cx, WakingStream::register_waker, &self.0, self.0.read(buf))
+    }
+}
+
+#[cfg(all(test, feature = "wave"))]
+mod tests {
+    use crate::{
+        formats::wave::{WaveFormat, WaveSupportedCodec},
+        utils::FormatUtilsExt,
+        AsyncFormatExt, AsyncStreamExt, FormatFromReader, FormatFromWriter, FormatWriter,
+        StreamSpec, StreamWriter,
+    };
+    use phonic_signal::SignalSpec;
+    use std::{
+        cell::Cell,
+        future::Future,
+        io::{self, Read},
+        mem::MaybeUninit,
+        pin::pin,
+        rc::Rc,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{self, Context, Wake, Waker},
+    };
+
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns `ErrorKind::WouldBlock` twice before every read once `is_stalling` is set
+    struct Stalling {
+        inner: io::Cursor<Vec<u8>>,
+        is_stalling: Rc<Cell<bool>>,
+        n_stalls: usize,
+    }
+
+    impl Read for Stalling {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.is_stalling.get() && self.n_stalls < 2 {
+                self.n_stalls += 1;
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+
+            self.n_stalls = 0;
+            self.inner.read(buf)
+        }
+    }
+
+    /// Polls the future to completion, checking that the task was woken every time it was pending
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = pin!(future);
+        loop {
+            let n_wakes = counter.0.load(Ordering::SeqCst);
+            match future.as_mut().poll(&mut cx) {
+                task::Poll::Ready(output) => return output,
+                task::Poll::Pending => assert!(counter.0.load(Ordering::SeqCst) > n_wakes),
+            }
+        }
+    }
+
+    fn wave_file(data: &[u8]) -> (WaveFormat<Stalling>, Rc<Cell<bool>>) {
+        let spec = StreamSpec::builder()
+            .with_codec(WaveSupportedCodec::PcmLE)
+            .with_sample_type::<i16>()
+            .with_decoded_spec(SignalSpec::stereo(48000))
+            .inferred()
+            .unwrap();
+
+        let mut format: WaveFormat<_> =
+            WaveFormat::write_index(io::Cursor::new(Vec::new()), [spec]).unwrap();
+
+        assert_eq!(StreamWriter::write(&mut format, data).unwrap(), data.len());
+        format.finalize().unwrap();
+
+        let is_stalling = Rc::new(Cell::new(false));
+        let reader = Stalling {
+            inner: io::Cursor::new(format.into_inner().into_inner()),
+            is_stalling: is_stalling.clone(),
+            n_stalls: 0,
+        };
+
+        (WaveFormat::read_index(reader).unwrap(), is_stalling)
+    }
+
+    #[test]
+    fn async_formats_read_a_wave_file() {
+        let data: Vec<u8> = (0..64).collect();
+        let (format, is_stalling) = wave_file(&data);
+        is_stalling.set(true);
+
+        let mut format = format.async_polled();
+        let mut buf = [MaybeUninit::uninit(); 24];
+        let mut output = Vec::new();
+
+        loop {
+            let (stream, bytes) =
+                block_on(AsyncFormatExt::read_init_async(&mut format, &mut buf)).unwrap();
+
+            if bytes.is_empty() {
+                break;
+            }
+
+            assert_eq!(stream, 0);
+            output.extend_from_slice(bytes);
+        }
+
+        assert_eq!(output, data);
+    }
+
+    #[test]
+    fn async_streams_read_a_wave_file() {
+        let data: Vec<u8> = (0..64).rev().collect();
+        let (format, is_stalling) = wave_file(&data);
+        is_stalling.set(true);
+
+        let mut stream = format.async_polled();
+        let mut buf = [MaybeUninit::uninit(); 64];
+        block_on(AsyncStreamExt::read_exact_async(&mut stream, &mut buf)).unwrap();
+
+        let output = buf.map(|byte| unsafe { byte.assume_init() });
+        assert_eq!(output, data[..]);
+    }
+}
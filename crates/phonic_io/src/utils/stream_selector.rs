@@ -1,10 +1,10 @@
 use crate::{
     BlockingFormat, BlockingStream, FiniteFormat, FiniteStream, Format, FormatReader, FormatSeeker,
     FormatTag, FormatWriter, IndexedFormat, IndexedStream, Stream, StreamReader, StreamSeeker,
-    StreamSpec, StreamWriter,
+    StreamSpec, StreamWriter, WakingFormat, WakingStream,
 };
 use phonic_signal::PhonicResult;
-use std::mem::MaybeUninit;
+use std::{mem::MaybeUninit, task::Waker};
 
 pub struct StreamSelector<F: Format> {
     inner: F,
@@ -38,6 +38,12 @@ impl<F: BlockingFormat> BlockingStream for StreamSelector<F> {
     }
 }
 
+impl<F: WakingFormat> WakingStream for StreamSelector<F> {
+    fn register_waker(&self, waker: &Waker) {
+        self.inner.register_waker(waker)
+    }
+}
+
 impl<F: IndexedFormat> IndexedStream for StreamSelector<F> {
     fn pos(&self) -> u64 {
         self.inner.stream_pos(self.stream)
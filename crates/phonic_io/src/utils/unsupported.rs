@@ -79,7 +79,7 @@ impl<T: io::Read> io::Read for UnReadable<T> {
 }
 
 delegate_format! {
-    impl<T> * + !FormatReader for UnReadable<T> {
+    impl<T> * + !FormatReader + !AsyncFormatReader for UnReadable<T> {
         Self as T;
 
         &self => &self.0;
@@ -38,6 +38,20 @@ macro_rules! variants {
                 }
             )*
 
+            /// Returns an error of the same kind, so an error can be returned to several readers
+            pub(crate) fn duplicate(&self) -> Self {
+                match self {
+                    $(Self::$variant { $($($field,)*)? .. } => Self::$variant {
+                        _private: Private,
+
+                        #[cfg(debug_assertions)]
+                        location: self.location(),
+
+                        $($($field: Duplicate::duplicate($field)),*)?
+                    }),*
+                }
+            }
+
             #[cfg(debug_assertions)]
             fn location(&self) -> &'static std::panic::Location<'static> {
                 match self {
@@ -91,6 +105,17 @@ variants! {
 
 pub type PhonicResult<T> = Result<T, PhonicError>;
 
+trait Duplicate {
+    fn duplicate(&self) -> Self;
+}
+
+impl Duplicate for std::io::Error {
+    /// Io errors can not be cloned, so only the kind and the message are kept
+    fn duplicate(&self) -> Self {
+        std::io::Error::new(self.kind(), self.to_string())
+    }
+}
+
 impl std::error::Error for PhonicError {}
 
 impl std::fmt::Display for PhonicError {
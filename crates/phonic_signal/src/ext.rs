@@ -1,8 +1,12 @@
 use crate::{
-    block_on_signal, utils::slice_as_init_mut, BlockingSignal, BufferedSignalReader, FiniteSignal,
-    IndexedSignal, PhonicError, PhonicResult, Signal, SignalReader, SignalSeeker, SignalWriter,
+    block_on_signal, utils::slice_as_init_mut, AsyncSignalReader, AsyncSignalWriter,
+    BlockingSignal, BufferedSignalReader, FiniteSignal, IndexedSignal, PhonicError, PhonicResult,
+    Signal, SignalReader, SignalSeeker, SignalWriter,
+};
+use std::{
+    future::{poll_fn, Future},
+    mem::MaybeUninit,
 };
-use std::mem::MaybeUninit;
 
 pub trait SignalExt: Signal {
     fn is_empty(&self) -> bool
@@ -183,3 +187,115 @@ pub trait SignalExt: Signal {
 }
 
 impl<T: Signal> SignalExt for T {}
+
+pub trait AsyncSignalExt: Signal {
+    fn read_async<'a>(
+        &'a mut self,
+        buf: &'a mut [MaybeUninit<Self::Sample>],
+    ) -> impl Future<Output = PhonicResult<usize>> + 'a
+    where
+        Self: AsyncSignalReader,
+    {
+        poll_fn(move |cx| self.poll_read(cx, buf))
+    }
+
+    fn read_init_async<'a>(
+        &'a mut self,
+        buf: &'a mut [MaybeUninit<Self::Sample>],
+    ) -> impl Future<Output = PhonicResult<&'a mut [Self::Sample]>> + 'a
+    where
+        Self: AsyncSignalReader,
+    {
+        async move {
+            let n_samples = self.read_async(buf).await?;
+            let uninit_slice = &mut buf[..n_samples];
+            let init_slice = unsafe { slice_as_init_mut(uninit_slice) };
+
+            Ok(init_slice)
+        }
+    }
+
+    fn read_exact_async<'a>(
+        &'a mut self,
+        mut buf: &'a mut [MaybeUninit<Self::Sample>],
+    ) -> impl Future<Output = PhonicResult<()>> + 'a
+    where
+        Self: AsyncSignalReader,
+    {
+        async move {
+            if buf.len() % self.spec().n_channels != 0 {
+                return Err(PhonicError::invalid_input());
+            }
+
+            while !buf.is_empty() {
+                match self.read_async(buf).await {
+                    Ok(0) => return Err(PhonicError::out_of_bounds()),
+                    Ok(n) => buf = &mut buf[n..],
+                    Err(PhonicError::Interrupted { .. }) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    fn read_exact_init_async<'a>(
+        &'a mut self,
+        buf: &'a mut [MaybeUninit<Self::Sample>],
+    ) -> impl Future<Output = PhonicResult<&'a mut [Self::Sample]>> + 'a
+    where
+        Self: AsyncSignalReader,
+    {
+        async move {
+            self.read_exact_async(buf).await?;
+            let init_slice = unsafe { slice_as_init_mut(buf) };
+
+            Ok(init_slice)
+        }
+    }
+
+    fn write_async<'a>(
+        &'a mut self,
+        buf: &'a [Self::Sample],
+    ) -> impl Future<Output = PhonicResult<usize>> + 'a
+    where
+        Self: AsyncSignalWriter,
+    {
+        poll_fn(move |cx| self.poll_write(cx, buf))
+    }
+
+    fn flush_async(&mut self) -> impl Future<Output = PhonicResult<()>> + '_
+    where
+        Self: AsyncSignalWriter,
+    {
+        poll_fn(move |cx| self.poll_flush(cx))
+    }
+
+    fn write_exact_async<'a>(
+        &'a mut self,
+        mut buf: &'a [Self::Sample],
+    ) -> impl Future<Output = PhonicResult<()>> + 'a
+    where
+        Self: AsyncSignalWriter,
+    {
+        async move {
+            if buf.len() % self.spec().n_channels != 0 {
+                return Err(PhonicError::invalid_input());
+            }
+
+            while !buf.is_empty() {
+                match self.write_async(buf).await {
+                    Ok(0) => return Err(PhonicError::out_of_bounds()),
+                    Ok(n) => buf = &buf[n..],
+                    Err(PhonicError::Interrupted { .. }) => continue,
+                    Err(e) => return Err(e),
+                };
+            }
+
+            Ok(())
+        }
+    }
+}
+
+impl<T: Signal> AsyncSignalExt for T {}
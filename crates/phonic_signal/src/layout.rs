@@ -0,0 +1,282 @@
+use crate::{PhonicError, PhonicResult};
+use std::fmt::{self, Debug};
+
+/// The position of the speaker that a channel is intended for. Positions are declared in the
+/// order of the speaker bits of the `WAVEFORMATEXTENSIBLE` channel mask.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChannelPosition {
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    LowFrequency,
+    BackLeft,
+    BackRight,
+    FrontLeftOfCenter,
+    FrontRightOfCenter,
+    BackCenter,
+    SideLeft,
+    SideRight,
+    TopCenter,
+    TopFrontLeft,
+    TopFrontCenter,
+    TopFrontRight,
+    TopBackLeft,
+    TopBackCenter,
+    TopBackRight,
+
+    /// A channel that isn't intended for a specific speaker
+    Unassigned,
+}
+
+/// The meaning of each channel in a signal.
+///
+/// A layout either assigns a speaker position to each interleaved channel or describes the
+/// components of an ambisonic sound field.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChannelLayout {
+    kind: LayoutKind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum LayoutKind {
+    Speakers {
+        len: u8,
+        positions: [ChannelPosition; ChannelLayout::MAX_SPEAKERS],
+    },
+    Ambisonic {
+        order: u8,
+    },
+}
+
+impl ChannelPosition {
+    /// Every position with a bit in the channel mask, in mask order
+    pub const MASK_POSITIONS: [Self; 18] = [
+        Self::FrontLeft,
+        Self::FrontRight,
+        Self::FrontCenter,
+        Self::LowFrequency,
+        Self::BackLeft,
+        Self::BackRight,
+        Self::FrontLeftOfCenter,
+        Self::FrontRightOfCenter,
+        Self::BackCenter,
+        Self::SideLeft,
+        Self::SideRight,
+        Self::TopCenter,
+        Self::TopFrontLeft,
+        Self::TopFrontCenter,
+        Self::TopFrontRight,
+        Self::TopBackLeft,
+        Self::TopBackCenter,
+        Self::TopBackRight,
+    ];
+
+    /// The `WAVEFORMATEXTENSIBLE` channel mask bit of the position, or 0 for unassigned channels
+    pub const fn mask(self) -> u32 {
+        match self {
+            Self::Unassigned => 0,
+            _ => 1 << self as u8,
+        }
+    }
+
+    pub const fn is_lfe(self) -> bool {
+        matches!(self, Self::LowFrequency)
+    }
+
+    pub const fn is_top(self) -> bool {
+        matches!(
+            self,
+            Self::TopCenter
+                | Self::TopFrontLeft
+                | Self::TopFrontCenter
+                | Self::TopFrontRight
+                | Self::TopBackLeft
+                | Self::TopBackCenter
+                | Self::TopBackRight
+        )
+    }
+}
+
+impl ChannelLayout {
+    /// The maximum number of speaker positions in a layout
+    pub const MAX_SPEAKERS: usize = 32;
+
+    pub const MONO: Self = Self::from_mask(0x4);
+    pub const STEREO: Self = Self::from_mask(0x3);
+    pub const SURROUND_2_1: Self = Self::from_mask(0xB);
+    pub const QUAD: Self = Self::from_mask(0x33);
+    pub const SURROUND_5_1: Self = Self::from_mask(0x60F);
+    pub const SURROUND_7_1: Self = Self::from_mask(0x63F);
+    pub const SURROUND_7_1_4: Self = Self::from_mask(0x2D63F);
+
+    /// Creates a layout from a `WAVEFORMATEXTENSIBLE` channel mask. Channels are assigned to the
+    /// set bits in ascending order and unknown bits are ignored.
+    pub const fn from_mask(mask: u32) -> Self {
+        let mut positions = [ChannelPosition::Unassigned; Self::MAX_SPEAKERS];
+        let mut len = 0;
+
+        let mut i = 0;
+        while i < ChannelPosition::MASK_POSITIONS.len() {
+            let position = ChannelPosition::MASK_POSITIONS[i];
+            if mask & position.mask() != 0 {
+                positions[len] = position;
+                len += 1;
+            }
+
+            i += 1;
+        }
+
+        Self {
+            kind: LayoutKind::Speakers {
+                len: len as u8,
+                positions,
+            },
+        }
+    }
+
+    /// Creates a layout that assigns each channel to the position at the same index
+    pub fn from_positions(positions: &[ChannelPosition]) -> PhonicResult<Self> {
+        if positions.is_empty() || positions.len() > Self::MAX_SPEAKERS {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let mut inner = [ChannelPosition::Unassigned; Self::MAX_SPEAKERS];
+        inner[..positions.len()].copy_from_slice(positions);
+
+        Ok(Self {
+            kind: LayoutKind::Speakers {
+                len: positions.len() as u8,
+                positions: inner,
+            },
+        })
+    }
+
+    /// Creates a full sphere ambisonic layout of the given order with channels in ACN order
+    pub const fn ambisonic(order: u8) -> Self {
+        Self {
+            kind: LayoutKind::Ambisonic { order },
+        }
+    }
+
+    /// The conventional speaker layout for the given number of channels. Channel counts without
+    /// a widely agreed upon layout return `None`.
+    pub fn default_for(n_channels: usize) -> Option<Self> {
+        match n_channels {
+            1 => Some(Self::MONO),
+            2 => Some(Self::STEREO),
+            4 => Some(Self::QUAD),
+            6 => Some(Self::SURROUND_5_1),
+            8 => Some(Self::SURROUND_7_1),
+            _ => None,
+        }
+    }
+
+    pub fn n_channels(&self) -> usize {
+        match self.kind {
+            LayoutKind::Speakers { len, .. } => len as usize,
+            LayoutKind::Ambisonic { order } => (order as usize + 1).pow(2),
+        }
+    }
+
+    /// The speaker positions of the channels, or `None` for ambisonic layouts
+    pub fn positions(&self) -> Option<&[ChannelPosition]> {
+        match &self.kind {
+            LayoutKind::Speakers { len, positions } => Some(&positions[..*len as usize]),
+            LayoutKind::Ambisonic { .. } => None,
+        }
+    }
+
+    pub fn position(&self, channel: usize) -> Option<ChannelPosition> {
+        self.positions()?.get(channel).copied()
+    }
+
+    /// The index of the first channel assigned to the given position
+    pub fn channel_of(&self, position: ChannelPosition) -> Option<usize> {
+        self.positions()?.iter().position(|p| *p == position)
+    }
+
+    pub fn ambisonic_order(&self) -> Option<u8> {
+        match self.kind {
+            LayoutKind::Ambisonic { order } => Some(order),
+            LayoutKind::Speakers { .. } => None,
+        }
+    }
+
+    /// The `WAVEFORMATEXTENSIBLE` channel mask of the layout. Only speaker layouts with distinct
+    /// positions in mask order can be described by a mask.
+    pub fn mask(&self) -> Option<u32> {
+        let mut mask = 0u32;
+        for position in self.positions()? {
+            let bit = position.mask();
+            if bit == 0 || bit <= mask {
+                return None;
+            }
+
+            mask |= bit;
+        }
+
+        Some(mask)
+    }
+}
+
+impl Debug for ChannelLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LayoutKind::Speakers { .. } => f
+                .debug_tuple("Speakers")
+                .field(&self.positions().unwrap_or_default())
+                .finish(),
+            LayoutKind::Ambisonic { order } => f.debug_tuple("Ambisonic").field(&order).finish(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ChannelLayout, ChannelPosition};
+
+    #[test]
+    fn presets_match_their_masks() {
+        let presets = [
+            (ChannelLayout::MONO, 1),
+            (ChannelLayout::STEREO, 2),
+            (ChannelLayout::SURROUND_2_1, 3),
+            (ChannelLayout::QUAD, 4),
+            (ChannelLayout::SURROUND_5_1, 6),
+            (ChannelLayout::SURROUND_7_1, 8),
+            (ChannelLayout::SURROUND_7_1_4, 12),
+        ];
+
+        for (layout, n_channels) in presets {
+            assert_eq!(layout.n_channels(), n_channels);
+
+            let mask = layout.mask().unwrap();
+            assert_eq!(ChannelLayout::from_mask(mask), layout);
+
+            let positions = layout.positions().unwrap();
+            assert_eq!(ChannelLayout::from_positions(positions).unwrap(), layout);
+        }
+
+        let surround = ChannelLayout::SURROUND_5_1;
+        assert_eq!(surround.channel_of(ChannelPosition::LowFrequency), Some(3));
+        assert_eq!(surround.position(4), Some(ChannelPosition::SideLeft));
+    }
+
+    #[test]
+    fn layouts_without_mask_order() {
+        use ChannelPosition::*;
+
+        let swapped = ChannelLayout::from_positions(&[FrontRight, FrontLeft]).unwrap();
+        assert_eq!(swapped.mask(), None);
+        assert_ne!(swapped, ChannelLayout::STEREO);
+
+        let unassigned = ChannelLayout::from_positions(&[FrontLeft, Unassigned]).unwrap();
+        assert_eq!(unassigned.mask(), None);
+
+        let ambisonic = ChannelLayout::ambisonic(3);
+        assert_eq!(ambisonic.n_channels(), 16);
+        assert_eq!(ambisonic.positions(), None);
+        assert_eq!(ambisonic.mask(), None);
+    }
+}
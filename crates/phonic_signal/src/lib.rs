@@ -1,11 +1,13 @@
 mod error;
 mod ext;
+mod layout;
 mod sample;
 mod signal;
 mod spec;
 
 pub use error::*;
 pub use ext::*;
+pub use layout::*;
 pub use sample::*;
 pub use signal::*;
 pub use spec::*;
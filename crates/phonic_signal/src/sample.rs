@@ -1,4 +1,7 @@
-use std::fmt::Debug;
+use std::{
+    error::Error,
+    fmt::{Debug, Display},
+};
 
 pub trait Sample: 'static + Sized + Send + Sync + Copy + Debug {
     const ORIGIN: Self;
@@ -40,3 +43,177 @@ impl_uint_sample!(u64);
 
 impl_float_sample!(f32);
 impl_float_sample!(f64);
+
+/// A packed 24 bit signed integer sample stored as 3 bytes in native byte order
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct I24([u8; 3]);
+
+/// A packed 24 bit unsigned integer sample stored as 3 bytes in native byte order
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct U24([u8; 3]);
+
+impl I24 {
+    pub const BITS: u32 = 24;
+    pub const MIN: Self = Self::new(-(1 << 23));
+    pub const MAX: Self = Self::new((1 << 23) - 1);
+
+    /// Creates a sample from the lower 24 bits of `value`
+    pub const fn new(value: i32) -> Self {
+        let [b0, b1, b2, b3] = value.to_ne_bytes();
+        if cfg!(target_endian = "little") {
+            Self([b0, b1, b2])
+        } else {
+            Self([b1, b2, b3])
+        }
+    }
+
+    pub const fn get(self) -> i32 {
+        let [b0, b1, b2] = self.0;
+        let bytes = if cfg!(target_endian = "little") {
+            [0, b0, b1, b2]
+        } else {
+            [b0, b1, b2, 0]
+        };
+
+        // the arithmetic shift sign extends the upper byte
+        i32::from_ne_bytes(bytes) >> 8
+    }
+
+    pub const fn from_ne_bytes(bytes: [u8; 3]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn to_ne_bytes(self) -> [u8; 3] {
+        self.0
+    }
+}
+
+impl U24 {
+    pub const BITS: u32 = 24;
+    pub const MIN: Self = Self::new(0);
+    pub const MAX: Self = Self::new((1 << 24) - 1);
+
+    /// Creates a sample from the lower 24 bits of `value`
+    pub const fn new(value: u32) -> Self {
+        let [b0, b1, b2, b3] = value.to_ne_bytes();
+        if cfg!(target_endian = "little") {
+            Self([b0, b1, b2])
+        } else {
+            Self([b1, b2, b3])
+        }
+    }
+
+    pub const fn get(self) -> u32 {
+        let [b0, b1, b2] = self.0;
+        let bytes = if cfg!(target_endian = "little") {
+            [b0, b1, b2, 0]
+        } else {
+            [0, b0, b1, b2]
+        };
+
+        u32::from_ne_bytes(bytes)
+    }
+
+    pub const fn from_ne_bytes(bytes: [u8; 3]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn to_ne_bytes(self) -> [u8; 3] {
+        self.0
+    }
+}
+
+impl Sample for I24 {
+    const ORIGIN: Self = Self::new(0);
+}
+
+impl Sample for U24 {
+    const ORIGIN: Self = Self::new(1 << 23);
+}
+
+// the default is the midpoint like the other unsigned samples rather than zero
+impl Default for U24 {
+    fn default() -> Self {
+        Self::ORIGIN
+    }
+}
+
+impl From<I24> for i32 {
+    fn from(value: I24) -> Self {
+        value.get()
+    }
+}
+
+impl From<U24> for u32 {
+    fn from(value: U24) -> Self {
+        value.get()
+    }
+}
+
+impl TryFrom<i32> for I24 {
+    type Error = TryFromIntError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            -0x800000..=0x7FFFFF => Ok(Self::new(value)),
+            _ => Err(TryFromIntError),
+        }
+    }
+}
+
+impl TryFrom<u32> for U24 {
+    type Error = TryFromIntError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0..=0xFFFFFF => Ok(Self::new(value)),
+            _ => Err(TryFromIntError),
+        }
+    }
+}
+
+/// The error returned when an integer is out of the range of a packed sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromIntError;
+
+impl Display for TryFromIntError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("out of range integral type conversion attempted")
+    }
+}
+
+impl Error for TryFromIntError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Sample, I24, U24};
+
+    #[test]
+    fn packed_samples_round_trip() {
+        for value in [-(1 << 23), -1, 0, 1, 0x123456, (1 << 23) - 1] {
+            assert_eq!(I24::new(value).get(), value);
+            assert_eq!(I24::try_from(value).map(i32::from), Ok(value));
+        }
+
+        for value in [0, 1, 0x123456, 1 << 23, (1 << 24) - 1] {
+            assert_eq!(U24::new(value).get(), value);
+            assert_eq!(U24::try_from(value).map(u32::from), Ok(value));
+        }
+
+        assert_eq!(I24::new(1 << 23), I24::MIN);
+        assert!(I24::try_from(1 << 23).is_err());
+        assert!(U24::try_from(1 << 24).is_err());
+        assert_eq!(U24::default(), U24::ORIGIN);
+        assert_eq!(I24::default(), I24::ORIGIN);
+        assert_eq!(I24::new(0x123456).to_ne_bytes(), {
+            let bytes = 0x123456i32.to_le_bytes();
+            if cfg!(target_endian = "little") {
+                [bytes[0], bytes[1], bytes[2]]
+            } else {
+                [bytes[2], bytes[1], bytes[0]]
+            }
+        });
+    }
+}
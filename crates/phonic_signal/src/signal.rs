@@ -24,6 +24,14 @@ delegate_group! {
         fn block(&self);
     }
 
+    pub trait WakingSignal: crate::Signal {
+        /// Registers a waker to be woken once an operation on this signal that returned
+        /// `Err(PhonicError::NotReady)` may succeed. This is the async counterpart to
+        /// `BlockingSignal::block`. Only the most recently registered waker is guaranteed to be
+        /// woken.
+        fn register_waker(&self, waker: &std::task::Waker);
+    }
+
     pub trait IndexedSignal: crate::Signal {
         /// Returns the number of frames between the start and the read/write head of this signal.
         fn pos(&self) -> u64;
@@ -80,6 +88,42 @@ delegate_group! {
         fn consume(&mut self, n_samples: usize);
     }
 
+    #[subgroup(Mut, Read, Planar)]
+    pub trait PlanarSignalReader: crate::Signal {
+        /// Reads samples from this signal into a separate buffer for each channel and returns the
+        /// total number of samples that were read across all channels. The same number of frames
+        /// is read into each channel. A return value of `Ok(0)` indicates this signal is exhausted.
+        ///
+        /// # Implementation
+        /// - `buf.len()` must equal `self.spec().n_channels`, otherwise
+        /// `Err(PhonicError::InvalidInput)` should be returned
+        /// - Failing to initialize `buf[i][..n / n_channels]` (where `n` is the returned value)
+        /// for each channel `i` causes undefined behavior
+        /// - The returned value must be a multiple of `self.spec().n_channels`
+        fn read_planar(
+            &mut self,
+            buf: &mut [&mut [std::mem::MaybeUninit<Self::Sample>]]
+        ) -> crate::PhonicResult<usize>;
+    }
+
+    #[subgroup(Mut, Read, Async)]
+    pub trait AsyncSignalReader: crate::Signal {
+        /// Attempts to read samples from this signal into the given buffer and returns the number
+        /// of interleaved samples that were read. A return value of `Poll::Ready(Ok(0))` indicates
+        /// this signal is exhausted. If no samples are available `Poll::Pending` is returned and
+        /// the waker of `cx` is scheduled to be woken once this signal may be read from again.
+        ///
+        /// # Implementation
+        /// - Failing to initialize `buf[..n]` (where `n` is the returned value) causes undefined
+        /// behavior
+        /// - The returned value must be a multiple of `self.spec().n_channels`
+        fn poll_read(
+            &mut self,
+            cx: &mut std::task::Context<'_>,
+            buf: &mut [std::mem::MaybeUninit<Self::Sample>]
+        ) -> std::task::Poll<crate::PhonicResult<usize>>;
+    }
+
     #[subgroup(Mut, Write)]
     pub trait SignalWriter: crate::Signal {
         /// Writes samples from the given buffer to this signal and returns the number of
@@ -116,6 +160,48 @@ delegate_group! {
         fn commit(&mut self, n_samples: usize);
     }
 
+    #[subgroup(Mut, Write, Planar)]
+    pub trait PlanarSignalWriter: crate::Signal {
+        /// Writes samples from a separate buffer for each channel to this signal and returns the
+        /// total number of samples that were written across all channels. The same number of
+        /// frames is written from each channel. A return value of `Ok(0)` indicates this signal is
+        /// exhausted.
+        ///
+        /// # Implementation
+        /// - `buf.len()` must equal `self.spec().n_channels`, otherwise
+        /// `Err(PhonicError::InvalidInput)` should be returned
+        /// - The returned value must be a multiple of `self.spec().n_channels`
+        fn write_planar(&mut self, buf: &[&[Self::Sample]]) -> crate::PhonicResult<usize>;
+
+        /// Ensures all samples in the signal chain have been written to the innermost
+        /// destination. See `SignalWriter::flush`.
+        fn flush_planar(&mut self) -> crate::PhonicResult<()>;
+    }
+
+    #[subgroup(Mut, Write, Async)]
+    pub trait AsyncSignalWriter: crate::Signal {
+        /// Attempts to write samples from the given buffer to this signal and returns the number
+        /// of interleaved samples that were written. A return value of `Poll::Ready(Ok(0))`
+        /// indicates this signal is exhausted. If no samples can be written `Poll::Pending` is
+        /// returned and the waker of `cx` is scheduled to be woken once this signal may be written
+        /// to again.
+        ///
+        /// # Implementation
+        /// - The returned value must be a multiple of `self.spec().n_channels`
+        fn poll_write(
+            &mut self,
+            cx: &mut std::task::Context<'_>,
+            buf: &[Self::Sample]
+        ) -> std::task::Poll<crate::PhonicResult<usize>>;
+
+        /// Attempts to ensure all samples in the signal chain have been written to the innermost
+        /// destination. See `SignalWriter::flush`.
+        fn poll_flush(
+            &mut self,
+            cx: &mut std::task::Context<'_>
+        ) -> std::task::Poll<crate::PhonicResult<()>>;
+    }
+
     #[subgroup(Mut)]
     pub trait SignalSeeker: crate::Signal {
         /// Moves this signal's read/write head by `n_frames`.
@@ -1,4 +1,4 @@
-use crate::{PhonicError, PhonicResult};
+use crate::{ChannelLayout, PhonicError, PhonicResult};
 
 /// A set of parameters that describes an interleaved pcm signal
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -8,12 +8,16 @@ pub struct SignalSpec {
 
     /// The number of interleaved channels in this signal.
     pub n_channels: usize,
+
+    /// The meaning of each channel, if it is known.
+    pub layout: Option<ChannelLayout>,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct SignalSpecBuilder {
     pub sample_rate: Option<usize>,
     pub n_channels: Option<usize>,
+    pub layout: Option<ChannelLayout>,
 }
 
 impl SignalSpec {
@@ -21,6 +25,7 @@ impl SignalSpec {
         Self {
             sample_rate,
             n_channels,
+            layout: None,
         }
     }
 
@@ -32,6 +37,11 @@ impl SignalSpec {
         Self::new(2, sample_rate)
     }
 
+    pub fn with_layout(mut self, layout: impl Into<Option<ChannelLayout>>) -> Self {
+        self.layout = layout.into();
+        self
+    }
+
     pub fn builder() -> SignalSpecBuilder {
         SignalSpecBuilder::new()
     }
@@ -39,6 +49,27 @@ impl SignalSpec {
     pub fn into_builder(self) -> SignalSpecBuilder {
         self.into()
     }
+
+    /// Returns whether samples can be passed between signals with this spec and `other` as they
+    /// are. Unlike `==`, an unknown layout is compatible with any layout.
+    pub fn is_compatible(&self, other: &Self) -> bool {
+        self.sample_rate == other.sample_rate
+            && self.n_channels == other.n_channels
+            && match (self.layout, other.layout) {
+                (Some(a), Some(b)) => a == b,
+                _ => true,
+            }
+    }
+
+    /// Merges two compatible specs, keeping whichever layout is known. Returns `ParamMismatch` if
+    /// the specs are not compatible.
+    pub fn merged(self, other: &Self) -> PhonicResult<Self> {
+        if !self.is_compatible(other) {
+            return Err(PhonicError::param_mismatch());
+        }
+
+        Ok(self.with_layout(self.layout.or(other.layout)))
+    }
 }
 
 impl SignalSpecBuilder {
@@ -56,8 +87,13 @@ impl SignalSpecBuilder {
         self
     }
 
+    pub fn with_layout(mut self, layout: impl Into<Option<ChannelLayout>>) -> Self {
+        self.layout = layout.into();
+        self
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.sample_rate.is_none() && self.n_channels.is_none()
+        self.sample_rate.is_none() && self.n_channels.is_none() && self.layout.is_none()
     }
 
     pub fn is_full(&self) -> bool {
@@ -77,6 +113,12 @@ impl SignalSpecBuilder {
             }
         }
 
+        if let Some(layout) = other.layout {
+            if self.layout.get_or_insert(layout) != &layout {
+                return Err(PhonicError::param_mismatch());
+            }
+        }
+
         Ok(())
     }
 
@@ -94,9 +136,22 @@ impl TryFrom<SignalSpecBuilder> for SignalSpec {
     type Error = PhonicError;
 
     fn try_from(builder: SignalSpecBuilder) -> Result<Self, Self::Error> {
+        let n_channels = builder
+            .n_channels
+            .or(builder.layout.map(|layout| layout.n_channels()))
+            .ok_or(PhonicError::missing_data())?;
+
+        if builder
+            .layout
+            .is_some_and(|layout| layout.n_channels() != n_channels)
+        {
+            return Err(PhonicError::param_mismatch());
+        }
+
         Ok(Self {
-            n_channels: builder.n_channels.ok_or(PhonicError::missing_data())?,
+            n_channels,
             sample_rate: builder.sample_rate.ok_or(PhonicError::missing_data())?,
+            layout: builder.layout,
         })
     }
 }
@@ -106,6 +161,7 @@ impl From<SignalSpec> for SignalSpecBuilder {
         Self {
             sample_rate: Some(spec.sample_rate),
             n_channels: Some(spec.n_channels),
+            layout: spec.layout,
         }
     }
 }
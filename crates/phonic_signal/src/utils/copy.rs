@@ -1,7 +1,7 @@
 use crate::{
     utils::{IntoDuration, NSamples},
-    BlockingSignal, BufferedSignalWriter, PhonicError, PhonicResult, SignalExt, SignalReader,
-    SignalWriter,
+    AsyncSignalExt, AsyncSignalReader, AsyncSignalWriter, BlockingSignal, BufferedSignalWriter,
+    PhonicError, PhonicResult, SignalExt, SignalReader, SignalWriter,
 };
 use std::mem::MaybeUninit;
 
@@ -16,7 +16,7 @@ where
     W: BlockingSignal + SignalWriter<Sample = R::Sample>,
 {
     let spec = reader.spec();
-    if spec != writer.spec() {
+    if !spec.is_compatible(writer.spec()) {
         return Err(PhonicError::param_mismatch());
     }
 
@@ -49,7 +49,7 @@ where
     W: BlockingSignal + BufferedSignalWriter<Sample = R::Sample>,
 {
     let spec = reader.spec();
-    if spec != writer.spec() {
+    if !spec.is_compatible(writer.spec()) {
         return Err(PhonicError::param_mismatch());
     }
 
@@ -91,7 +91,7 @@ where
     R: BlockingSignal + SignalReader,
     W: BlockingSignal + SignalWriter<Sample = R::Sample>,
 {
-    if reader.spec() != writer.spec() {
+    if !reader.spec().is_compatible(writer.spec()) {
         return Err(PhonicError::param_mismatch());
     }
 
@@ -113,13 +113,44 @@ where
     Ok(())
 }
 
+pub async fn copy_all_async<R, W>(
+    mut reader: R,
+    mut writer: W,
+    buf: &mut [MaybeUninit<R::Sample>],
+) -> PhonicResult<()>
+where
+    R: AsyncSignalReader,
+    W: AsyncSignalWriter<Sample = R::Sample>,
+{
+    if !reader.spec().is_compatible(writer.spec()) {
+        return Err(PhonicError::param_mismatch());
+    }
+
+    loop {
+        let samples = match reader.read_init_async(buf).await {
+            Err(PhonicError::Interrupted { .. }) => continue,
+            Err(e) => return Err(e),
+            Ok([]) => break,
+            Ok(samples) => samples,
+        };
+
+        match writer.write_exact_async(samples).await {
+            Ok(()) => continue,
+            Err(PhonicError::OutOfBounds { .. }) => break,
+            Err(e) => return Err(e),
+        };
+    }
+
+    Ok(())
+}
+
 pub fn copy_all_buffered<R, W>(mut reader: R, mut writer: W) -> PhonicResult<()>
 where
     R: BlockingSignal + SignalReader,
     W: BlockingSignal + BufferedSignalWriter<Sample = R::Sample>,
 {
     let spec = reader.spec();
-    if spec != writer.spec() {
+    if !spec.is_compatible(writer.spec()) {
         return Err(PhonicError::param_mismatch());
     }
 
@@ -5,12 +5,13 @@ use crate::{
     },
     BlockingSignal, BufferedSignalReader, BufferedSignalWriter, FiniteSignal, IndexedSignal,
     PhonicError, PhonicResult, Sample, Signal, SignalReader, SignalSeeker, SignalSpec,
-    SignalWriter,
+    SignalWriter, WakingSignal,
 };
 use std::{
     borrow::{Borrow, BorrowMut},
     marker::PhantomData,
     mem::MaybeUninit,
+    task::Waker,
 };
 
 pub struct Cursor<B, S> {
@@ -165,6 +166,15 @@ impl<B, S: Sample> Signal for Cursor<B, MaybeUninit<S>> {
     }
 }
 
+/// Cursors never return `Err(PhonicError::NotReady)`, so the waker is never registered
+impl<B, S: Sample> WakingSignal for Cursor<B, S> {
+    fn register_waker(&self, _waker: &Waker) {}
+}
+
+impl<B, S: Sample> WakingSignal for Cursor<B, MaybeUninit<S>> {
+    fn register_waker(&self, _waker: &Waker) {}
+}
+
 impl<B, S: Sample> IndexedSignal for Cursor<B, S> {
     fn pos(&self) -> u64 {
         self._pos()
@@ -1,8 +1,8 @@
 use crate::{
     utils::{
-        copy_all, copy_all_buffered, copy_exact, copy_exact_buffered, BufReader, BufWriter, Cursor,
-        DynamicBuf, Indexed, IntoDuration, NFrames, NSamples, Observer, Poll, ResizeBuf,
-        SignalEvent, SizedBuf,
+        copy_all, copy_all_buffered, copy_exact, copy_exact_buffered, AsyncPoll, BufReader,
+        BufWriter, Cursor, DefaultSizedBuf, DynamicBuf, Indexed, Interleaved, IntoDuration,
+        NFrames, NSamples, Observer, Planar, Poll, ResizeBuf, SignalEvent, SizedBuf, Tee,
     },
     BlockingSignal, BufferedSignalWriter, FiniteSignal, IndexedSignal, PhonicError, PhonicResult,
     Signal, SignalExt, SignalReader, SignalSeeker, SignalWriter,
@@ -234,9 +234,33 @@ pub trait SignalUtilsExt: Sized + Signal {
         Observer::on_seek(self, callback)
     }
 
+    fn planar(self) -> Planar<Self> {
+        Planar::new(self, DefaultSizedBuf::uninit())
+    }
+
+    fn planar_buf<B>(self, buf: B) -> Planar<Self, B> {
+        Planar::new(self, buf)
+    }
+
+    fn interleaved(self) -> Interleaved<Self> {
+        Interleaved::new(self, DefaultSizedBuf::uninit())
+    }
+
+    fn interleaved_buf<B>(self, buf: B) -> Interleaved<Self, B> {
+        Interleaved::new(self, buf)
+    }
+
+    fn tee(self, n_readers: usize, capacity: usize) -> PhonicResult<Vec<Tee<Self>>> {
+        Tee::new(self, n_readers, capacity)
+    }
+
     fn polled(self) -> Poll<Self> {
         Poll(self)
     }
+
+    fn async_polled(self) -> AsyncPoll<Self> {
+        AsyncPoll(self)
+    }
 }
 
 impl<T: Sized + Signal> SignalUtilsExt for T {}
@@ -1,8 +1,8 @@
 use crate::{
     delegate_signal,
     utils::{IntoDuration, NFrames, NSamples},
-    BufferedSignalReader, BufferedSignalWriter, IndexedSignal, PhonicError, PhonicResult, Signal,
-    SignalReader, SignalSeeker, SignalWriter,
+    BufferedSignalReader, BufferedSignalWriter, IndexedSignal, PhonicError, PhonicResult,
+    PlanarSignalReader, PlanarSignalWriter, Signal, SignalReader, SignalSeeker, SignalWriter,
 };
 use std::mem::MaybeUninit;
 
@@ -63,6 +63,15 @@ impl<T: BufferedSignalReader> BufferedSignalReader for Indexed<T> {
     }
 }
 
+impl<T: PlanarSignalReader> PlanarSignalReader for Indexed<T> {
+    fn read_planar(&mut self, buf: &mut [&mut [MaybeUninit<Self::Sample>]]) -> PhonicResult<usize> {
+        let n_samples = self.inner.read_planar(buf)?;
+        self.advance(n_samples);
+
+        Ok(n_samples)
+    }
+}
+
 impl<T: SignalWriter> SignalWriter for Indexed<T> {
     fn write(&mut self, buf: &[Self::Sample]) -> PhonicResult<usize> {
         let n_samples = self.inner.write(buf)?;
@@ -76,6 +85,19 @@ impl<T: SignalWriter> SignalWriter for Indexed<T> {
     }
 }
 
+impl<T: PlanarSignalWriter> PlanarSignalWriter for Indexed<T> {
+    fn write_planar(&mut self, buf: &[&[Self::Sample]]) -> PhonicResult<usize> {
+        let n_samples = self.inner.write_planar(buf)?;
+        self.advance(n_samples);
+
+        Ok(n_samples)
+    }
+
+    fn flush_planar(&mut self) -> PhonicResult<()> {
+        self.inner.flush_planar()
+    }
+}
+
 impl<T: BufferedSignalWriter> BufferedSignalWriter for Indexed<T> {
     fn buffer_mut(&mut self) -> Option<&mut [MaybeUninit<Self::Sample>]> {
         self.inner.buffer_mut()
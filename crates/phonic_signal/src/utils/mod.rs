@@ -8,7 +8,9 @@ mod indexed;
 mod iter;
 mod null;
 mod observe;
+mod planar;
 mod poll;
+mod tee;
 mod uninit;
 
 pub use buf::*;
@@ -21,5 +23,7 @@ pub use indexed::*;
 pub use iter::*;
 pub use null::*;
 pub use observe::*;
+pub use planar::*;
 pub use poll::*;
+pub use tee::*;
 pub use uninit::*;
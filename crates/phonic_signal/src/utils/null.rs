@@ -1,5 +1,8 @@
-use crate::{PhonicResult, Sample, Signal, SignalReader, SignalSpec, SignalWriter};
-use std::{marker::PhantomData, mem::MaybeUninit};
+use crate::{
+    PhonicResult, Sample, Signal, SignalReader, SignalSeeker, SignalSpec, SignalWriter,
+    WakingSignal,
+};
+use std::{marker::PhantomData, mem::MaybeUninit, task::Waker};
 
 pub struct NullSignal<S> {
     spec: SignalSpec,
@@ -23,6 +26,10 @@ impl<S: Sample> Signal for NullSignal<S> {
     }
 }
 
+impl<S: Sample> WakingSignal for NullSignal<S> {
+    fn register_waker(&self, _waker: &Waker) {}
+}
+
 impl<S: Sample> SignalReader for NullSignal<S> {
     fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
         let mut len = buf.len();
@@ -45,3 +52,9 @@ impl<S: Sample> SignalWriter for NullSignal<S> {
         Ok(())
     }
 }
+
+impl<S: Sample> SignalSeeker for NullSignal<S> {
+    fn seek(&mut self, _offset: i64) -> PhonicResult<()> {
+        Ok(())
+    }
+}
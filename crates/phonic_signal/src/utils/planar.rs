@@ -0,0 +1,281 @@
+use crate::{
+    delegate_signal,
+    utils::{slice_as_init, DefaultSizedBuf},
+    PhonicError, PhonicResult, PlanarSignalReader, PlanarSignalWriter, Signal, SignalExt,
+    SignalReader, SignalWriter,
+};
+use std::mem::MaybeUninit;
+
+/// Exposes an interleaved signal as a planar signal. Mono signals are read and written without
+/// copying, all other signals are (de)interleaved through an intermediate buffer.
+pub struct Planar<T: Signal, B = DefaultSizedBuf<MaybeUninit<<T as Signal>::Sample>>> {
+    inner: T,
+    buf: B,
+}
+
+/// The maximum number of channels of the planar signal inside of an `Interleaved`
+pub const MAX_INTERLEAVED_CHANNELS: usize = 64;
+
+/// Exposes a planar signal as an interleaved signal. Mono signals are read and written without
+/// copying, all other signals are (de)interleaved through an intermediate buffer. The planes are
+/// passed to the inner signal without allocating, so at most `MAX_INTERLEAVED_CHANNELS` channels
+/// are supported.
+pub struct Interleaved<T: Signal, B = DefaultSizedBuf<MaybeUninit<<T as Signal>::Sample>>> {
+    inner: T,
+    buf: B,
+}
+
+/// Copies the interleaved samples of `src` into the start of each channel of `dst`
+pub fn deinterleave<S: Copy>(src: &[S], dst: &mut [&mut [MaybeUninit<S>]]) {
+    let n_channels = dst.len();
+    for (i, frame) in src.chunks_exact(n_channels).enumerate() {
+        for (channel, sample) in dst.iter_mut().zip(frame) {
+            channel[i].write(*sample);
+        }
+    }
+}
+
+/// Interleaves the channels of `src` into the start of `dst`
+pub fn interleave<S: Copy>(src: &[&[S]], dst: &mut [MaybeUninit<S>]) {
+    let n_channels = src.len();
+    for (i, frame) in dst.chunks_exact_mut(n_channels).enumerate() {
+        for (channel, sample) in src.iter().zip(frame) {
+            sample.write(channel[i]);
+        }
+    }
+}
+
+/// Splits the start of `buf` into `N` planes of `n_frames` samples each. Any planes that don't fit
+/// are left empty.
+fn split_planes<S, const N: usize>(buf: &mut [S], n_frames: usize) -> [&mut [S]; N] {
+    let mut chunks = buf.chunks_exact_mut(n_frames);
+    std::array::from_fn(|_| chunks.next().unwrap_or_default())
+}
+
+/// Returns the number of frames that fit in every channel of a planar buffer
+fn planar_frames<B: AsRef<[S]>, S>(buf: &[B], n_channels: usize) -> PhonicResult<usize> {
+    if buf.len() != n_channels {
+        return Err(PhonicError::invalid_input());
+    }
+
+    Ok(buf.iter().map(|ch| ch.as_ref().len()).min().unwrap_or(0))
+}
+
+impl<T: Signal, B> Planar<T, B> {
+    pub fn new(inner: T, buf: B) -> Self {
+        Self { inner, buf }
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Signal, B> Interleaved<T, B> {
+    pub fn new(inner: T, buf: B) -> Self {
+        Self { inner, buf }
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+delegate_signal! {
+    impl<T: Signal, B> * + !Planar for Planar<T, B> {
+        Self as T;
+
+        &self => &self.inner;
+        &mut self => &mut self.inner;
+    }
+}
+
+delegate_signal! {
+    impl<T: Signal, B> * + !Read + !Write for Interleaved<T, B> {
+        Self as T;
+
+        &self => &self.inner;
+        &mut self => &mut self.inner;
+    }
+}
+
+impl<T, B> PlanarSignalReader for Planar<T, B>
+where
+    T: SignalReader,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn read_planar(&mut self, buf: &mut [&mut [MaybeUninit<Self::Sample>]]) -> PhonicResult<usize> {
+        let n_channels = self.inner.spec().n_channels;
+        let n_frames = planar_frames(buf, n_channels)?;
+        if let [channel] = buf {
+            return self.inner.read(channel);
+        }
+
+        let inner_buf = self.buf.as_mut();
+        let n_frames = n_frames.min(inner_buf.len() / n_channels);
+        if n_frames == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let samples = self
+            .inner
+            .read_init(&mut inner_buf[..n_frames * n_channels])?;
+        deinterleave(samples, buf);
+
+        Ok(samples.len())
+    }
+}
+
+impl<T, B> PlanarSignalWriter for Planar<T, B>
+where
+    T: SignalWriter,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn write_planar(&mut self, buf: &[&[Self::Sample]]) -> PhonicResult<usize> {
+        let n_channels = self.inner.spec().n_channels;
+        let n_frames = planar_frames(buf, n_channels)?;
+        if let [channel] = buf {
+            return self.inner.write(channel);
+        }
+
+        let inner_buf = self.buf.as_mut();
+        let n_frames = n_frames.min(inner_buf.len() / n_channels);
+        if n_frames == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let uninit_slice = &mut inner_buf[..n_frames * n_channels];
+        interleave(buf, uninit_slice);
+
+        let init_slice = unsafe { slice_as_init(uninit_slice) };
+        self.inner.write(init_slice)
+    }
+
+    fn flush_planar(&mut self) -> PhonicResult<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T, B> SignalReader for Interleaved<T, B>
+where
+    T: PlanarSignalReader,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let n_channels = self.inner.spec().n_channels;
+        if n_channels == 1 {
+            return self.inner.read_planar(&mut [buf]);
+        }
+
+        let inner_buf = self.buf.as_mut();
+        let n_frames = (buf.len() / n_channels).min(inner_buf.len() / n_channels);
+        if n_frames == 0 || n_channels > MAX_INTERLEAVED_CHANNELS {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let mut planes: [_; MAX_INTERLEAVED_CHANNELS] =
+            split_planes(&mut inner_buf[..n_frames * n_channels], n_frames);
+        let n_samples = self.inner.read_planar(&mut planes[..n_channels])?;
+        let n_read = n_samples / n_channels;
+        let planes: [_; MAX_INTERLEAVED_CHANNELS] = std::array::from_fn(|i| unsafe {
+            slice_as_init(&planes[i][..n_read.min(planes[i].len())])
+        });
+
+        interleave(&planes[..n_channels], &mut buf[..n_samples]);
+        Ok(n_samples)
+    }
+}
+
+impl<T, B> SignalWriter for Interleaved<T, B>
+where
+    T: PlanarSignalWriter,
+    B: AsMut<[MaybeUninit<T::Sample>]>,
+{
+    fn write(&mut self, buf: &[Self::Sample]) -> PhonicResult<usize> {
+        let n_channels = self.inner.spec().n_channels;
+        if n_channels == 1 {
+            return self.inner.write_planar(&[buf]);
+        }
+
+        let inner_buf = self.buf.as_mut();
+        let n_frames = (buf.len() / n_channels).min(inner_buf.len() / n_channels);
+        if n_frames == 0 || n_channels > MAX_INTERLEAVED_CHANNELS {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let mut planes: [_; MAX_INTERLEAVED_CHANNELS] =
+            split_planes(&mut inner_buf[..n_frames * n_channels], n_frames);
+        deinterleave(&buf[..n_frames * n_channels], &mut planes[..n_channels]);
+        let planes: [_; MAX_INTERLEAVED_CHANNELS] =
+            std::array::from_fn(|i| unsafe { slice_as_init(&*planes[i]) });
+
+        self.inner.write_planar(&planes[..n_channels])
+    }
+
+    fn flush(&mut self) -> PhonicResult<()> {
+        self.inner.flush_planar()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        utils::{Cursor, SignalUtilsExt},
+        PlanarSignalReader, PlanarSignalWriter, SignalExt, SignalSpec, SignalWriter,
+    };
+    use std::mem::MaybeUninit;
+
+    #[test]
+    fn planar_round_trip() {
+        let samples = [1, -1, 2, -2, 3, -3];
+        let spec = SignalSpec::stereo(48000);
+        let mut planar = Cursor::new(spec, &samples[..]).planar();
+
+        let mut left = [MaybeUninit::uninit(); 3];
+        let mut right = [MaybeUninit::uninit(); 3];
+        let n_samples = planar
+            .read_planar(&mut [&mut left[..], &mut right[..]])
+            .unwrap();
+
+        assert_eq!(n_samples, 6);
+        let left = left.map(|s| unsafe { s.assume_init() });
+        let right = right.map(|s| unsafe { s.assume_init() });
+        assert_eq!((left, right), ([1, 2, 3], [-1, -2, -3]));
+
+        let mut interleaved = Cursor::new(spec, &samples[..]).planar().interleaved();
+        let mut buf = [MaybeUninit::uninit(); 6];
+        assert_eq!(interleaved.read_init(&mut buf).unwrap(), samples);
+
+        let mut output = [0; 6];
+        let mut writer = Cursor::new(spec, &mut output[..]).planar().interleaved();
+        assert_eq!(writer.write(&samples).unwrap(), 6);
+
+        assert_eq!(output, samples);
+    }
+
+    #[test]
+    fn planar_buffers_must_match_the_channel_count() {
+        let spec = SignalSpec::stereo(48000);
+        let samples = [0.0; 4];
+        let mut planar = Cursor::new(spec, &samples[..]).planar();
+
+        let mut mono = [MaybeUninit::uninit(); 4];
+        assert!(planar.read_planar(&mut [&mut mono[..]]).is_err());
+
+        let mut output = [0.0; 4];
+        let mut writer = Cursor::new(spec, &mut output[..]).planar();
+        let n_samples = writer.write_planar(&[&[1.0, 2.0], &[-1.0, -2.0]]).unwrap();
+
+        assert_eq!(n_samples, 4);
+
+        assert_eq!(output, [1.0, -1.0, 2.0, -2.0]);
+    }
+}
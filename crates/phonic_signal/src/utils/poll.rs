@@ -1,9 +1,22 @@
-use crate::{delegate_signal, BlockingSignal, Signal};
-use std::time::Duration;
+use crate::{
+    delegate_signal, AsyncSignalReader, AsyncSignalWriter, BlockingSignal, PhonicResult, Signal,
+    SignalReader, SignalWriter, WakingSignal,
+};
+use std::{
+    mem::MaybeUninit,
+    task::{self, Context},
+    time::Duration,
+};
 
 #[repr(transparent)]
 pub struct Poll<T>(pub T);
 
+/// Exposes a signal that returns `Err(PhonicError::NotReady)` as an async signal. The task is
+/// registered with `WakingSignal::register_waker` and is only polled again once the signal wakes
+/// it.
+#[repr(transparent)]
+pub struct AsyncPoll<T>(pub T);
+
 #[macro_export]
 macro_rules! block_on_signal {
     ($self:expr, $func:expr, $result:pat => $return:expr) => {
@@ -20,6 +33,31 @@ macro_rules! block_on_signal {
     }
 }
 
+/// Attempts the operation, registering the waker of `$cx` with `$register` if it returns
+/// `Err(PhonicError::NotReady)`. The operation is attempted once more after registering so that
+/// progress made in between is not missed.
+#[macro_export]
+macro_rules! poll_on_signal {
+    ($cx:expr, $register:path, $source:expr, $func:expr) => {{
+        let mut is_registered = false;
+        loop {
+            match $func {
+                ::std::result::Result::Err($crate::PhonicError::Interrupted { .. }) => continue,
+                ::std::result::Result::Err($crate::PhonicError::NotReady { .. })
+                    if is_registered =>
+                {
+                    break ::std::task::Poll::Pending;
+                }
+                ::std::result::Result::Err($crate::PhonicError::NotReady { .. }) => {
+                    $register($source, ::std::task::Context::waker($cx));
+                    is_registered = true;
+                }
+                result => break ::std::task::Poll::Ready(result),
+            }
+        }
+    }};
+}
+
 delegate_signal! {
     impl<T> * + !BlockingSignal for Poll<T> {
         Self as T;
@@ -44,3 +82,152 @@ impl<T: Signal> BlockingSignal for Poll<T> {
         Self::interval()
     }
 }
+
+delegate_signal! {
+    impl<T> * + !Async for AsyncPoll<T> {
+        Self as T;
+
+        &self => &self.0;
+        &mut self => &mut self.0;
+    }
+}
+
+impl<T: SignalReader + WakingSignal> AsyncSignalReader for AsyncPoll<T> {
+    fn poll_read(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut [MaybeUninit<Self::Sample>],
+    ) -> task::Poll<PhonicResult<usize>> {
+        poll_on_signal!(cx, WakingSignal::register_waker, &self.0, self.0.read(buf))
+    }
+}
+
+impl<T: SignalWriter + WakingSignal> AsyncSignalWriter for AsyncPoll<T> {
+    fn poll_write(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &[Self::Sample],
+    ) -> task::Poll<PhonicResult<usize>> {
+        poll_on_signal!(cx, WakingSignal::register_waker, &self.0, self.0.write(buf))
+    }
+
+    fn poll_flush(&mut self, cx: &mut Context<'_>) -> task::Poll<PhonicResult<()>> {
+        poll_on_signal!(cx, WakingSignal::register_waker, &self.0, self.0.flush())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        utils::{copy_all_async, Cursor, SignalUtilsExt},
+        PhonicError, PhonicResult, Signal, SignalReader, SignalSpec, SignalWriter, WakingSignal,
+    };
+    use std::{
+        cell::{Cell, RefCell},
+        future::Future,
+        mem::MaybeUninit,
+        pin::pin,
+        rc::Rc,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{self, Context, Wake, Waker},
+    };
+
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns `NotReady` until `is_ready` is set
+    struct Delayed<T> {
+        inner: T,
+        is_ready: Rc<Cell<bool>>,
+        waker: Rc<RefCell<Option<Waker>>>,
+    }
+
+    impl<T: Signal> Signal for Delayed<T> {
+        type Sample = T::Sample;
+
+        fn spec(&self) -> &SignalSpec {
+            self.inner.spec()
+        }
+    }
+
+    impl<T: Signal> WakingSignal for Delayed<T> {
+        fn register_waker(&self, waker: &Waker) {
+            *self.waker.borrow_mut() = Some(waker.clone());
+        }
+    }
+
+    impl<T: SignalReader> SignalReader for Delayed<T> {
+        fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+            match self.is_ready.get() {
+                true => self.inner.read(buf),
+                false => Err(PhonicError::not_ready()),
+            }
+        }
+    }
+
+    impl<T: SignalWriter> SignalWriter for Delayed<T> {
+        fn write(&mut self, buf: &[Self::Sample]) -> PhonicResult<usize> {
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> PhonicResult<()> {
+            self.inner.flush()
+        }
+    }
+
+    #[test]
+    fn polled_signals_wait_for_their_waker() {
+        let spec = SignalSpec::stereo(48000);
+        let samples = [1, 2, 3, 4, 5, 6];
+        let mut output = [0; 6];
+
+        let is_ready = Rc::new(Cell::new(false));
+        let waker = Rc::new(RefCell::new(None));
+        let reader = Delayed {
+            inner: Cursor::new(spec, &samples[..]),
+            is_ready: is_ready.clone(),
+            waker: waker.clone(),
+        };
+
+        let writer = Delayed {
+            inner: Cursor::new(spec, &mut output[..]),
+            is_ready: Rc::default(),
+            waker: Rc::default(),
+        };
+
+        let counter = Arc::new(CountingWaker::default());
+        let task_waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&task_waker);
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        let result = {
+            let mut future = pin!(copy_all_async(
+                reader.async_polled(),
+                writer.async_polled(),
+                &mut buf
+            ));
+
+            // the task is not woken until the signal is ready, so the executor doesn't spin
+            assert!(future.as_mut().poll(&mut cx).is_pending());
+            assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+
+            is_ready.set(true);
+            waker.take().unwrap().wake();
+            assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+
+            future.as_mut().poll(&mut cx)
+        };
+
+        assert!(matches!(result, task::Poll::Ready(Ok(()))));
+        assert_eq!(output, samples);
+    }
+}
@@ -0,0 +1,377 @@
+use crate::{
+    utils::DEFAULT_BUF_LEN, BlockingSignal, FiniteSignal, IndexedSignal, PhonicError, PhonicResult,
+    Signal, SignalExt, SignalReader, SignalSpec,
+};
+use std::{cell::RefCell, collections::VecDeque, mem::MaybeUninit, rc::Rc};
+
+/// One of several readers that each receive every sample of a shared signal.
+///
+/// Frames are buffered until every live reader has consumed them. When a reader would need to
+/// read past the capacity of the buffer because another reader has fallen behind,
+/// `Err(PhonicError::NotReady)` is returned until the other reader catches up.
+pub struct Tee<T: Signal> {
+    shared: Rc<RefCell<TeeBuf<T>>>,
+    id: usize,
+    spec: SignalSpec,
+}
+
+/// The state shared between the readers of a tee. Each reader is identified by an index in the
+/// range `0..n_readers`.
+pub struct TeeBuf<T: Signal> {
+    inner: T,
+    cursors: Box<[Option<u64>]>,
+    buf: VecDeque<T::Sample>,
+    scratch: Box<[MaybeUninit<T::Sample>]>,
+    capacity: usize,
+    start: u64,
+    exhausted: bool,
+
+    /// an error from the shared signal, which is returned to every reader once it has read the
+    /// frames that were buffered before the error. The shared signal is not read again until
+    /// every live reader has received it.
+    error: Option<PhonicError>,
+    error_pending: Box<[bool]>,
+}
+
+impl<T: Signal> Tee<T> {
+    /// Creates `n_readers` readers of the signal. Up to `capacity` frames are buffered for
+    /// readers that fall behind.
+    pub fn new(inner: T, n_readers: usize, capacity: usize) -> PhonicResult<Vec<Self>> {
+        let spec = *inner.spec();
+        let shared = Rc::new(RefCell::new(TeeBuf::new(inner, n_readers, capacity)?));
+        let readers = (0..n_readers)
+            .map(|id| Self {
+                shared: shared.clone(),
+                id,
+                spec,
+            })
+            .collect();
+
+        Ok(readers)
+    }
+
+    /// Creates two readers of the signal
+    pub fn pair(inner: T) -> (Self, Self) {
+        let mut readers = Self::new(inner, 2, DEFAULT_BUF_LEN).unwrap();
+        let b = readers.pop().unwrap();
+        let a = readers.pop().unwrap();
+
+        (a, b)
+    }
+
+    /// The number of frames this reader is behind the reader that is furthest ahead
+    pub fn lag(&self) -> u64 {
+        self.shared.borrow().lag(self.id)
+    }
+}
+
+impl<T: Signal> TeeBuf<T> {
+    pub fn new(inner: T, n_readers: usize, capacity: usize) -> PhonicResult<Self> {
+        if n_readers == 0 || capacity == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let n_channels = inner.spec().n_channels;
+        Ok(Self {
+            inner,
+            cursors: vec![Some(0); n_readers].into(),
+            buf: VecDeque::with_capacity(capacity * n_channels),
+            scratch: Box::new_uninit_slice(capacity * n_channels),
+            capacity,
+            start: 0,
+            exhausted: false,
+            error: None,
+            error_pending: vec![false; n_readers].into(),
+        })
+    }
+
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn n_readers(&self) -> usize {
+        self.cursors.len()
+    }
+
+    /// The number of frames the reader is behind the reader that is furthest ahead
+    pub fn lag(&self, id: usize) -> u64 {
+        self.cursors[id].map_or(0, |cursor| self.end() - cursor)
+    }
+
+    /// The position of the reader in frames, derived from the position of the shared signal
+    pub fn pos(&self, id: usize) -> u64
+    where
+        T: IndexedSignal,
+    {
+        self.inner.pos() - self.lag(id)
+    }
+
+    /// Whether the reader must wait for another reader to catch up before the shared signal can
+    /// be read again
+    pub fn is_waiting_for_readers(&self, id: usize) -> bool {
+        let is_error_pending = self.error.is_some() && !self.error_pending[id];
+        self.lag(id) == 0 && (self.n_buffered() == self.capacity || is_error_pending)
+    }
+
+    /// Stops buffering frames for the reader
+    pub fn release(&mut self, id: usize) {
+        self.cursors[id] = None;
+        self.error_pending[id] = false;
+        if !self.error_pending.contains(&true) {
+            self.error = None;
+        }
+
+        self.trim();
+    }
+
+    fn n_buffered(&self) -> usize {
+        self.buf.len() / self.inner.spec().n_channels
+    }
+
+    fn end(&self) -> u64 {
+        self.start + self.n_buffered() as u64
+    }
+
+    fn trim(&mut self) {
+        let n_channels = self.inner.spec().n_channels;
+        let min_cursor = self.cursors.iter().flatten().min().copied();
+        let new_start = min_cursor.unwrap_or(self.end());
+
+        let n_frames = (new_start - self.start) as usize;
+        self.buf.drain(..n_frames * n_channels);
+        self.start = new_start;
+    }
+
+    /// Reads up to `n_frames` frames of the shared signal into the buffer and returns the number
+    /// of frames that were read. `Ok(0)` is returned once the shared signal is exhausted, or if it
+    /// returned an error, which is kept until every reader has received it. While the buffer is
+    /// full or an error is kept `Err(PhonicError::NotReady)` is returned.
+    pub fn fill(&mut self, n_frames: usize) -> PhonicResult<usize>
+    where
+        T: SignalReader,
+    {
+        let n_free = self.capacity - self.n_buffered();
+        if self.exhausted {
+            return Ok(0);
+        } else if n_free == 0 || self.error.is_some() {
+            return Err(PhonicError::not_ready());
+        }
+
+        let n_channels = self.inner.spec().n_channels;
+        let n_samples = n_free.min(n_frames) * n_channels;
+        match self.inner.read_init(&mut self.scratch[..n_samples]) {
+            Ok([]) => self.exhausted = true,
+            Ok(samples) => {
+                self.buf.extend(samples.iter());
+                return Ok(samples.len() / n_channels);
+            }
+            Err(e @ (PhonicError::NotReady { .. } | PhonicError::Interrupted { .. })) => {
+                return Err(e)
+            }
+            Err(e) => {
+                self.error = Some(e);
+                for (pending, cursor) in self.error_pending.iter_mut().zip(&self.cursors) {
+                    *pending = cursor.is_some();
+                }
+            }
+        }
+
+        Ok(0)
+    }
+
+    /// Takes the kept error if the reader has read every frame buffered before it
+    fn take_error(&mut self, id: usize) -> Option<PhonicError> {
+        if self.lag(id) != 0 || !self.error_pending[id] {
+            return None;
+        }
+
+        self.error_pending[id] = false;
+        match self.error_pending.contains(&true) {
+            true => self.error.as_ref().map(PhonicError::duplicate),
+            false => self.error.take(),
+        }
+    }
+
+    /// Reads samples for the reader. See `SignalReader::read`.
+    pub fn read(&mut self, id: usize, buf: &mut [MaybeUninit<T::Sample>]) -> PhonicResult<usize>
+    where
+        T: SignalReader,
+    {
+        let n_channels = self.inner.spec().n_channels;
+        let n_frames = buf.len() / n_channels;
+        if n_frames == 0 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let cursor = self.cursors[id].ok_or(PhonicError::invalid_state())?;
+        if let Some(e) = self.take_error(id) {
+            return Err(e);
+        }
+
+        let mut n_available = (self.end() - cursor) as usize;
+        if n_available < n_frames {
+            match self.fill(n_frames - n_available) {
+                Ok(n) => n_available += n,
+                Err(e) if n_available == 0 => return Err(e),
+                Err(_) => (),
+            }
+        }
+
+        if let Some(e) = self.take_error(id) {
+            return Err(e);
+        }
+
+        let n_samples = n_available.min(n_frames) * n_channels;
+        let offset = (cursor - self.start) as usize * n_channels;
+        let samples = self.buf.range(offset..offset + n_samples);
+        buf.iter_mut().zip(samples).for_each(|(outer, inner)| {
+            outer.write(*inner);
+        });
+
+        self.cursors[id] = Some(cursor + (n_samples / n_channels) as u64);
+        self.trim();
+
+        Ok(n_samples)
+    }
+}
+
+impl<T: Signal> Drop for Tee<T> {
+    fn drop(&mut self) {
+        if let Ok(mut shared) = self.shared.try_borrow_mut() {
+            shared.release(self.id);
+        }
+    }
+}
+
+impl<T: Signal> Signal for Tee<T> {
+    type Sample = T::Sample;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+/// Blocks on the shared signal if this reader has read every buffered frame. A reader that is
+/// waiting for another reader to catch up can not block, since the other readers are on the same
+/// thread and can not make progress in the meantime. It returns immediately instead, so a blocking
+/// read of such a reader never completes. Use `phonic_sync::SyncTee` to block readers on
+/// different threads.
+impl<T: BlockingSignal> BlockingSignal for Tee<T> {
+    fn block(&self) {
+        let shared = self.shared.borrow();
+        if shared.lag(self.id) == 0 && !shared.is_waiting_for_readers(self.id) {
+            shared.inner.block()
+        }
+    }
+}
+
+impl<T: IndexedSignal> IndexedSignal for Tee<T> {
+    fn pos(&self) -> u64 {
+        self.shared.borrow().pos(self.id)
+    }
+}
+
+impl<T: FiniteSignal> FiniteSignal for Tee<T> {
+    fn len(&self) -> u64 {
+        self.shared.borrow().inner.len()
+    }
+}
+
+impl<T: SignalReader> SignalReader for Tee<T> {
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        self.shared.borrow_mut().read(self.id, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        utils::{Cursor, SignalUtilsExt},
+        PhonicError, PhonicResult, Signal, SignalExt, SignalReader, SignalSpec,
+    };
+    use std::mem::MaybeUninit;
+
+    /// A mono signal of ones that fails once after `n_samples` samples and then ends
+    struct Failing {
+        spec: SignalSpec,
+        n_samples: usize,
+        failed: bool,
+    }
+
+    impl Signal for Failing {
+        type Sample = i32;
+
+        fn spec(&self) -> &SignalSpec {
+            &self.spec
+        }
+    }
+
+    impl SignalReader for Failing {
+        fn read(&mut self, buf: &mut [MaybeUninit<i32>]) -> PhonicResult<usize> {
+            if self.n_samples == 0 {
+                return match std::mem::replace(&mut self.failed, true) {
+                    false => Err(PhonicError::invalid_state()),
+                    true => Ok(0),
+                };
+            }
+
+            let n = buf.len().min(self.n_samples);
+            buf[..n].fill(MaybeUninit::new(1));
+            self.n_samples -= n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn readers_have_independent_positions() {
+        let samples = [1, 2, 3, 4, 5, 6];
+        let signal = Cursor::new(SignalSpec::stereo(48000), &samples[..]);
+        let mut readers = signal.tee(3, 8).unwrap();
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        assert_eq!(readers[0].read_init(&mut buf).unwrap(), [1, 2, 3, 4]);
+        assert_eq!(readers[1].lag(), 2);
+        assert_eq!(readers[2].lag(), 2);
+
+        assert_eq!(readers[1].read_init(&mut buf).unwrap(), [1, 2, 3, 4]);
+        assert_eq!(readers[1].read_init(&mut buf).unwrap(), [5, 6]);
+        assert_eq!(readers[0].lag(), 1);
+
+        readers.truncate(2);
+        assert_eq!(readers[0].read_init(&mut buf).unwrap(), [5, 6]);
+        assert_eq!(readers[0].read_init(&mut buf).unwrap(), []);
+    }
+
+    #[test]
+    fn errors_are_returned_to_every_reader_after_the_buffered_frames() {
+        let signal = Failing {
+            spec: SignalSpec::mono(48000),
+            n_samples: 2,
+            failed: false,
+        };
+
+        let mut readers = signal.tee(2, 8).unwrap();
+        let mut buf = [MaybeUninit::uninit(); 4];
+        assert_eq!(readers[0].read_init(&mut buf[..2]).unwrap(), [1, 1]);
+
+        assert_eq!(readers[1].read_init(&mut buf).unwrap(), [1, 1]);
+        assert!(matches!(
+            readers[1].read(&mut buf),
+            Err(PhonicError::InvalidState { .. })
+        ));
+
+        // the signal is not read again until every reader has received the error
+        assert!(matches!(
+            readers[1].read(&mut buf),
+            Err(PhonicError::NotReady { .. })
+        ));
+
+        assert!(matches!(
+            readers[0].read(&mut buf),
+            Err(PhonicError::InvalidState { .. })
+        ));
+
+        assert_eq!(readers[0].read_init(&mut buf).unwrap(), []);
+        assert_eq!(readers[1].read_init(&mut buf).unwrap(), []);
+    }
+}
@@ -1 +1,13 @@
 pub mod spsc;
+
+#[cfg(feature = "signal")]
+mod prefetch;
+
+#[cfg(feature = "signal")]
+mod tee;
+
+#[cfg(feature = "signal")]
+pub use prefetch::*;
+
+#[cfg(feature = "signal")]
+pub use tee::*;
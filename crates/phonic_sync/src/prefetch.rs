@@ -0,0 +1,465 @@
+use crate::spsc::{park::Parker, AtomicWaker, SignalConsumer, SignalProducer, SpscSignal};
+use phonic_signal::{
+    utils::{DefaultDynamicBuf, IntoDuration, NSamples, OwnedBuf, Poll},
+    BlockingSignal, BufferedSignalReader, BufferedSignalWriter, PhonicError, PhonicResult, Signal,
+    SignalReader, SignalSeeker, SignalSpec, WakingSignal,
+};
+use std::{
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+type Ring<S> = <DefaultDynamicBuf<S> as OwnedBuf>::Uninit;
+
+/// Reads a signal ahead of the consumer on a worker thread.
+///
+/// The worker keeps a ring buffer of decoded samples filled while the consumer reads from the
+/// other end, so a slow or bursty inner signal (e.g. a decoder reading from disk) doesn't stall
+/// a real-time consumer. Errors from the inner signal are returned to the consumer at the point
+/// in the signal where they occurred, after every sample read before them.
+pub struct Prefetch<T: Signal> {
+    spec: SignalSpec,
+    consumer: SignalConsumer<T::Sample, Ring<T::Sample>>,
+    commands: Sender<Command<T>>,
+    events: Receiver<Event>,
+    shared: Arc<Shared>,
+    parker: Parker,
+
+    /// the next event from the worker that the consumer has not reached yet
+    pending: Option<Event>,
+
+    /// the number of events taken from the channel
+    n_received: u64,
+
+    /// the number of samples taken from the ring since the worker was spawned
+    n_consumed: u64,
+
+    /// the number of seeks that have been sent to the worker but not yet reached by the consumer.
+    /// any samples or events preceding the latest seek are discarded.
+    n_seeking: usize,
+}
+
+enum Command<T> {
+    /// resume reading after an error
+    Resume,
+
+    /// seek relative to the position of the consumer, which is given as the number of samples
+    /// the worker had committed when the consumer was at that position. `None` refers to the
+    /// position of the previous seek.
+    Seek {
+        from: Option<u64>,
+        n_frames: i64,
+        seek: fn(&mut T, i64) -> PhonicResult<()>,
+    },
+}
+
+struct Event {
+    /// the number of samples the worker had committed when the event occurred
+    at: u64,
+    kind: EventKind,
+}
+
+enum EventKind {
+    Seeked(PhonicResult<()>),
+    Error(PhonicError),
+    End,
+}
+
+struct Shared {
+    n_events: AtomicU64,
+    waker: AtomicWaker,
+}
+
+struct Worker<T: Signal> {
+    inner: T,
+    producer: SignalProducer<T::Sample, Ring<T::Sample>>,
+    commands: Receiver<Command<T>>,
+    events: Sender<Event>,
+    shared: Arc<Shared>,
+    n_committed: u64,
+    last_seek: u64,
+    paused: bool,
+}
+
+impl<T: Signal> Prefetch<T> {
+    /// Spawns a worker thread that reads up to `duration` ahead of the consumer. If the inner
+    /// signal is not ready the worker polls it at `Poll::interval`.
+    pub fn new(inner: T, duration: impl IntoDuration<NSamples>) -> PhonicResult<Self>
+    where
+        T: SignalReader + Send + 'static,
+    {
+        let spec = *inner.spec();
+        let NSamples { n_samples } = duration.into_duration(&spec);
+        if n_samples < spec.n_channels as u64 {
+            return Err(PhonicError::invalid_input());
+        }
+
+        let (producer, consumer) = SpscSignal::default_duration(spec, NSamples { n_samples });
+        let (command_tx, command_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            n_events: AtomicU64::new(0),
+            waker: AtomicWaker::new(),
+        });
+
+        let worker = Worker {
+            inner,
+            producer,
+            commands: command_rx,
+            events: event_tx,
+            shared: shared.clone(),
+            n_committed: 0,
+            last_seek: 0,
+            paused: false,
+        };
+
+        thread::Builder::new()
+            .name("phonic-prefetch".into())
+            .spawn(move || worker.run())?;
+
+        Ok(Self {
+            spec,
+            consumer,
+            commands: command_tx,
+            events: event_rx,
+            shared,
+            parker: Parker::default(),
+            pending: None,
+            n_received: 0,
+            n_consumed: 0,
+            n_seeking: 0,
+        })
+    }
+
+    fn poll_event(&mut self) -> Option<&Event> {
+        if self.pending.is_none() {
+            self.pending = self.events.try_recv().ok();
+            self.n_received += self.pending.is_some() as u64;
+        }
+
+        self.pending.as_ref()
+    }
+
+    /// Returns true if the next event has been reached by the consumer
+    fn event_reached(&mut self) -> bool {
+        let n_consumed = self.n_consumed;
+        self.poll_event()
+            .is_some_and(|event| event.at == n_consumed)
+    }
+
+    /// The number of samples that can be taken from the ring before the next event is reached
+    fn n_readable(&mut self) -> u64 {
+        let n_consumed = self.n_consumed;
+        self.poll_event()
+            .map_or(u64::MAX, |event| event.at - n_consumed)
+    }
+
+    /// Discards the samples and events that precede the latest seek
+    fn skip_stale(&mut self) -> PhonicResult<()> {
+        while self.n_seeking > 0 {
+            if self.event_reached() {
+                let event = self.pending.take().unwrap();
+                if let EventKind::Seeked(result) = event.kind {
+                    self.n_seeking -= 1;
+                    result?;
+                }
+
+                continue;
+            }
+
+            let n_available = match self.consumer.fill() {
+                Ok([]) => return Err(PhonicError::terminated()),
+                Ok(samples) => samples.len(),
+                Err(PhonicError::NotReady { .. }) => 0,
+                Err(e) => return Err(e),
+            };
+
+            // events are sent before any samples that follow them are committed, so the ring has
+            // to be checked before the channel
+            let n_samples = self.n_readable().min(n_available as u64) as usize;
+            if n_samples == 0 && !self.event_reached() {
+                return Err(PhonicError::not_ready());
+            }
+
+            self.consumer.consume(n_samples);
+            self.n_consumed += n_samples as u64;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Signal> Worker<T> {
+    fn run(mut self)
+    where
+        T: SignalReader,
+    {
+        let n_channels = self.inner.spec().n_channels;
+
+        loop {
+            let command = match self.paused {
+                true => self.commands.recv().map_err(|_| TryRecvError::Disconnected),
+                false => self.commands.try_recv(),
+            };
+
+            match command {
+                Ok(command) => {
+                    self.handle(command);
+                    continue;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => return,
+            }
+
+            let Some(buf) = self.producer.buffer_mut() else {
+                return;
+            };
+
+            if buf.len() < n_channels {
+                self.producer.block();
+                continue;
+            }
+
+            match self.inner.read(buf) {
+                Ok(0) => self.notify(EventKind::End),
+                Ok(n) => {
+                    self.producer.commit(n);
+                    self.n_committed += n as u64;
+                }
+                Err(PhonicError::Interrupted { .. }) => continue,
+                // the inner signal is not required to be blocking, so it is polled instead
+                Err(PhonicError::NotReady { .. }) => Poll::<T>::interval(),
+                Err(e) => self.notify(EventKind::Error(e)),
+            }
+        }
+    }
+
+    fn handle(&mut self, command: Command<T>) {
+        self.paused = false;
+
+        let Command::Seek {
+            from,
+            n_frames,
+            seek,
+        } = command
+        else {
+            return;
+        };
+
+        // the worker is ahead of the consumer by the samples committed since its position
+        let n_channels = self.inner.spec().n_channels as u64;
+        let lead = (self.n_committed - from.unwrap_or(self.last_seek)) / n_channels;
+        let result = seek(&mut self.inner, n_frames - lead as i64);
+
+        self.last_seek = self.n_committed;
+        self.notify(EventKind::Seeked(result));
+    }
+
+    fn notify(&mut self, kind: EventKind) {
+        self.paused = !matches!(kind, EventKind::Seeked(_));
+
+        let event = Event {
+            at: self.n_committed,
+            kind,
+        };
+
+        if self.events.send(event).is_ok() {
+            self.shared.n_events.fetch_add(1, Ordering::Release);
+            self.shared.waker.wake();
+        }
+    }
+}
+
+impl<T: Signal> Signal for Prefetch<T> {
+    type Sample = T::Sample;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+impl<T: Signal> BlockingSignal for Prefetch<T> {
+    fn block(&self) {
+        self.register_waker(&self.parker.waker());
+
+        let n_events = self.shared.n_events.load(Ordering::Acquire);
+        let n_available = self.consumer.buffer().map_or(0, <[_]>::len);
+        if n_events == self.n_received && n_available == 0 && self.consumer.buffer().is_some() {
+            self.parker.park();
+        }
+    }
+}
+
+impl<T: Signal> WakingSignal for Prefetch<T> {
+    fn register_waker(&self, waker: &std::task::Waker) {
+        self.consumer.register_waker(waker);
+        self.shared.waker.register(waker);
+    }
+}
+
+impl<T: Signal> SignalReader for Prefetch<T> {
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let n_channels = self.spec.n_channels;
+        if buf.len() < n_channels {
+            return Err(PhonicError::invalid_input());
+        }
+
+        self.skip_stale()?;
+
+        loop {
+            if self.event_reached() {
+                // the end is left pending so every following read also returns `Ok(0)`
+                if let Some(Event {
+                    kind: EventKind::End,
+                    ..
+                }) = self.pending
+                {
+                    return Ok(0);
+                }
+
+                match self.pending.take().unwrap().kind {
+                    EventKind::End => unreachable!(),
+                    EventKind::Error(e) => {
+                        let _ = self.commands.send(Command::Resume);
+                        return Err(e);
+                    }
+                    EventKind::Seeked(_) => continue,
+                }
+            }
+
+            let len = self.n_readable().min(buf.len() as u64) as usize;
+            let len = len - len % n_channels;
+
+            match self.consumer.read(&mut buf[..len]) {
+                Ok(n) => {
+                    self.n_consumed += n as u64;
+                    return Ok(n);
+                }
+                Err(PhonicError::NotReady { .. })
+                    if self.pending.is_none() && self.poll_event().is_some() =>
+                {
+                    continue
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<T: SignalSeeker> SignalSeeker for Prefetch<T> {
+    fn seek(&mut self, n_frames: i64) -> PhonicResult<()> {
+        let command = Command::Seek {
+            from: (self.n_seeking == 0).then_some(self.n_consumed),
+            n_frames,
+            seek: T::seek,
+        };
+
+        self.commands
+            .send(command)
+            .map_err(|_| PhonicError::terminated())?;
+
+        self.n_seeking += 1;
+
+        // flush the ring so a worker waiting for free slots picks up the seek
+        match self.skip_stale() {
+            Err(PhonicError::NotReady { .. }) => Ok(()),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Prefetch;
+    use phonic_signal::{
+        utils::{Cursor, NFrames, SignalUtilsExt},
+        PhonicError, PhonicResult, Signal, SignalExt, SignalReader, SignalSeeker, SignalSpec,
+    };
+    use std::mem::MaybeUninit;
+
+    /// Yields `[0, 1]`, an error, then `[2, 3]`
+    struct Faulty {
+        spec: SignalSpec,
+        n_reads: usize,
+    }
+
+    impl Signal for Faulty {
+        type Sample = u8;
+
+        fn spec(&self) -> &SignalSpec {
+            &self.spec
+        }
+    }
+
+    impl SignalReader for Faulty {
+        fn read(&mut self, buf: &mut [MaybeUninit<u8>]) -> PhonicResult<usize> {
+            self.n_reads += 1;
+            let samples: &[u8] = match self.n_reads {
+                1 => &[0, 1],
+                2 => return Err(PhonicError::invalid_data()),
+                3 => &[2, 3],
+                _ => &[],
+            };
+
+            for (outer, inner) in buf.iter_mut().zip(samples) {
+                outer.write(*inner);
+            }
+
+            Ok(samples.len())
+        }
+    }
+
+    #[test]
+    fn samples_are_read_ahead_on_a_worker_thread() {
+        let samples = (0..4096).map(|i| i as f32).collect::<Vec<_>>();
+        let signal = Cursor::new(SignalSpec::stereo(48000), samples.clone());
+
+        let mut prefetch = Prefetch::new(signal, NFrames { n_frames: 64 }).unwrap();
+        assert_eq!(prefetch.read_all_into::<Vec<f32>>().unwrap(), samples);
+    }
+
+    #[test]
+    fn seeking_discards_prefetched_samples() {
+        let samples = (0..4096).collect::<Vec<u32>>();
+        let signal = Cursor::new(SignalSpec::mono(48000), samples);
+        let mut prefetch = Prefetch::new(signal, NFrames { n_frames: 128 }).unwrap();
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        assert_eq!(prefetch.read_exact_init(&mut buf).unwrap(), [0, 1, 2, 3]);
+
+        prefetch.seek(100).unwrap();
+        assert_eq!(
+            prefetch.read_exact_init(&mut buf).unwrap(),
+            [104, 105, 106, 107]
+        );
+
+        prefetch.seek(-50).unwrap();
+        prefetch.seek(-50).unwrap();
+        assert_eq!(prefetch.read_exact_init(&mut buf).unwrap(), [8, 9, 10, 11]);
+    }
+
+    #[test]
+    fn errors_are_returned_in_order() {
+        let signal = Faulty {
+            spec: SignalSpec::mono(48000),
+            n_reads: 0,
+        };
+
+        let mut prefetch = Prefetch::new(signal, NFrames { n_frames: 16 }).unwrap();
+        let mut buf = [MaybeUninit::uninit(); 8];
+
+        assert_eq!(prefetch.read_exact_init(&mut buf[..2]).unwrap(), [0, 1]);
+        assert!(matches!(
+            prefetch.read_blocking(&mut buf),
+            Err(PhonicError::InvalidData { .. })
+        ));
+
+        assert_eq!(prefetch.read_exact_init(&mut buf[..2]).unwrap(), [2, 3]);
+        assert_eq!(prefetch.read_blocking(&mut buf).unwrap(), 0);
+    }
+}
@@ -1,9 +1,11 @@
+use crate::spsc::AtomicWaker;
 use std::{
     mem::{needs_drop, MaybeUninit},
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
+    task::Waker,
 };
 
 pub struct SpscBuf<T, B> {
@@ -22,6 +24,15 @@ pub struct SpscBuf<T, B> {
     /// only used to differentiate between an empty and a full buffer when r_idx == w_idx.
     /// if both indices are equal and the last operation was a write the buffer must be full.
     trailing_write: AtomicBool,
+
+    /// set when either the producer or the consumer is dropped
+    closed: AtomicBool,
+
+    /// woken when elements are committed or the producer is dropped
+    consumer_waker: AtomicWaker,
+
+    /// woken when elements are consumed or the consumer is dropped
+    producer_waker: AtomicWaker,
 }
 
 pub struct Producer<T, B> {
@@ -35,6 +46,14 @@ pub struct Consumer<T, B> {
 type SpscPair<T, B> = (Producer<T, B>, Consumer<T, B>);
 
 impl<T, B> SpscBuf<T, B> {
+    /// Creates a producer and consumer pair over `cap` elements starting at `ptr`, keeping `buf`
+    /// alive for as long as either half exists.
+    ///
+    /// # Safety
+    /// The caller must ensure that `ptr` points to at least `cap` elements owned by `buf`, and that
+    /// they stay valid and do not move for as long as `buf` is alive. The returned producer and
+    /// consumer must be the only pair accessing those elements, and no other references to them
+    /// may be used while either half exists.
     pub unsafe fn from_raw_parts(buf: B, ptr: *mut MaybeUninit<T>, cap: usize) -> SpscPair<T, B> {
         let inner = Self {
             _buf: buf,
@@ -44,6 +63,9 @@ impl<T, B> SpscBuf<T, B> {
             w_idx: Default::default(),
             r_idx: Default::default(),
             trailing_write: Default::default(),
+            closed: Default::default(),
+            consumer_waker: Default::default(),
+            producer_waker: Default::default(),
         };
 
         let inner_ref = Arc::new(inner);
@@ -134,12 +156,17 @@ impl<T, B> Producer<T, B> {
         (trailing_slots, leading_slots)
     }
 
+    /// Makes `n` written slots available to the consumer and wakes the consumer's waker on the
+    /// current thread. Foreign `Waker::wake` code, such as an async runtime's scheduler, runs
+    /// inside this call, so wakers should not be registered with a consumer whose producer runs on
+    /// a real-time thread unless waking them is known not to block.
     pub fn commit(&mut self, n: usize) {
         let w_idx = self.buf.w_idx.load(Ordering::Relaxed);
-        let end_idx = w_idx + n % self.buf.cap;
+        let end_idx = (w_idx + n) % self.buf.cap;
 
-        self.buf.w_idx.store(end_idx, Ordering::Relaxed);
+        self.buf.w_idx.store(end_idx, Ordering::Release);
         self.buf.trailing_write.store(true, Ordering::SeqCst);
+        self.buf.consumer_waker.wake();
     }
 
     pub fn is_empty(&self) -> bool {
@@ -157,7 +184,13 @@ impl<T, B> Producer<T, B> {
     }
 
     pub fn is_abandoned(&self) -> bool {
-        Arc::strong_count(&self.buf) < 2
+        self.buf.closed.load(Ordering::Acquire)
+    }
+
+    /// Registers a waker to be woken when slots are freed by the consumer or the consumer is
+    /// dropped
+    pub fn register(&self, waker: &Waker) {
+        self.buf.producer_waker.register(waker)
     }
 }
 
@@ -205,6 +238,8 @@ impl<T, B> Consumer<T, B> {
         (trailing_elements, leading_elements)
     }
 
+    /// Frees `n` elements for reuse by the producer and wakes the producer's waker on the current
+    /// thread. See `Producer::commit` for the implications on real-time threads.
     pub fn consume(&mut self, n: usize) {
         if n == 0 {
             // this check is necessary because drop_elements assumes the buffer is always full when
@@ -213,13 +248,15 @@ impl<T, B> Consumer<T, B> {
         }
 
         let r_idx = self.buf.r_idx.load(Ordering::Relaxed);
-        let end_idx = r_idx + n % self.buf.cap;
+        let end_idx = (r_idx + n) % self.buf.cap;
 
         unsafe { self.buf.drop_elements(r_idx, end_idx) };
 
-        self.buf.r_idx.store(end_idx, Ordering::Relaxed);
+        self.buf.r_idx.store(end_idx, Ordering::Release);
         self.buf.trailing_write.store(false, Ordering::SeqCst);
         // TODO: can trailing write interactions be relaxed to Acquire/Release ordering?
+
+        self.buf.producer_waker.wake();
     }
 
     pub fn is_empty(&self) -> bool {
@@ -237,10 +274,31 @@ impl<T, B> Consumer<T, B> {
     }
 
     pub fn is_abandoned(&self) -> bool {
-        Arc::strong_count(&self.buf) < 2
+        self.buf.closed.load(Ordering::Acquire)
+    }
+
+    /// Registers a waker to be woken when elements are committed by the producer or the
+    /// producer is dropped
+    pub fn register(&self, waker: &Waker) {
+        self.buf.consumer_waker.register(waker)
+    }
+}
+
+impl<T, B> Drop for Producer<T, B> {
+    fn drop(&mut self) {
+        self.buf.closed.store(true, Ordering::Release);
+        self.buf.consumer_waker.wake();
+    }
+}
+
+impl<T, B> Drop for Consumer<T, B> {
+    fn drop(&mut self) {
+        self.buf.closed.store(true, Ordering::Release);
+        self.buf.producer_waker.wake();
     }
 }
 
+unsafe impl<T: Send, B: Send> Send for Producer<T, B> {}
 unsafe impl<T: Send, B: Send> Send for Consumer<T, B> {}
 
 impl<T, B> Drop for SpscBuf<T, B> {
@@ -1,8 +1,20 @@
 mod buf;
+mod waker;
+
 pub use buf::*;
+pub use waker::*;
+
+#[cfg(feature = "signal")]
+pub(crate) mod park;
 
 #[cfg(feature = "signal")]
 mod signal;
 
+#[cfg(feature = "signal")]
+mod stats;
+
 #[cfg(feature = "signal")]
 pub use signal::*;
+
+#[cfg(feature = "signal")]
+pub use stats::*;
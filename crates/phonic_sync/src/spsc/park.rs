@@ -0,0 +1,65 @@
+use std::{
+    cell::RefCell,
+    sync::Arc,
+    task::{Wake, Waker},
+    thread::{self, Thread, ThreadId},
+    time::Duration,
+};
+
+struct ThreadWaker(Thread);
+
+/// Parks the current thread until it is woken through a `Waker` registered with the peer of a
+/// ring buffer.
+///
+/// The waker of the last thread to block is kept alive for the lifetime of the parker, so waking
+/// it from the peer never frees memory. This keeps `commit` and `consume` allocation-free on a
+/// real-time thread.
+#[derive(Default)]
+pub(crate) struct Parker {
+    waker: RefCell<Option<(ThreadId, Waker)>>,
+    timeout: Option<Duration>,
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark()
+    }
+}
+
+impl Parker {
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout
+    }
+
+    /// Returns a waker that unparks the current thread
+    pub fn waker(&self) -> Waker {
+        let id = thread::current().id();
+        let mut cached = self.waker.borrow_mut();
+
+        match &*cached {
+            Some((cached_id, waker)) if *cached_id == id => waker.clone(),
+            _ => {
+                let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+                *cached = Some((id, waker.clone()));
+
+                waker
+            }
+        }
+    }
+
+    /// Parks the current thread until it is woken or the timeout elapses
+    pub fn park(&self) {
+        match self.timeout {
+            Some(timeout) => thread::park_timeout(timeout),
+            None => thread::park(),
+        }
+    }
+}
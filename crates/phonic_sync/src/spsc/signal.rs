@@ -1,29 +1,96 @@
-use crate::spsc::{Consumer, Producer, SpscBuf};
+use crate::spsc::{park::Parker, Consumer, Producer, SpscBuf, SpscStats};
 use phonic_signal::{
+    poll_on_signal,
     utils::{
-        copy_to_uninit_slice, DefaultDynamicBuf, DefaultSizedBuf, DynamicBuf, IntoDuration,
-        NSamples, OwnedBuf, SizedBuf,
+        copy_to_uninit_slice, slice_as_init, DefaultDynamicBuf, DefaultSizedBuf, DynamicBuf,
+        IntoDuration, NSamples, OwnedBuf, SizedBuf,
     },
-    BufferedSignalReader, BufferedSignalWriter, PhonicError, PhonicResult, Sample, Signal,
-    SignalReader, SignalSpec, SignalWriter,
+    AsyncSignalReader, AsyncSignalWriter, BlockingSignal, BufferedSignalReader,
+    BufferedSignalWriter, PhonicError, PhonicResult, Sample, Signal, SignalReader, SignalSpec,
+    SignalWriter, WakingSignal,
+};
+use std::{
+    mem::MaybeUninit,
+    sync::{atomic::Ordering, Arc},
+    task::{Context, Poll, Waker},
+    time::Duration,
 };
-use std::{mem::MaybeUninit, sync::atomic::Ordering};
 
 pub struct SpscSignal;
 
+/// Determines how a `SignalConsumer` handles a read when there are fewer samples in the ring than
+/// were requested. Reads at the end of the signal, after the producer has been dropped, are never
+/// padded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnderrunPolicy {
+    /// Returns the available samples, or `Err(PhonicError::NotReady)` if the ring is empty
+    #[default]
+    Error,
+
+    /// Fills the rest of the buffer with `Sample::ORIGIN`
+    Silence,
+
+    /// Fills the rest of the buffer by repeating the last frame that was read
+    RepeatLast,
+}
+
+/// Determines how a `SignalProducer` handles a write when there are fewer free slots in the ring
+/// than there are samples to write.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OverrunPolicy {
+    /// Writes the samples that fit, or returns `Err(PhonicError::NotReady)` if the ring is full.
+    /// Waiting for the consumer is back-pressure rather than an overrun, so it is never counted.
+    #[default]
+    Error,
+
+    /// Writes the samples that fit and discards the rest, so writes never wait for the consumer
+    Discard,
+}
+
+/// The writing half of an `SpscSignal`. Blocking parks the current thread until the consumer
+/// frees slots in the ring or is dropped.
 pub struct SignalProducer<T, B> {
     spec: SignalSpec,
     producer: Producer<T, B>,
+    parker: Parker,
+    stats: Arc<SpscStats>,
+    overrun_policy: OverrunPolicy,
+
+    /// whether the last write discarded samples, so that consecutive overruns are counted once
+    is_overrun: bool,
+
+    /// whether the last call to return `Err(PhonicError::NotReady)` was a flush, in which case
+    /// blocking waits for the ring to be emptied rather than for a free slot
+    flushing: bool,
 }
 
+/// The reading half of an `SpscSignal`. Blocking parks the current thread until the producer
+/// commits samples to the ring or is dropped.
 pub struct SignalConsumer<T, B> {
     spec: SignalSpec,
     consumer: Consumer<T, B>,
+    parker: Parker,
+    stats: Arc<SpscStats>,
+    underrun_policy: UnderrunPolicy,
+
+    /// whether the last read came up short, so that consecutive underruns are counted once
+    is_starved: bool,
+
+    /// the last frame that was read, which is empty until the first frame is read
+    last_frame: Vec<T>,
 }
 
 type SpscSignalPair<T, B> = (SignalProducer<T, B>, SignalConsumer<T, B>);
 
 impl SpscSignal {
+    /// Creates a producer and consumer pair over `cap` elements starting at `ptr`, keeping `buf`
+    /// alive for as long as either half exists. `cap` is rounded down to a whole number of frames.
+    ///
+    /// # Safety
+    /// The caller must ensure that `ptr` points to at least `cap` elements owned by `buf`, and that
+    /// they stay valid and do not move for as long as `buf` is alive. No other references to those
+    /// elements may be used while the returned pair exists, so the memory must only be shared by
+    /// this single producer and consumer pair.
     pub unsafe fn from_raw_parts<T, B>(
         spec: SignalSpec,
         buf: B,
@@ -32,10 +99,27 @@ impl SpscSignal {
     ) -> SpscSignalPair<T, B> {
         let aligned_cap = cap - cap % spec.n_channels;
         let (producer, consumer) = SpscBuf::from_raw_parts(buf, ptr, aligned_cap);
+        let stats = Arc::new(SpscStats::default());
 
         (
-            SignalProducer { spec, producer },
-            SignalConsumer { spec, consumer },
+            SignalProducer {
+                spec,
+                producer,
+                parker: Parker::default(),
+                stats: stats.clone(),
+                overrun_policy: OverrunPolicy::default(),
+                is_overrun: false,
+                flushing: false,
+            },
+            SignalConsumer {
+                spec,
+                consumer,
+                parker: Parker::default(),
+                stats,
+                underrun_policy: UnderrunPolicy::default(),
+                is_starved: false,
+                last_frame: Vec::with_capacity(spec.n_channels),
+            },
         )
     }
 
@@ -101,6 +185,70 @@ impl SpscSignal {
     }
 }
 
+impl<T, B> SignalProducer<T, B> {
+    /// The longest a call to `BlockingSignal::block` parks the current thread, or `None` if it
+    /// waits until it is woken by the consumer
+    pub fn block_timeout(&self) -> Option<Duration> {
+        self.parker.timeout()
+    }
+
+    pub fn set_block_timeout(&mut self, timeout: Option<Duration>) {
+        self.parker.set_timeout(timeout)
+    }
+
+    /// Returns the counters shared by both halves of the signal
+    pub fn stats(&self) -> Arc<SpscStats> {
+        self.stats.clone()
+    }
+
+    pub fn overrun_policy(&self) -> OverrunPolicy {
+        self.overrun_policy
+    }
+
+    pub fn set_overrun_policy(&mut self, policy: OverrunPolicy) {
+        self.overrun_policy = policy
+    }
+}
+
+impl<T, B> SignalConsumer<T, B> {
+    /// The longest a call to `BlockingSignal::block` parks the current thread, or `None` if it
+    /// waits until it is woken by the producer
+    pub fn block_timeout(&self) -> Option<Duration> {
+        self.parker.timeout()
+    }
+
+    pub fn set_block_timeout(&mut self, timeout: Option<Duration>) {
+        self.parker.set_timeout(timeout)
+    }
+
+    /// Returns the counters shared by both halves of the signal
+    pub fn stats(&self) -> Arc<SpscStats> {
+        self.stats.clone()
+    }
+
+    pub fn underrun_policy(&self) -> UnderrunPolicy {
+        self.underrun_policy
+    }
+
+    pub fn set_underrun_policy(&mut self, policy: UnderrunPolicy) {
+        self.underrun_policy = policy
+    }
+
+    fn pad(&self, buf: &mut [MaybeUninit<T>])
+    where
+        T: Sample,
+    {
+        match (self.underrun_policy, self.last_frame.as_slice()) {
+            (UnderrunPolicy::RepeatLast, frame @ [_, ..]) => {
+                buf.chunks_exact_mut(frame.len()).for_each(|outer| {
+                    copy_to_uninit_slice(frame, outer);
+                })
+            }
+            _ => buf.fill(MaybeUninit::new(T::ORIGIN)),
+        }
+    }
+}
+
 impl<T: Sample, B> Signal for SignalConsumer<T, B> {
     type Sample = T;
 
@@ -109,21 +257,47 @@ impl<T: Sample, B> Signal for SignalConsumer<T, B> {
     }
 }
 
+impl<T: Sample, B> BlockingSignal for SignalConsumer<T, B> {
+    fn block(&self) {
+        self.register_waker(&self.parker.waker());
+        if self.consumer.is_empty() && !self.consumer.is_abandoned() {
+            self.parker.park();
+        }
+    }
+}
+
+/// The waker is woken when the producer commits samples to the ring or is dropped. It is woken on
+/// the producer's thread, which is often a real-time audio thread, so wakers registered here
+/// should be cheap to wake and must not block.
+impl<T: Sample, B> WakingSignal for SignalConsumer<T, B> {
+    fn register_waker(&self, waker: &Waker) {
+        self.consumer.register(waker)
+    }
+}
+
 impl<T: Sample, B> SignalReader for SignalConsumer<T, B> {
     fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        let n_channels = self.spec.n_channels;
+        let buf_len = buf.len() - buf.len() % n_channels;
+
+        // checked before the ring so that every sample committed before the producer was dropped
+        // is read before the end of the signal is reported
+        let is_abandoned = self.consumer.is_abandoned();
+        if is_abandoned {
+            std::sync::atomic::fence(Ordering::Acquire);
+        }
+
         let (trailing, leading) = self.consumer.elements();
         if trailing.is_empty() {
-            if self.consumer.is_abandoned() {
-                std::sync::atomic::fence(Ordering::Acquire);
+            if is_abandoned {
                 return Ok(0);
             }
 
-            return Err(PhonicError::not_ready());
+            if buf_len == 0 {
+                return Err(PhonicError::not_ready());
+            }
         }
 
-        let n_channels = self.spec.n_channels;
-        let buf_len = buf.len() - buf.len() % n_channels;
-
         let trailing_len = trailing.len().min(buf_len);
         debug_assert_eq!(trailing_len % n_channels, 0);
         copy_to_uninit_slice(&trailing[..trailing_len], &mut buf[..trailing_len]);
@@ -137,7 +311,42 @@ impl<T: Sample, B> SignalReader for SignalConsumer<T, B> {
         }
 
         self.consumer.consume(n_samples);
-        Ok(n_samples)
+        self.stats.consumed(n_samples);
+
+        if n_samples > 0 {
+            let frame = unsafe { slice_as_init(&buf[n_samples - n_channels..n_samples]) };
+            self.last_frame.clear();
+            self.last_frame.extend_from_slice(frame);
+        }
+
+        if n_samples == buf_len || is_abandoned {
+            self.is_starved = false;
+            return Ok(n_samples);
+        }
+
+        if !self.is_starved {
+            self.is_starved = true;
+            self.stats.underrun();
+        }
+
+        match self.underrun_policy {
+            UnderrunPolicy::Error if n_samples == 0 => Err(PhonicError::not_ready()),
+            UnderrunPolicy::Error => Ok(n_samples),
+            UnderrunPolicy::Silence | UnderrunPolicy::RepeatLast => {
+                self.pad(&mut buf[n_samples..buf_len]);
+                Ok(buf_len)
+            }
+        }
+    }
+}
+
+impl<T: Sample, B> AsyncSignalReader for SignalConsumer<T, B> {
+    fn poll_read(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut [MaybeUninit<Self::Sample>],
+    ) -> Poll<PhonicResult<usize>> {
+        poll_on_signal!(cx, WakingSignal::register_waker, &*self, self.read(buf))
     }
 }
 
@@ -163,7 +372,16 @@ impl<T: Sample, B> BufferedSignalReader for SignalConsumer<T, B> {
     }
 
     fn consume(&mut self, n_samples: usize) {
-        self.consumer.consume(n_samples)
+        let n_channels = self.spec.n_channels;
+        if n_samples >= n_channels {
+            let (trailing, _) = self.consumer.elements();
+            let frame = &trailing[n_samples - n_channels..n_samples];
+            self.last_frame.clear();
+            self.last_frame.extend_from_slice(frame);
+        }
+
+        self.consumer.consume(n_samples);
+        self.stats.consumed(n_samples);
     }
 }
 
@@ -175,6 +393,29 @@ impl<T: Sample, B> Signal for SignalProducer<T, B> {
     }
 }
 
+impl<T: Sample, B> BlockingSignal for SignalProducer<T, B> {
+    fn block(&self) {
+        self.register_waker(&self.parker.waker());
+        let ready = match self.flushing {
+            true => self.producer.is_empty(),
+            false => !self.producer.is_full(),
+        };
+
+        if !ready && !self.producer.is_abandoned() {
+            self.parker.park();
+        }
+    }
+}
+
+/// The waker is woken when the consumer frees slots in the ring or is dropped. It is woken on the
+/// consumer's thread, which is often a real-time audio thread, so wakers registered here should be
+/// cheap to wake and must not block.
+impl<T: Sample, B> WakingSignal for SignalProducer<T, B> {
+    fn register_waker(&self, waker: &Waker) {
+        self.producer.register(waker)
+    }
+}
+
 impl<T: Sample, B> SignalWriter for SignalProducer<T, B> {
     fn write(&mut self, buf: &[Self::Sample]) -> PhonicResult<usize> {
         if self.producer.is_abandoned() {
@@ -182,14 +423,15 @@ impl<T: Sample, B> SignalWriter for SignalProducer<T, B> {
             return Err(PhonicError::terminated());
         }
 
+        let n_channels = self.spec.n_channels;
+        let buf_len = buf.len() - buf.len() % n_channels;
+
         let (trailing, leading) = self.producer.slots();
-        if trailing.is_empty() {
+        if trailing.is_empty() && self.overrun_policy == OverrunPolicy::Error {
+            self.flushing = false;
             return Err(PhonicError::not_ready());
         }
 
-        let n_channels = self.spec.n_channels;
-        let buf_len = buf.len() - buf.len() % n_channels;
-
         let trailing_len = trailing.len().min(buf_len);
         debug_assert_eq!(trailing_len % n_channels, 0);
         copy_to_uninit_slice(&buf[..trailing_len], &mut trailing[..trailing_len]);
@@ -202,8 +444,21 @@ impl<T: Sample, B> SignalWriter for SignalProducer<T, B> {
             copy_to_uninit_slice(&buf[trailing_len..n_samples], &mut leading[..leading_len]);
         }
 
+        // the stats are updated first so the consumer never sees more samples than are counted
+        self.stats.committed(n_samples);
         self.producer.commit(n_samples);
-        Ok(n_samples)
+
+        if n_samples == buf_len || self.overrun_policy == OverrunPolicy::Error {
+            self.is_overrun = false;
+            return Ok(n_samples);
+        }
+
+        if !self.is_overrun {
+            self.is_overrun = true;
+            self.stats.overrun();
+        }
+
+        Ok(buf_len)
     }
 
     fn flush(&mut self) -> PhonicResult<()> {
@@ -213,11 +468,26 @@ impl<T: Sample, B> SignalWriter for SignalProducer<T, B> {
             std::sync::atomic::fence(Ordering::Acquire);
             Err(PhonicError::terminated())
         } else {
+            self.flushing = true;
             Err(PhonicError::not_ready())
         }
     }
 }
 
+impl<T: Sample, B> AsyncSignalWriter for SignalProducer<T, B> {
+    fn poll_write(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &[Self::Sample],
+    ) -> Poll<PhonicResult<usize>> {
+        poll_on_signal!(cx, WakingSignal::register_waker, &*self, self.write(buf))
+    }
+
+    fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<PhonicResult<()>> {
+        poll_on_signal!(cx, WakingSignal::register_waker, &*self, self.flush())
+    }
+}
+
 impl<T: Sample, B> BufferedSignalWriter for SignalProducer<T, B> {
     fn buffer_mut(&mut self) -> Option<&mut [MaybeUninit<Self::Sample>]> {
         if self.producer.is_abandoned() {
@@ -230,6 +500,170 @@ impl<T: Sample, B> BufferedSignalWriter for SignalProducer<T, B> {
     }
 
     fn commit(&mut self, n_samples: usize) {
+        self.stats.committed(n_samples);
         self.producer.commit(n_samples)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::spsc::{OverrunPolicy, SpscSignal, UnderrunPolicy};
+    use phonic_signal::{
+        utils::SignalUtilsExt, AsyncSignalReader, PhonicError, SignalExt, SignalReader, SignalSpec,
+        SignalWriter,
+    };
+    use std::{
+        mem::MaybeUninit,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Wake, Waker},
+        thread,
+        time::Duration,
+    };
+
+    #[derive(Default)]
+    struct CountingWaker(AtomicUsize);
+
+    impl Wake for CountingWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn consumer_is_woken_by_producer() {
+        let spec = SignalSpec::stereo(48000);
+        let (mut producer, mut consumer) = SpscSignal::new(spec, [0.0f32; 8]);
+
+        let counter = Arc::new(CountingWaker::default());
+        let waker = Waker::from(counter.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut buf = [MaybeUninit::uninit(); 4];
+        assert!(consumer.poll_read(&mut cx, &mut buf).is_pending());
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+
+        producer.write(&[1.0, 2.0]).unwrap();
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            consumer.poll_read(&mut cx, &mut buf),
+            Poll::Ready(Ok(2))
+        ));
+
+        assert!(consumer.poll_read(&mut cx, &mut buf).is_pending());
+        drop(producer);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 2);
+        assert!(matches!(
+            consumer.poll_read(&mut cx, &mut buf),
+            Poll::Ready(Ok(0))
+        ));
+    }
+
+    #[test]
+    fn blocked_threads_are_woken_by_their_peer() {
+        let spec = SignalSpec::mono(48000);
+        let (mut producer, mut consumer) = SpscSignal::new(spec, [0u32; 4]);
+        let samples = (0..1024).collect::<Vec<u32>>();
+
+        let expected = samples.clone();
+        let handle = thread::spawn(move || {
+            producer.write_exact(&samples).unwrap();
+            producer.flush_blocking().unwrap();
+        });
+
+        consumer.set_block_timeout(Some(Duration::from_secs(5)));
+        let received = consumer.read_all_into::<Vec<u32>>().unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn underruns_are_padded_and_counted() {
+        let spec = SignalSpec::stereo(48000);
+        let (mut producer, mut consumer) = SpscSignal::new(spec, [0i16; 8]);
+        let stats = consumer.stats();
+        let mut buf = [MaybeUninit::uninit(); 6];
+
+        assert!(matches!(
+            consumer.read(&mut buf),
+            Err(PhonicError::NotReady { .. })
+        ));
+
+        consumer.set_underrun_policy(UnderrunPolicy::Silence);
+        assert_eq!(consumer.read_init(&mut buf).unwrap(), [0; 6]);
+
+        producer.write(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        assert!(matches!(
+            producer.write(&[9, 10]),
+            Err(PhonicError::NotReady { .. })
+        ));
+
+        assert_eq!(stats.n_buffered(), 8);
+        assert_eq!(consumer.read_init(&mut buf).unwrap(), [1, 2, 3, 4, 5, 6]);
+
+        consumer.set_underrun_policy(UnderrunPolicy::RepeatLast);
+        assert_eq!(consumer.read_init(&mut buf).unwrap(), [7, 8, 7, 8, 7, 8]);
+
+        assert_eq!(stats.n_underruns(), 2);
+        assert_eq!(stats.n_overruns(), 0);
+        assert_eq!(stats.n_buffered(), 0);
+        assert_eq!(stats.high_water_mark(), 8);
+
+        drop(producer);
+        assert_eq!(consumer.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn partial_reads_are_counted_as_underruns() {
+        let spec = SignalSpec::mono(48000);
+        let (mut producer, mut consumer) = SpscSignal::new(spec, vec![0u8; 8]);
+        let stats = consumer.stats();
+        let mut buf = [MaybeUninit::uninit(); 4];
+
+        producer.write(&[1, 2]).unwrap();
+        assert_eq!(consumer.read_init(&mut buf).unwrap(), [1, 2]);
+        assert!(matches!(
+            consumer.read(&mut buf),
+            Err(PhonicError::NotReady { .. })
+        ));
+
+        assert_eq!(stats.n_underruns(), 1);
+
+        producer.write(&[3, 4, 5, 6]).unwrap();
+        assert_eq!(consumer.read_init(&mut buf).unwrap(), [3, 4, 5, 6]);
+        producer.write(&[7]).unwrap();
+        assert_eq!(consumer.read_init(&mut buf).unwrap(), [7]);
+
+        assert_eq!(stats.n_underruns(), 2);
+    }
+
+    #[test]
+    fn back_pressure_is_not_counted_as_an_overrun() {
+        let spec = SignalSpec::mono(48000);
+        let (mut producer, mut consumer) = SpscSignal::new(spec, vec![0u8; 4]);
+        let stats = producer.stats();
+        let mut buf = [MaybeUninit::uninit(); 4];
+
+        assert_eq!(producer.write(&[1, 2, 3, 4, 5, 6]).unwrap(), 4);
+        assert!(matches!(
+            producer.write(&[5, 6]),
+            Err(PhonicError::NotReady { .. })
+        ));
+
+        assert_eq!(stats.n_overruns(), 0);
+
+        producer.set_overrun_policy(OverrunPolicy::Discard);
+        assert_eq!(producer.write(&[5, 6]).unwrap(), 2);
+        assert_eq!(producer.write(&[7, 8]).unwrap(), 2);
+        assert_eq!(consumer.read_init(&mut buf).unwrap(), [1, 2, 3, 4]);
+        assert_eq!(producer.write(&[9, 10]).unwrap(), 2);
+        assert_eq!(producer.write(&[11, 12, 13]).unwrap(), 3);
+        assert_eq!(consumer.read_init(&mut buf).unwrap(), [9, 10, 11, 12]);
+
+        assert_eq!(stats.n_overruns(), 2);
+        assert_eq!(stats.n_underruns(), 0);
+    }
+}
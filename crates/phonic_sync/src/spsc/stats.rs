@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Counters describing the health of an `SpscSignal`. They are updated by the producer and the
+/// consumer without locking and can be read from any thread.
+#[derive(Debug, Default)]
+pub struct SpscStats {
+    n_underruns: AtomicU64,
+    n_overruns: AtomicU64,
+    n_buffered: AtomicUsize,
+    high_water_mark: AtomicUsize,
+}
+
+impl SpscStats {
+    /// The number of times the consumer ran out of samples while the producer was still running,
+    /// either because a read returned fewer samples than were requested or because it was padded
+    /// according to the `UnderrunPolicy`. Consecutive short reads are counted once.
+    pub fn n_underruns(&self) -> u64 {
+        self.n_underruns.load(Ordering::Relaxed)
+    }
+
+    /// The number of times the producer discarded samples because the ring was full under
+    /// `OverrunPolicy::Discard`. Consecutive writes that discard samples are counted once, and
+    /// writes that wait for the consumer under `OverrunPolicy::Error` are never counted.
+    pub fn n_overruns(&self) -> u64 {
+        self.n_overruns.load(Ordering::Relaxed)
+    }
+
+    /// The number of samples currently in the ring
+    pub fn n_buffered(&self) -> usize {
+        self.n_buffered.load(Ordering::Relaxed)
+    }
+
+    /// The largest number of samples that have been in the ring at once
+    pub fn high_water_mark(&self) -> usize {
+        self.high_water_mark.load(Ordering::Relaxed)
+    }
+
+    /// Resets the underrun and overrun counters and the high-water mark
+    pub fn reset(&self) {
+        self.n_underruns.store(0, Ordering::Relaxed);
+        self.n_overruns.store(0, Ordering::Relaxed);
+        self.high_water_mark
+            .store(self.n_buffered(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn underrun(&self) {
+        self.n_underruns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn overrun(&self) {
+        self.n_overruns.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn committed(&self, n_samples: usize) {
+        let n_buffered = self.n_buffered.fetch_add(n_samples, Ordering::Relaxed) + n_samples;
+        self.high_water_mark
+            .fetch_max(n_buffered, Ordering::Relaxed);
+    }
+
+    pub(crate) fn consumed(&self, n_samples: usize) {
+        self.n_buffered.fetch_sub(n_samples, Ordering::Relaxed);
+    }
+}
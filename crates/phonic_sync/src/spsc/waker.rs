@@ -0,0 +1,99 @@
+use std::{
+    cell::UnsafeCell,
+    hint,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Waker,
+};
+
+const WAITING: usize = 0;
+const REGISTERING: usize = 0b01;
+const WAKING: usize = 0b10;
+
+/// A slot for a single waker that can be registered and woken from different threads without
+/// locking.
+///
+/// `register` may only be called by one thread at a time, while `wake` may be called from any
+/// thread. If `wake` races with `register` the newly registered waker is woken by the
+/// registering thread once it has been stored.
+pub struct AtomicWaker {
+    state: AtomicUsize,
+    waker: UnsafeCell<Option<Waker>>,
+}
+
+impl AtomicWaker {
+    pub fn new() -> Self {
+        Self {
+            state: AtomicUsize::new(WAITING),
+            waker: UnsafeCell::new(None),
+        }
+    }
+
+    /// Stores the waker to be woken by the next call to `wake`, replacing the previous waker
+    pub fn register(&self, waker: &Waker) {
+        let state = self
+            .state
+            .compare_exchange(WAITING, REGISTERING, Ordering::Acquire, Ordering::Acquire)
+            .unwrap_or_else(|state| state);
+
+        match state {
+            WAITING => {
+                // SAFETY: the REGISTERING bit gives this thread exclusive access to the slot
+                let prev = unsafe { (*self.waker.get()).replace(waker.clone()) };
+                let result = self.state.compare_exchange(
+                    REGISTERING,
+                    WAITING,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+
+                if result.is_err() {
+                    // a call to wake raced with this registration and left the waker in place
+                    let waker = unsafe { (*self.waker.get()).take() };
+                    self.state.swap(WAITING, Ordering::AcqRel);
+
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+
+                drop(prev);
+            }
+            WAKING => {
+                // the waker is currently being woken, so wake the new waker directly
+                waker.wake_by_ref();
+                hint::spin_loop();
+            }
+            state => debug_assert!(state == REGISTERING || state == REGISTERING | WAKING),
+        }
+    }
+
+    /// Removes the registered waker from the slot, if there is one
+    pub fn take(&self) -> Option<Waker> {
+        match self.state.fetch_or(WAKING, Ordering::AcqRel) {
+            WAITING => {
+                // SAFETY: the WAKING bit gives this thread exclusive access to the slot
+                let waker = unsafe { (*self.waker.get()).take() };
+                self.state.fetch_and(!WAKING, Ordering::Release);
+
+                waker
+            }
+            _ => None,
+        }
+    }
+
+    /// Wakes the registered waker, if there is one
+    pub fn wake(&self) {
+        if let Some(waker) = self.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Default for AtomicWaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Send for AtomicWaker {}
+unsafe impl Sync for AtomicWaker {}
@@ -0,0 +1,205 @@
+use crate::spsc::park::Parker;
+use phonic_signal::{
+    utils::TeeBuf, BlockingSignal, FiniteSignal, IndexedSignal, PhonicError, PhonicResult, Signal,
+    SignalReader, SignalSpec, WakingSignal,
+};
+use std::{
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex, MutexGuard,
+    },
+};
+
+/// A reader of a signal that is shared with readers on other threads. See
+/// `phonic_signal::utils::Tee`.
+pub struct SyncTee<T: Signal> {
+    shared: Arc<SyncTeeBuf<T>>,
+    id: usize,
+    spec: SignalSpec,
+    parker: Parker,
+}
+
+struct SyncTeeBuf<T: Signal> {
+    buf: Mutex<TeeBuf<T>>,
+    advanced: Condvar,
+
+    /// whether a reader is parked until the shared signal wakes it. It is only accessed while
+    /// `buf` is locked.
+    is_blocking: AtomicBool,
+}
+
+impl<T: Signal> SyncTee<T> {
+    /// Creates `n_readers` readers of the signal. Up to `capacity` frames are buffered for
+    /// readers that fall behind.
+    pub fn new(inner: T, n_readers: usize, capacity: usize) -> PhonicResult<Vec<Self>> {
+        let spec = *inner.spec();
+        let shared = Arc::new(SyncTeeBuf {
+            buf: Mutex::new(TeeBuf::new(inner, n_readers, capacity)?),
+            advanced: Condvar::new(),
+            is_blocking: AtomicBool::new(false),
+        });
+
+        let readers = (0..n_readers)
+            .map(|id| Self {
+                shared: shared.clone(),
+                id,
+                spec,
+                parker: Parker::default(),
+            })
+            .collect();
+
+        Ok(readers)
+    }
+
+    /// The number of frames this reader is behind the reader that is furthest ahead
+    pub fn lag(&self) -> u64 {
+        self.lock().lag(self.id)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, TeeBuf<T>> {
+        self.shared
+            .buf
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<T: Signal> Drop for SyncTee<T> {
+    fn drop(&mut self) {
+        self.lock().release(self.id);
+        self.shared.advanced.notify_all();
+    }
+}
+
+impl<T: Signal> Signal for SyncTee<T> {
+    type Sample = T::Sample;
+
+    fn spec(&self) -> &SignalSpec {
+        &self.spec
+    }
+}
+
+/// Blocking waits until another reader catches up if the buffer is full. If this reader has read
+/// every buffered frame, a waker is registered with the shared signal and the thread is parked
+/// without holding the lock, so the other readers can keep reading buffered frames. Only one reader
+/// is parked at a time, the others wait until it has been woken.
+impl<T: SignalReader + WakingSignal> BlockingSignal for SyncTee<T> {
+    fn block(&self) {
+        let is_blocked = |buf: &mut TeeBuf<T>| {
+            buf.is_waiting_for_readers(self.id)
+                || buf.lag(self.id) == 0 && self.shared.is_blocking.load(Ordering::Relaxed)
+        };
+
+        let mut guard = self.lock();
+        if is_blocked(&mut guard) {
+            let _guard = self
+                .shared
+                .advanced
+                .wait_while(guard, is_blocked)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            return;
+        } else if guard.lag(self.id) > 0 {
+            return;
+        }
+
+        // the waker is registered before the shared signal is read again, so progress made before
+        // the thread is parked is not missed
+        guard.as_inner().register_waker(&self.parker.waker());
+        if let Err(PhonicError::NotReady { .. }) = guard.fill(usize::MAX) {
+            self.shared.is_blocking.store(true, Ordering::Relaxed);
+            drop(guard);
+
+            self.parker.park();
+            guard = self.lock();
+            self.shared.is_blocking.store(false, Ordering::Relaxed);
+        }
+
+        drop(guard);
+        self.shared.advanced.notify_all();
+    }
+}
+
+impl<T: IndexedSignal> IndexedSignal for SyncTee<T> {
+    fn pos(&self) -> u64 {
+        self.lock().pos(self.id)
+    }
+}
+
+impl<T: FiniteSignal> FiniteSignal for SyncTee<T> {
+    fn len(&self) -> u64 {
+        self.lock().as_inner().len()
+    }
+}
+
+impl<T: SignalReader> SignalReader for SyncTee<T> {
+    fn read(&mut self, buf: &mut [MaybeUninit<Self::Sample>]) -> PhonicResult<usize> {
+        // readers that are waiting for this one are woken once it reads frames or an error
+        let result = self.lock().read(self.id, buf);
+        if !matches!(result, Ok(0) | Err(PhonicError::NotReady { .. })) {
+            self.shared.advanced.notify_all();
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{spsc::SpscSignal, SyncTee};
+    use phonic_signal::{
+        utils::{Cursor, SignalUtilsExt},
+        SignalExt, SignalSpec, SignalWriter,
+    };
+    use std::{mem::MaybeUninit, sync::mpsc, thread, time::Duration};
+
+    #[test]
+    fn readers_on_other_threads_receive_every_sample() {
+        let samples = (0..1024).map(|i| i as f32).collect::<Vec<_>>();
+        let signal = Cursor::new(SignalSpec::stereo(48000), samples.clone()).polled();
+        let readers = SyncTee::new(signal, 3, 16).unwrap();
+
+        let handles = readers
+            .into_iter()
+            .map(|mut reader| thread::spawn(move || reader.read_all_into::<Vec<f32>>().unwrap()))
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), samples);
+        }
+    }
+
+    #[test]
+    fn readers_that_are_behind_are_not_stalled_by_a_blocked_reader() {
+        let spec = SignalSpec::mono(48000);
+        let (mut producer, consumer) = SpscSignal::new(spec, vec![0u32; 16]);
+        let mut readers = SyncTee::new(consumer, 2, 16).unwrap();
+        let mut behind = readers.pop().unwrap();
+        let mut ahead = readers.pop().unwrap();
+
+        producer.write(&[1, 2, 3, 4]).unwrap();
+        let mut buf = [MaybeUninit::uninit(); 4];
+        assert_eq!(ahead.read_init(&mut buf).unwrap(), [1, 2, 3, 4]);
+
+        // the reader that is ahead blocks on the empty ring until more samples are written
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let mut buf = [MaybeUninit::uninit(); 2];
+            sender.send(()).unwrap();
+            ahead.read_exact(&mut buf).unwrap();
+            buf.map(|sample| unsafe { sample.assume_init() })
+        });
+
+        receiver.recv().unwrap();
+        thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(behind.read_init(&mut buf).unwrap(), [1, 2, 3, 4]);
+        assert_eq!(behind.lag(), 0);
+        assert!(!handle.is_finished());
+
+        producer.write(&[5, 6]).unwrap();
+        assert_eq!(handle.join().unwrap(), [5, 6]);
+        assert_eq!(behind.read_init(&mut buf).unwrap(), [5, 6]);
+    }
+}
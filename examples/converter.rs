@@ -1,7 +1,7 @@
 use phonic::{
     dsp::ops::TaggedSignalExt,
     io::{
-        dynamic::{DynFormatConstructor, DynStream, FormatIdentifier},
+        dynamic::{DynFormatConstructor, DynStream, FormatIdentifier, KnownFormat, Probe},
         utils::FormatUtilsExt,
         StreamSpec,
     },
@@ -15,13 +15,17 @@ use std::{
 
 fn main() -> PhonicResult<()> {
     let src_path = Path::new("sine.wav");
-    let src_file = File::open(src_path)?;
+    let mut src_file = File::open(src_path)?;
 
-    let src_fmt = FormatIdentifier::try_from(src_path)?
-        .known_format()
-        .ok_or(PhonicError::unsupported())?
-        .read_index(src_file)?
-        .finalize_on_drop();
+    let src_fmt = match FormatIdentifier::try_from(src_path)
+        .ok()
+        .and_then(FormatIdentifier::known_format)
+    {
+        Some(format) => format,
+        None => KnownFormat::try_from(&Probe::read(&mut src_file)?)?,
+    };
+
+    let src_fmt = src_fmt.read_index(src_file)?.finalize_on_drop();
 
     let decoder = src_fmt.into_primary_stream()?.into_decoder()?;
     let spec = StreamSpec::builder()
@@ -43,6 +43,6 @@ where
     let output = <CpalSignal>::new().build_output(&device, consumer);
     output.unwrap().play().unwrap();
 
-    (&mut producer).polled().copy_all_buffered(signal)?;
-    producer.polled().flush_blocking()
+    (&mut producer).copy_all_buffered(signal)?;
+    producer.flush_blocking()
 }
@@ -15,5 +15,8 @@ pub use phonic_io as io;
 #[cfg(feature = "sync")]
 pub use phonic_sync as sync;
 
+#[cfg(feature = "graph")]
+pub use phonic_graph as graph;
+
 #[cfg(feature = "cpal")]
 pub use phonic_cpal as cpal;